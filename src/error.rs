@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -48,9 +49,11 @@ pub enum EthereumError {
     EventParsing(String),
 
     #[error("Block error: {0}")]
+    #[allow(dead_code)]
     Block(String),
 
     #[error("Transaction error: {0}")]
+    #[allow(dead_code)]
     Transaction(String),
 
     #[error("WebSocket error: {0}")]
@@ -120,14 +123,28 @@ pub enum SubgraphError {
     #[error("Parsing error: {0}")]
     Parsing(String),
 
+    /// Body ended mid-JSON, e.g. the connection was reset while reading it
+    #[error("Truncated response: {0}")]
+    TruncatedResponse(String),
+
     #[error("Timeout error: {0}")]
     Timeout(String),
 
     #[error("Rate limit exceeded")]
-    RateLimit,
+    RateLimit {
+        /// Wait requested by the server's `Retry-After` header, if any
+        retry_after: Option<Duration>,
+    },
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    #[error("Subgraph indexing error: {0}")]
+    IndexingError(String),
+
+    /// Indexed block is further behind chain head than `subgraph.max_block_lag`
+    #[error("Subgraph lagging: {0}")]
+    Lagging(String),
 }
 
 #[derive(Error, Debug)]
@@ -167,14 +184,17 @@ pub type Result<T> = std::result::Result<T, DAppError>;
 
 // Helper methods to construct specific error variants
 impl SolanaError {
+    #[allow(dead_code)]
     pub fn rpc_error(message: impl Into<String>) -> Self {
         SolanaError::Rpc(message.into())
     }
 
+    #[allow(dead_code)]
     pub fn program_error(message: impl Into<String>) -> Self {
         SolanaError::Program(message.into())
     }
 
+    #[allow(dead_code)]
     pub fn transaction_error(message: impl Into<String>) -> Self {
         SolanaError::Transaction(message.into())
     }
@@ -206,6 +226,54 @@ impl RedisError {
     }
 }
 
+impl DAppError {
+    /// Whether retrying the failed operation may succeed
+    ///
+    /// Configuration, validation and deterministic subgraph response errors
+    /// are fatal; everything else is treated as transient.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DAppError::Config(_) | DAppError::Validation(_) => false,
+            DAppError::Subgraph(error) => error.is_retryable(),
+            _ => true,
+        }
+    }
+
+    /// How long the server asked us to wait before trying again
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            DAppError::Subgraph(SubgraphError::RateLimit { retry_after }) => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl SubgraphError {
+    /// Whether the same query may succeed if sent again
+    ///
+    /// Truncated bodies are transient; well-formed responses that fail to
+    /// parse or have the wrong shape will fail the same way on every retry.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            SubgraphError::Parsing(_) | SubgraphError::InvalidResponse(_)
+        )
+    }
+
+    /// Map to the `DAppError` variant callers should react to
+    ///
+    /// Timeouts and transport failures get their dedicated variants;
+    /// everything else remains a subgraph error. Rate limits stay subgraph
+    /// errors so the `Retry-After` delay is kept.
+    pub fn into_dapp_error(self) -> DAppError {
+        match self {
+            SubgraphError::Timeout(message) => DAppError::Timeout(message),
+            SubgraphError::Http(message) => DAppError::Network(NetworkError::Http(message)),
+            other => DAppError::Subgraph(other),
+        }
+    }
+}
+
 impl NetworkError {
     pub fn websocket_error(message: impl Into<String>) -> Self {
         NetworkError::WebSocket(message.into())
@@ -221,7 +289,6 @@ impl NetworkError {
 }
 
 impl SerializationError {
-    #[allow(dead_code)]
     pub fn borsh_error(message: impl Into<String>) -> Self {
         SerializationError::Borsh(message.into())
     }
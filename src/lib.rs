@@ -8,6 +8,7 @@ pub mod error;
 pub mod model;
 pub mod redis;
 pub mod service;
+pub mod sink;
 pub mod subgraph;
 pub mod telemetry;
 pub mod utils;
@@ -18,5 +19,7 @@ pub use error::{DAppError, Result};
 pub use model::{SwapEvent, SwapEventBuilder, TokenInfo, UniswapVersion};
 pub use redis::RedisPublisher;
 pub use service::swap_collector::SwapEventCollector;
+pub use sink::EventSink;
 pub use subgraph::SubgraphClient;
+pub use telemetry::exporter::MetricsExporter;
 pub use telemetry::MetricsCollector;
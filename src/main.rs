@@ -3,32 +3,53 @@ mod error;
 mod model;
 mod redis;
 mod service;
+mod sink;
 mod subgraph;
 mod telemetry;
 mod utils;
 
-use crate::config::AppConfig;
+use crate::config::{AppConfig, LIVE_CONFIG_FIELDS};
 use crate::error::Result;
 use crate::redis::publisher::RedisPublisher;
+use crate::service::backfill::BackfillRequest;
 use crate::service::swap_collector::SwapEventCollector;
+use crate::sink::fan_out::FanOutSink;
+use crate::sink::file::FileSink;
+use crate::sink::webhook::WebhookSink;
+use crate::sink::EventSink;
 use crate::subgraph::SubgraphClient;
 use crate::telemetry::metrics::MetricsCollector;
 
-use tracing::{error, info};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, Registry};
+
+/// Base configuration file, watched when `application.watch_config` is set
+const CONFIG_FILE: &str = "config/config.toml";
+
+/// Swaps the log filter at runtime
+type LogFilterHandle = reload::Handle<EnvFilter, Registry>;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     info!("Starting Uniswap Relay DApp (Subgraph-only)...");
 
     // Load configuration
-    let config = AppConfig::load().map_err(|e| {
+    let mut config = AppConfig::load().map_err(|e| {
         error!("Failed to load configuration: {}", e);
         crate::error::DAppError::Config(e.to_string())
     })?;
 
+    // Command-line overrides
+    let backfill = BackfillRequest::from_args(std::env::args().skip(1)).transpose()?;
+    if let Some(path) = capture_to_file_arg(std::env::args().skip(1)) {
+        config.application.capture_to_file = Some(path);
+    }
+
     // Initialize logging
-    init_logging(&config)?;
+    let log_filter = init_logging(&config)?;
 
     // Validate configuration
     if let Err(e) = config.validate() {
@@ -42,13 +63,25 @@ async fn main() -> Result<()> {
     }
 
     info!("Configuration loaded successfully");
+    info!(
+        "Running {} v{}",
+        config.application.name, config.application.version
+    );
+
+    // Historical ingestion runs once and exits without starting the live loop
+    if let Some(request) = backfill {
+        return run_backfill(config, &request).await;
+    }
 
     // Initialize subgraph client
-    let subgraph_client = SubgraphClient::new(config.clone());
+    let subgraph_client = SubgraphClient::new(config.clone())?;
 
     // Test subgraph connectivity
-    subgraph_client.test_connectivity().await?;
-    info!("Subgraph connectivity verified");
+    let sync_status = subgraph_client.test_connectivity().await?;
+    info!(
+        "Subgraph connectivity verified (V2 block {:?}, V3 block {:?})",
+        sync_status.v2_block, sync_status.v3_block
+    );
 
     // Initialize Redis publisher
     let redis_publisher = RedisPublisher::new(config.clone()).await?;
@@ -57,9 +90,24 @@ async fn main() -> Result<()> {
     redis_publisher.test_connection().await?;
     info!("Redis connection established");
 
+    // Keep the liveness key fresh for external watchdogs
+    let liveness_refresher = redis_publisher.start_liveness_refresher();
+
     // Initialize metrics collector
     let metrics_collector = MetricsCollector::new(config.clone());
 
+    // Collect and export metric snapshots (no-op unless metrics are enabled)
+    metrics_collector.start_collection().await;
+
+    // Track how many subscribers the channel has (no-op unless metrics are enabled)
+    let subscriber_gauge = redis_publisher.start_subscriber_gauge(metrics_collector.clone());
+
+    // Expose counters for Prometheus to scrape
+    let (metrics_shutdown, metrics_shutdown_rx) = tokio::sync::watch::channel(false);
+    if config.monitoring.enable_metrics {
+        metrics_collector.serve_metrics(metrics_shutdown_rx).await?;
+    }
+
     // Start health checks if enabled
     if config.monitoring.enable_health_checks {
         let health_collector = metrics_collector.clone();
@@ -68,44 +116,126 @@ async fn main() -> Result<()> {
         });
     }
 
-    // Initialize swap event collector
-    let mut swap_collector = SwapEventCollector::new(
-        config.clone(),
-        subgraph_client,
-        redis_publisher,
-        metrics_collector,
-    );
+    // Serve /health (and /config, if exposed) for orchestrators and operators
+    let health_server = metrics_collector.serve_health().await?;
+
+    // Additional outputs configured under [file_sink] and [webhook_sink]
+    let mut extra_sinks: Vec<Arc<dyn EventSink>> = Vec::new();
+    if let Some(file_sink) = FileSink::from_config(&config)? {
+        info!("Also writing events to {:?}", config.file_sink.path);
+        extra_sinks.push(Arc::new(file_sink));
+    }
+    if let Some(webhook_sink) = WebhookSink::from_config(&config)? {
+        info!("Also posting events to the configured webhook");
+        extra_sinks.push(Arc::new(webhook_sink));
+    }
+
+    // Initialize swap event collector, fanning out to Redis and any extra sinks
+    let mut swap_collector = if extra_sinks.is_empty() {
+        SwapEventCollector::new(
+            config.clone(),
+            subgraph_client,
+            redis_publisher,
+            metrics_collector,
+        )?
+    } else {
+        let mut targets: Vec<Arc<dyn EventSink>> = vec![Arc::new(redis_publisher)];
+        targets.extend(extra_sinks);
+        SwapEventCollector::builder(config.clone())
+            .with_subgraph_client(subgraph_client)
+            .with_sink(Arc::new(FanOutSink::new(&config, targets)))
+            .with_metrics_collector(metrics_collector)
+            .build()?
+    };
+
+    // Re-apply live-safe settings when the config file changes
+    let config_watcher = if config.application.watch_config {
+        info!(
+            "Watching {} for changes to {}",
+            CONFIG_FILE,
+            LIVE_CONFIG_FIELDS.join(", ")
+        );
+        let (reload_tx, reload_rx) = mpsc::channel(4);
+        let (collector_tx, collector_rx) = mpsc::channel(4);
+        swap_collector = swap_collector.with_config_updates(collector_rx);
+        tokio::spawn(forward_reloaded_config(reload_rx, collector_tx, log_filter));
+        Some(AppConfig::watch(CONFIG_FILE, reload_tx))
+    } else {
+        None
+    };
 
     // Start collecting events from subgraphs
     swap_collector.start_collecting().await?;
 
     info!("Uniswap Relay DApp started successfully");
-    info!(
-        "Environment: {}",
-        if config.is_production() {
-            "PRODUCTION"
-        } else {
-            "DEVELOPMENT"
-        }
-    );
+    info!("Environment: {}", config.application.environment);
     info!("Configuration: {}", swap_collector.get_config_summary());
 
-    // Wait for shutdown signal
-    wait_for_shutdown().await;
+    // One-shot runs have already published everything; daemons wait for a signal
+    if !config.application.one_shot {
+        let max_lifetime = match config.application.max_process_lifetime_seconds {
+            0 => None,
+            seconds => Some(tokio::time::Duration::from_secs(seconds)),
+        };
+        wait_for_shutdown(max_lifetime).await;
+    }
 
     info!("Shutting down Uniswap Relay DApp...");
 
     // Graceful shutdown
-    swap_collector.shutdown().await?;
+    handle_shutdown(&mut swap_collector).await?;
+    if let Some(watcher) = config_watcher {
+        watcher.abort();
+    }
+    if let Some(refresher) = liveness_refresher {
+        refresher.abort();
+    }
+    if let Some(gauge) = subscriber_gauge {
+        gauge.abort();
+    }
+    if let Some(server) = health_server {
+        server.abort();
+    }
+    let _ = metrics_shutdown.send(true);
 
     info!("Uniswap Relay DApp shutdown complete");
     Ok(())
 }
 
+/// Publish the swaps of a historical range to Redis, then print a summary
+async fn run_backfill(config: AppConfig, request: &BackfillRequest) -> Result<()> {
+    let subgraph_client = SubgraphClient::new(config.clone())?;
+    let redis_publisher = RedisPublisher::new(config.clone()).await?;
+    redis_publisher.test_connection().await?;
+    let metrics_collector = MetricsCollector::new(config.clone());
+    let swap_collector =
+        SwapEventCollector::new(config, subgraph_client, redis_publisher, metrics_collector)?;
+
+    let summary = swap_collector.backfill(request).await?;
+    println!("Backfill complete: {}", summary);
+    Ok(())
+}
+
+/// Path given with `--capture-to-file <path>` or `--capture-to-file=<path>`
+fn capture_to_file_arg(mut args: impl Iterator<Item = String>) -> Option<String> {
+    while let Some(arg) = args.next() {
+        if arg == "--capture-to-file" {
+            return args.next();
+        }
+        if let Some(path) = arg.strip_prefix("--capture-to-file=") {
+            return Some(path.to_string());
+        }
+    }
+    None
+}
+
 /// Initialize logging with structured JSON output
-fn init_logging(config: &AppConfig) -> Result<()> {
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+///
+/// Returns a handle for changing the log filter later on.
+fn init_logging(config: &AppConfig) -> Result<LogFilterHandle> {
+    let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| config.application.log_level.clone().into());
+    let (env_filter, log_filter) = reload::Layer::new(env_filter);
 
     let formatting_layer = tracing_subscriber::fmt::layer()
         .with_target(false)
@@ -124,11 +254,48 @@ fn init_logging(config: &AppConfig) -> Result<()> {
         "Logging initialized with level: {}, structured: {}",
         config.application.log_level, config.monitoring.enable_structured_logging
     );
-    Ok(())
+    Ok(log_filter)
 }
 
-/// Wait for shutdown signal (Ctrl+C)
-async fn wait_for_shutdown() {
+/// Apply each reloaded log level, then pass the config on to the collector
+///
+/// A `RUST_LOG` set at startup keeps precedence over the configured level.
+async fn forward_reloaded_config(
+    mut reloaded: mpsc::Receiver<AppConfig>,
+    collector: mpsc::Sender<AppConfig>,
+    log_filter: LogFilterHandle,
+) {
+    let log_level_pinned = std::env::var_os("RUST_LOG").is_some();
+    while let Some(config) = reloaded.recv().await {
+        if !log_level_pinned {
+            match EnvFilter::try_new(&config.application.log_level) {
+                Ok(filter) => {
+                    if let Err(e) = log_filter.reload(filter) {
+                        warn!("Failed to apply reloaded log level: {}", e);
+                    }
+                }
+                Err(e) => warn!(
+                    "Ignoring invalid log level {:?}: {}",
+                    config.application.log_level, e
+                ),
+            }
+        }
+        if collector.send(config).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Completes once the process has run for `max_lifetime`; never if `None`
+async fn lifetime_elapsed(max_lifetime: Option<tokio::time::Duration>) {
+    match max_lifetime {
+        Some(max_lifetime) => tokio::time::sleep(max_lifetime).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Wait for shutdown signal (Ctrl+C, SIGTERM) or the end of the process lifetime
+async fn wait_for_shutdown(max_lifetime: Option<tokio::time::Duration>) {
     let ctrl_c = async {
         tokio::signal::ctrl_c()
             .await
@@ -153,18 +320,23 @@ async fn wait_for_shutdown() {
         _ = terminate => {
             info!("Received SIGTERM, initiating shutdown...");
         }
+        _ = lifetime_elapsed(max_lifetime) => {
+            info!("Maximum process lifetime reached, initiating clean shutdown for restart...");
+        }
     }
 }
 
 /// Handle graceful shutdown
-#[allow(dead_code)]
-async fn handle_shutdown() {
+///
+/// Stops new polls and lets in-flight batches finish publishing, bounded by
+/// `application.shutdown_timeout_seconds`, before flushing the sink.
+async fn handle_shutdown(swap_collector: &mut SwapEventCollector) -> Result<()> {
     info!("Initiating graceful shutdown...");
 
-    // Give components time to finish current operations
-    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    swap_collector.shutdown().await?;
 
     info!("Graceful shutdown complete");
+    Ok(())
 }
 
 #[cfg(test)]
@@ -180,6 +352,38 @@ mod tests {
         assert!(!config.redis.url.is_empty());
     }
 
+    #[test]
+    fn test_capture_to_file_arg() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            capture_to_file_arg(args(&["--capture-to-file", "events.ndjson"]).into_iter()),
+            Some("events.ndjson".to_string())
+        );
+        assert_eq!(
+            capture_to_file_arg(args(&["--capture-to-file=events.ndjson"]).into_iter()),
+            Some("events.ndjson".to_string())
+        );
+        assert_eq!(capture_to_file_arg(args(&["--verbose"]).into_iter()), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_fires_after_max_lifetime() {
+        let lifetime = tokio::time::Duration::from_secs(5);
+        let started = tokio::time::Instant::now();
+
+        wait_for_shutdown(Some(lifetime)).await;
+        assert!(started.elapsed() >= lifetime);
+
+        // Without a lifetime only a signal ends the wait
+        let unlimited = tokio::time::timeout(
+            tokio::time::Duration::from_secs(86_400),
+            wait_for_shutdown(None),
+        )
+        .await;
+        assert!(unlimited.is_err());
+    }
+
     #[tokio::test]
     async fn test_config_validation() {
         let config = AppConfig::default();
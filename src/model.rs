@@ -1,68 +1,261 @@
+use crate::error::SerializationError;
+use crate::utils::address::normalize_address;
+use borsh::{BorshDeserialize, BorshSerialize};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use tracing::warn;
 
 /// Represents a normalized Uniswap swap event
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Borsh encoding follows field order; timestamps are unix milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct SwapEvent {
     pub id: String,
     pub version: UniswapVersion,
+    #[borsh(
+        serialize_with = "unix_millis::serialize",
+        deserialize_with = "unix_millis::deserialize"
+    )]
     pub timestamp: DateTime<Utc>,
     pub block_number: u64,
+    /// Position of the swap's log within its block, as reported by the subgraph
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_index: Option<u32>,
     pub transaction_hash: String,
     pub pool_address: String,
     pub token_in: TokenInfo,
     pub token_out: TokenInfo,
     pub amount_in: String,
     pub amount_out: String,
+    /// `amount_in` adjusted by the token decimals, set by `normalize_amounts`
+    /// (e.g. for `amount_format = "both"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_in_decimal: Option<String>,
+    /// `amount_out` adjusted by the token decimals, set by `normalize_amounts`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_out_decimal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub amount_in_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub amount_out_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_amount: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_usd: Option<f64>,
     pub user_address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gas_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gas_price: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gas_cost_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pool_info: Option<PoolInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub enriched_data: Option<EnrichedData>,
+    /// Buy or sell of `application.reference_token`, when configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<Direction>,
+    /// V2 pair reserves captured with the swap
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub v2_reserves: Option<V2Reserves>,
+    /// Free-form labels, e.g. added by a transform script
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// Reserves of a V2 pair, oriented like the swap (token in / token out)
+///
+/// Values are decimal-adjusted token amounts as reported by the V2 subgraph.
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq)]
+pub struct V2Reserves {
+    pub reserve_in: String,
+    pub reserve_out: String,
+}
+
+/// Aggregate of the swaps observed in a single block
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlockSummary {
+    pub block: u64,
+    pub swap_count: u64,
+    pub total_volume_usd: f64,
+    pub pools_touched: Vec<String>,
+}
+
+/// Open/high/low/close of a pool's execution prices over one interval
+///
+/// Prices are quoted in the pool's second token per unit of its first, with
+/// tokens ordered by lowercase address so both swap directions share a scale.
+/// `volume` is the amount of the first token traded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OhlcCandle {
+    pub pool: String,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub count: u64,
+    pub interval_start: DateTime<Utc>,
+}
+
+/// Raw swap that failed to parse, kept for inspection and reprocessing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuarantinedSwap {
+    /// Source the swap was fetched from, e.g. `uniswap_v2`
+    pub source: String,
+    pub reason: String,
+    /// The swap exactly as the subgraph returned it
+    pub payload: serde_json::Value,
+    pub quarantined_at: DateTime<Utc>,
+}
+
+/// Version of the published event schema, bumped on breaking changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Non-event message published on the event channel
+///
+/// Serialized with a `type` tag so subscribers can tell control messages
+/// apart from swap events, which have no such field.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlMessage {
+    /// Sent once on startup so subscribers can configure themselves
+    Hello {
+        schema_version: u32,
+        sources: Vec<String>,
+        chain_ids: Vec<u64>,
+        serialization_format: String,
+    },
+}
+
+/// Which event field partitioned sinks route on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PartitionKeyStrategy {
+    /// Pool (pair) contract address
+    Pool,
+    /// Unordered token pair, so both swap directions share a key
+    Pair,
+    /// Address of the token sold
+    TokenIn,
+    /// Address of the swapper
+    User,
+}
+
+/// How token amounts are represented in published events
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AmountFormat {
+    /// Amounts as collected, in the token's smallest unit
+    #[default]
+    Raw,
+    /// `amount_in`/`amount_out` replaced by their decimal-adjusted values
+    Decimal,
+    /// Raw amounts plus `amount_in_decimal`/`amount_out_decimal`
+    Both,
+}
+
+/// Wire format of published swap events
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SerializationFormat {
+    /// One JSON object per event
+    #[default]
+    Json,
+    /// Borsh-encoded `SwapEvent`, for bandwidth-sensitive consumers
+    Borsh,
+}
+
+impl SerializationFormat {
+    /// Name announced in the `hello` control message
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SerializationFormat::Json => "json",
+            SerializationFormat::Borsh => "borsh",
+        }
+    }
+}
+
+/// Side of a swap relative to a reference token
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, BorshSerialize, BorshDeserialize, PartialEq, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// The reference token was bought (it is `token_out`)
+    Buy,
+    /// The reference token was sold (it is `token_in`)
+    Sell,
+    /// Neither side of the swap is the reference token
+    NotInvolved,
 }
 
 /// Uniswap version identifier
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+///
+/// Ordered by declaration, which is the source priority for
+/// `redis.order_window_ms`.
+#[derive(
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
 pub enum UniswapVersion {
     V2,
     V3,
 }
 
 /// Token information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct TokenInfo {
     pub address: String,
     pub symbol: String,
     pub name: String,
     pub decimals: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub logo_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub price_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub market_cap: Option<f64>,
 }
 
 /// Pool information from subgraphs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct PoolInfo {
     pub address: String,
     pub token0: String,
     pub token1: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_tier: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub liquidity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub volume_24h: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fees_24h: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub apy: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[borsh(
+        serialize_with = "unix_millis::serialize_option",
+        deserialize_with = "unix_millis::deserialize_option"
+    )]
     pub created_at: Option<DateTime<Utc>>,
 }
 
 /// Enriched data from additional sources
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct EnrichedData {
     pub token_metadata: HashMap<String, TokenMetadata>,
     pub market_data: Option<MarketData>,
@@ -70,7 +263,7 @@ pub struct EnrichedData {
 }
 
 /// Token metadata from subgraphs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct TokenMetadata {
     pub total_supply: Option<String>,
     pub circulating_supply: Option<String>,
@@ -80,7 +273,7 @@ pub struct TokenMetadata {
 }
 
 /// Market data information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct MarketData {
     pub price_change_24h: Option<f64>,
     pub price_change_7d: Option<f64>,
@@ -90,7 +283,7 @@ pub struct MarketData {
 }
 
 /// Risk metrics for the swap
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
 pub struct RiskMetrics {
     pub impermanent_loss_risk: Option<f64>,
     pub volatility_score: Option<f64>,
@@ -119,19 +312,39 @@ pub struct GraphQLLocation {
 }
 
 /// Swap event from Uniswap V2 subgraph
+///
+/// Matches the collection query. Fields the parser relies on are required,
+/// so schema drift fails deserialization instead of yielding zeros; the rest
+/// default when absent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UniswapV2SwapEvent {
     pub id: String,
+    #[serde(deserialize_with = "graphql_scalar::string")]
     pub timestamp: String,
     pub pair: GraphQLPair,
     pub sender: String,
+    #[serde(deserialize_with = "graphql_scalar::string")]
     pub amount0_in: String,
+    #[serde(default = "zero_amount", deserialize_with = "graphql_scalar::string")]
     pub amount1_in: String,
+    #[serde(default = "zero_amount", deserialize_with = "graphql_scalar::string")]
     pub amount0_out: String,
+    #[serde(deserialize_with = "graphql_scalar::string")]
     pub amount1_out: String,
+    #[serde(default)]
     pub to: String,
-    pub log_index: u32,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub log_index: Option<u32>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub amount_usd: Option<String>,
+    #[serde(default)]
+    pub transaction: Option<GraphQLTransaction>,
+    #[serde(default)]
+    pub block: Option<GraphQLBlock>,
+}
+
+fn zero_amount() -> String {
+    "0".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -139,13 +352,19 @@ pub struct GraphQLPair {
     pub id: String,
     pub token0: GraphQLToken,
     pub token1: GraphQLToken,
-    pub reserve0: String,
-    pub reserve1: String,
-    pub total_supply: String,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
+    pub reserve0: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
+    pub reserve1: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
+    pub total_supply: Option<String>,
     pub reserve_usd: Option<String>,
     pub tracked_reserve_eth: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub token0_price: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub token1_price: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub volume_usd: Option<String>,
     pub untracked_volume_usd: Option<String>,
     pub tx_count: Option<String>,
@@ -154,22 +373,41 @@ pub struct GraphQLPair {
 }
 
 /// Swap event from Uniswap V3 subgraph
+///
+/// Like `UniswapV2SwapEvent`, only the fields the parser relies on are required.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UniswapV3SwapEvent {
     pub id: String,
+    #[serde(deserialize_with = "graphql_scalar::string")]
     pub timestamp: String,
     pub pool: GraphQLV3Pool,
+    #[serde(default)]
     pub token0: String,
+    #[serde(default)]
     pub token1: String,
     pub sender: String,
+    #[serde(default)]
     pub recipient: String,
+    #[serde(default)]
     pub origin: String,
+    #[serde(deserialize_with = "graphql_scalar::string")]
     pub amount0: String,
+    #[serde(deserialize_with = "graphql_scalar::string")]
     pub amount1: String,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub amount_usd: Option<String>,
-    pub sqrt_price_x96: String,
-    pub liquidity: String,
-    pub tick: i32,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
+    pub sqrt_price_x96: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
+    pub liquidity: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub tick: Option<i32>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub log_index: Option<u32>,
+    #[serde(default)]
+    pub transaction: Option<GraphQLTransaction>,
+    #[serde(default)]
+    pub block: Option<GraphQLBlock>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -177,13 +415,20 @@ pub struct GraphQLV3Pool {
     pub id: String,
     pub token0: GraphQLToken,
     pub token1: GraphQLToken,
-    pub fee_tier: u32,
-    pub liquidity: String,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub fee_tier: Option<u32>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
+    pub liquidity: Option<String>,
     pub sqrt_price: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub token0_price: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub token1_price: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub volume_usd: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub fees_usd: Option<String>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_string")]
     pub total_value_locked_usd: Option<String>,
 }
 
@@ -191,15 +436,63 @@ pub struct GraphQLV3Pool {
 pub struct GraphQLToken {
     pub id: String,
     pub symbol: String,
+    #[serde(default)]
     pub name: String,
+    #[serde(deserialize_with = "graphql_scalar::integer")]
     pub decimals: u8,
     pub total_supply: Option<String>,
     pub volume: Option<String>,
     pub volume_usd: Option<String>,
 }
 
+/// Transaction a swap was included in
+///
+/// V2 subgraphs expose the block through the swap's transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphQLTransaction {
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub block_number: Option<u64>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub timestamp: Option<i64>,
+}
+
+/// Block a swap was included in, as V3 subgraphs expose it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphQLBlock {
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub number: Option<u64>,
+    #[serde(default, deserialize_with = "graphql_scalar::opt_integer")]
+    pub timestamp: Option<i64>,
+}
+
+/// Block number and time of a subgraph swap
+///
+/// Takes whichever of the transaction or block the subgraph provided. The
+/// block number is 0 when neither carries one. The time is the swap's own
+/// `timestamp`, falling back to the block's when that is not a valid unix time.
+fn subgraph_block_info(
+    timestamp: &str,
+    transaction: Option<&GraphQLTransaction>,
+    block: Option<&GraphQLBlock>,
+) -> Result<(u64, DateTime<Utc>), String> {
+    let block_number = transaction
+        .and_then(|transaction| transaction.block_number)
+        .or_else(|| block.and_then(|block| block.number))
+        .unwrap_or(0);
+    let timestamp = timestamp
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .or_else(|| transaction.and_then(|transaction| transaction.timestamp))
+        .or_else(|| block.and_then(|block| block.timestamp))
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .ok_or_else(|| format!("Invalid swap timestamp: {:?}", timestamp))?;
+    Ok((block_number, timestamp))
+}
+
 /// Health check status
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct HealthStatus {
     pub status: String,
     pub timestamp: DateTime<Utc>,
@@ -209,6 +502,7 @@ pub struct HealthStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct CheckStatus {
     pub status: String,
     pub message: Option<String>,
@@ -218,6 +512,7 @@ pub struct CheckStatus {
 
 /// Metrics data
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct Metrics {
     pub events_processed_total: u64,
     pub events_processed_rate: f64,
@@ -232,6 +527,115 @@ pub struct Metrics {
 }
 
 impl SwapEvent {
+    /// Stable partition key for this event under `strategy`
+    ///
+    /// Addresses are lowercased so differently-cased sources map to the same key.
+    pub fn partition_key(&self, strategy: PartitionKeyStrategy) -> String {
+        match strategy {
+            PartitionKeyStrategy::Pool => self.pool_address.to_lowercase(),
+            PartitionKeyStrategy::Pair => {
+                let mut tokens = [
+                    self.token_in.address.to_lowercase(),
+                    self.token_out.address.to_lowercase(),
+                ];
+                tokens.sort();
+                tokens.join("-")
+            }
+            PartitionKeyStrategy::TokenIn => self.token_in.address.to_lowercase(),
+            PartitionKeyStrategy::User => self.user_address.to_lowercase(),
+        }
+    }
+
+    /// Whether this swap bought or sold the token at `reference_address`
+    ///
+    /// Addresses are compared case-insensitively. Returns `None` when the
+    /// reference is empty or both sides are the same token, since no side
+    /// can be determined.
+    pub fn direction_vs(&self, reference_address: &str) -> Option<Direction> {
+        if reference_address.is_empty() {
+            return None;
+        }
+
+        let is_reference = |address: &str| address.eq_ignore_ascii_case(reference_address);
+        match (
+            is_reference(&self.token_in.address),
+            is_reference(&self.token_out.address),
+        ) {
+            (false, true) => Some(Direction::Buy),
+            (true, false) => Some(Direction::Sell),
+            (false, false) => Some(Direction::NotInvolved),
+            (true, true) => None,
+        }
+    }
+
+    /// Serialize the event with amounts in `format`
+    ///
+    /// Decimal amounts are only computed for the `decimal` and `both` formats.
+    /// Amounts that are not plain integers (e.g. already decimal-adjusted by
+    /// the subgraph) are left unchanged.
+    pub fn to_json_with_format(&self, format: AmountFormat) -> serde_json::Result<String> {
+        serde_json::to_string(&self.with_amount_format(format))
+    }
+
+    /// Encode the event in `serialization` with amounts in `amount_format`
+    pub fn encode(
+        &self,
+        serialization: SerializationFormat,
+        amount_format: AmountFormat,
+    ) -> crate::error::Result<Vec<u8>> {
+        match serialization {
+            SerializationFormat::Json => self
+                .to_json_with_format(amount_format)
+                .map(String::into_bytes)
+                .map_err(|e| SerializationError::Json(e.to_string()).into()),
+            SerializationFormat::Borsh => {
+                borsh::to_vec(self.with_amount_format(amount_format).as_ref())
+                    .map_err(|e| SerializationError::borsh_error(e.to_string()).into())
+            }
+        }
+    }
+
+    /// The event as published with amounts in `format`
+    fn with_amount_format(&self, format: AmountFormat) -> Cow<'_, SwapEvent> {
+        if format == AmountFormat::Raw {
+            return Cow::Borrowed(self);
+        }
+
+        let mut event = self.clone();
+        event.normalize_amounts();
+        if format == AmountFormat::Decimal {
+            event.amount_in = event.amount_in_decimal.take().unwrap_or_default();
+            event.amount_out = event.amount_out_decimal.take().unwrap_or_default();
+        }
+        Cow::Owned(event)
+    }
+
+    /// Write events to `writer` as newline-delimited JSON
+    ///
+    /// Each event is serialized straight into the writer, so no per-event
+    /// `String` is allocated; pass a `Vec<u8>` to build a batch in one buffer.
+    pub fn serialize_batch_ndjson<'a, W: std::io::Write>(
+        events: impl IntoIterator<Item = &'a SwapEvent>,
+        mut writer: W,
+    ) -> serde_json::Result<()> {
+        for event in events {
+            serde_json::to_writer(&mut writer, event)?;
+            writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+        }
+        Ok(())
+    }
+
+    /// Fill `amount_in_decimal`/`amount_out_decimal` from the raw amounts
+    ///
+    /// The shift by `token_in.decimals`/`token_out.decimals` is done on the
+    /// digit string, so 18-decimal amounts keep full precision. Zero stays
+    /// `0`, 0-decimal tokens keep their integer amount, and amounts that
+    /// already contain a decimal point are copied unchanged.
+    pub fn normalize_amounts(&mut self) {
+        self.amount_in_decimal = Some(normalize_amount(&self.amount_in, self.token_in.decimals));
+        self.amount_out_decimal = Some(normalize_amount(&self.amount_out, self.token_out.decimals));
+    }
+
     #[allow(dead_code)]
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -249,12 +653,15 @@ impl SwapEvent {
             version,
             timestamp: Utc::now(),
             block_number: 0, // Will be set by the collector
+            log_index: None,
             transaction_hash,
             pool_address,
             token_in,
             token_out,
             amount_in,
             amount_out,
+            amount_in_decimal: None,
+            amount_out_decimal: None,
             amount_in_usd: None,
             amount_out_usd: None,
             fee_amount: None,
@@ -265,6 +672,9 @@ impl SwapEvent {
             gas_cost_usd: None,
             pool_info: None,
             enriched_data: None,
+            direction: None,
+            v2_reserves: None,
+            tags: Vec::new(),
         }
     }
 
@@ -316,7 +726,9 @@ impl SwapEvent {
         user_address: String,
     ) -> Result<Self, String> {
         // Parse token information from the V2 event
-        let token_in = if v2_event.amount0_in != "0" {
+        let token0_in = !is_zero_amount(&v2_event.amount0_in);
+        let token0_out = !is_zero_amount(&v2_event.amount0_out);
+        let token_in = if token0_in {
             TokenInfo {
                 address: v2_event.pair.token0.id.clone(),
                 symbol: v2_event.pair.token0.symbol.clone(),
@@ -338,7 +750,7 @@ impl SwapEvent {
             }
         };
 
-        let token_out = if v2_event.amount0_out != "0" {
+        let token_out = if token0_out {
             TokenInfo {
                 address: v2_event.pair.token0.id.clone(),
                 symbol: v2_event.pair.token0.symbol.clone(),
@@ -360,20 +772,26 @@ impl SwapEvent {
             }
         };
 
-        let amount_in = if v2_event.amount0_in != "0" {
+        let amount_in = if token0_in {
             v2_event.amount0_in.clone()
         } else {
             v2_event.amount1_in.clone()
         };
 
-        let amount_out = if v2_event.amount0_out != "0" {
+        let amount_out = if token0_out {
             v2_event.amount0_out.clone()
         } else {
             v2_event.amount1_out.clone()
         };
 
+        let (block_number, timestamp) = subgraph_block_info(
+            &v2_event.timestamp,
+            v2_event.transaction.as_ref(),
+            v2_event.block.as_ref(),
+        )?;
+
         // Use the builder pattern
-        Self::builder()
+        let mut event = Self::builder()
             .version(UniswapVersion::V2)
             .transaction_hash(v2_event.id.clone())
             .pool_address(pool_address)
@@ -382,7 +800,9 @@ impl SwapEvent {
             .amount_in(amount_in)
             .amount_out(amount_out)
             .user_address(user_address)
-            .build()
+            .build()?;
+        event.set_block_info(block_number, timestamp);
+        Ok(event)
     }
 
     /// Create a SwapEvent from Uniswap V3 subgraph data using builder
@@ -392,65 +812,37 @@ impl SwapEvent {
         pool_address: String,
         user_address: String,
     ) -> Result<Self, String> {
-        // Parse token information from the V3 event
-        let token_in = if v3_event.amount0.parse::<f64>().unwrap_or(0.0) < 0.0 {
-            TokenInfo {
-                address: v3_event.pool.token0.id.clone(),
-                symbol: v3_event.pool.token0.symbol.clone(),
-                name: v3_event.pool.token0.name.clone(),
-                decimals: v3_event.pool.token0.decimals,
-                logo_uri: None,
-                price_usd: None,
-                market_cap: None,
-            }
-        } else {
-            TokenInfo {
-                address: v3_event.pool.token1.id.clone(),
-                symbol: v3_event.pool.token1.symbol.clone(),
-                name: v3_event.pool.token1.name.clone(),
-                decimals: v3_event.pool.token1.decimals,
-                logo_uri: None,
-                price_usd: None,
-                market_cap: None,
-            }
-        };
-
-        let token_out = if v3_event.amount0.parse::<f64>().unwrap_or(0.0) > 0.0 {
-            TokenInfo {
-                address: v3_event.pool.token0.id.clone(),
-                symbol: v3_event.pool.token0.symbol.clone(),
-                name: v3_event.pool.token0.name.clone(),
-                decimals: v3_event.pool.token0.decimals,
-                logo_uri: None,
-                price_usd: None,
-                market_cap: None,
-            }
-        } else {
-            TokenInfo {
-                address: v3_event.pool.token1.id.clone(),
-                symbol: v3_event.pool.token1.symbol.clone(),
-                name: v3_event.pool.token1.name.clone(),
-                decimals: v3_event.pool.token1.decimals,
-                logo_uri: None,
-                price_usd: None,
-                market_cap: None,
-            }
+        let token_info = |token: &GraphQLToken| TokenInfo {
+            address: token.id.clone(),
+            symbol: token.symbol.clone(),
+            name: token.name.clone(),
+            decimals: token.decimals,
+            logo_uri: None,
+            price_usd: None,
+            market_cap: None,
         };
-
-        let amount_in = if v3_event.amount0.parse::<f64>().unwrap_or(0.0) < 0.0 {
-            v3_event.amount0.clone()
+        let (token0_in, amount_in, amount_out) =
+            Self::orient_v3_amounts(&v3_event.amount0, &v3_event.amount1);
+        let (token_in, token_out) = if token0_in {
+            (
+                token_info(&v3_event.pool.token0),
+                token_info(&v3_event.pool.token1),
+            )
         } else {
-            v3_event.amount1.clone()
+            (
+                token_info(&v3_event.pool.token1),
+                token_info(&v3_event.pool.token0),
+            )
         };
 
-        let amount_out = if v3_event.amount0.parse::<f64>().unwrap_or(0.0) > 0.0 {
-            v3_event.amount0.clone()
-        } else {
-            v3_event.amount1.clone()
-        };
+        let (block_number, timestamp) = subgraph_block_info(
+            &v3_event.timestamp,
+            v3_event.transaction.as_ref(),
+            v3_event.block.as_ref(),
+        )?;
 
         // Use the builder pattern
-        Self::builder()
+        let mut event = Self::builder()
             .version(UniswapVersion::V3)
             .transaction_hash(v3_event.id.clone())
             .pool_address(pool_address)
@@ -459,7 +851,25 @@ impl SwapEvent {
             .amount_in(amount_in)
             .amount_out(amount_out)
             .user_address(user_address)
-            .build()
+            .build()?;
+        event.set_block_info(block_number, timestamp);
+        Ok(event)
+    }
+
+    /// Orient a V3 swap from the pool's signed token deltas
+    ///
+    /// V3 reports `amount0`/`amount1` from the pool's side: positive flowed
+    /// in from the trader, negative flowed out to the recipient. Returns
+    /// whether token0 was sold, then the unsigned amounts in and out.
+    pub fn orient_v3_amounts(amount0: &str, amount1: &str) -> (bool, String, String) {
+        let signed = |amount: &str| amount.parse::<f64>().unwrap_or(0.0);
+        let unsigned = |amount: &str| amount.trim_start_matches('-').to_string();
+
+        if signed(amount0) > 0.0 || signed(amount1) < 0.0 {
+            (true, unsigned(amount0), unsigned(amount1))
+        } else {
+            (false, unsigned(amount1), unsigned(amount0))
+        }
     }
 
     /// Create a SwapEvent from raw data using builder with validation
@@ -632,6 +1042,55 @@ impl SwapEvent {
         builder.build()
     }
 
+    /// Units of `token_out` received per unit of `token_in`
+    ///
+    /// Returns `None` when either amount is missing, unparsable or zero.
+    pub fn execution_price(&self) -> Option<f64> {
+        Some(positive_amount(&self.amount_out)? / positive_amount(&self.amount_in)?)
+    }
+
+    /// Price impact of this swap against the captured V2 reserves, as a fraction
+    ///
+    /// Compares the execution price with the pool's mid price:
+    ///
+    /// `impact = 1 - effective_price / (reserve_out / reserve_in)`
+    ///
+    /// For a constant-product swap without fees this equals
+    /// `amount_in / (reserve_in + amount_in)`; the 0.3% LP fee is included
+    /// because the observed amounts already reflect it. Assumes the reserves
+    /// are the pre-swap reserves. `effective_price` puts the amounts in the
+    /// same decimal-adjusted units as the reserves. Returns `None` without
+    /// reserves or decimal amounts, or when any value is missing, unparsable
+    /// or zero.
+    #[allow(dead_code)]
+    pub fn price_impact(&self) -> Option<f64> {
+        Some(1.0 - self.effective_price()? / self.reserve_mid_price()?)
+    }
+
+    /// Decimal-adjusted units of `token_out` received per unit of `token_in`
+    ///
+    /// Uses `amount_out_decimal / amount_in_decimal`, so it is only available
+    /// after `normalize_amounts`. Returns `None` when either amount is
+    /// missing, unparsable or zero.
+    #[allow(dead_code)]
+    pub fn effective_price(&self) -> Option<f64> {
+        let amount_in = positive_amount(self.amount_in_decimal.as_deref()?)?;
+        let amount_out = positive_amount(self.amount_out_decimal.as_deref()?)?;
+        Some(amount_out / amount_in)
+    }
+
+    /// `price_impact` in basis points
+    #[allow(dead_code)]
+    pub fn price_impact_bps(&self) -> Option<f64> {
+        Some(self.price_impact()? * 10_000.0)
+    }
+
+    /// `reserve_out / reserve_in` of the captured V2 reserves
+    fn reserve_mid_price(&self) -> Option<f64> {
+        let reserves = self.v2_reserves.as_ref()?;
+        Some(positive_amount(&reserves.reserve_out)? / positive_amount(&reserves.reserve_in)?)
+    }
+
     pub fn add_pool_info(&mut self, pool_info: PoolInfo) {
         self.pool_info = Some(pool_info);
     }
@@ -641,7 +1100,18 @@ impl SwapEvent {
         self.enriched_data = Some(enriched_data);
     }
 
+    /// Merge enrichment into the existing data instead of replacing it
+    ///
+    /// Token metadata maps are unioned and, field by field, newer `Some`
+    /// values win while `None` keeps whatever was already known.
     #[allow(dead_code)]
+    pub fn merge_enrichment(&mut self, enriched_data: EnrichedData) {
+        match self.enriched_data.as_mut() {
+            Some(existing) => existing.merge(enriched_data),
+            None => self.enriched_data = Some(enriched_data),
+        }
+    }
+
     pub fn set_block_info(&mut self, block_number: u64, timestamp: DateTime<Utc>) {
         self.block_number = block_number;
         self.timestamp = timestamp;
@@ -654,12 +1124,111 @@ impl SwapEvent {
         self.gas_cost_usd = Some(gas_cost_usd);
     }
 
-    #[allow(dead_code)]
     pub fn set_usd_amounts(&mut self, amount_in_usd: f64, amount_out_usd: f64) {
         self.amount_in_usd = Some(amount_in_usd);
         self.amount_out_usd = Some(amount_out_usd);
     }
 
+    /// Fill a missing USD leg from the known one at the pool price
+    ///
+    /// `in_per_out` is the price of `token_out` in units of `token_in`, and
+    /// the amounts must be decimal-adjusted as the subgraph reports them.
+    /// Does nothing unless exactly one leg is known and both amounts parse.
+    pub fn derive_missing_usd_amount(&mut self, in_per_out: f64) {
+        let (Ok(amount_in), Ok(amount_out)) = (
+            self.amount_in.parse::<f64>(),
+            self.amount_out.parse::<f64>(),
+        ) else {
+            return;
+        };
+        // Value of the bought amount in units of the sold token
+        let out_in_token_in = amount_out * in_per_out;
+        if !(amount_in > 0.0 && out_in_token_in > 0.0 && out_in_token_in.is_finite()) {
+            return;
+        }
+
+        match (self.amount_in_usd, self.amount_out_usd) {
+            (Some(in_usd), None) => {
+                self.amount_out_usd = Some(in_usd * out_in_token_in / amount_in);
+            }
+            (None, Some(out_usd)) => {
+                self.amount_in_usd = Some(out_usd * amount_in / out_in_token_in);
+            }
+            _ => {}
+        }
+    }
+
+    /// USD value of the swap: `amount_in_usd`, falling back to `amount_out_usd`
+    pub fn volume_usd(&self) -> Option<f64> {
+        self.amount_in_usd.or(self.amount_out_usd)
+    }
+
+    /// Check a built event against the same rules as `SwapEventBuilder::validate`
+    ///
+    /// Returns the reasons the event is invalid; empty when it is valid.
+    pub fn validate(&self) -> Vec<String> {
+        let mut reasons = Vec::new();
+        let is_numeric = |amount: &str| amount.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+        if self.transaction_hash.is_empty() {
+            reasons.push("Transaction hash is empty".to_string());
+        }
+
+        if self.pool_address.is_empty() {
+            reasons.push("Pool address is empty".to_string());
+        } else if !self.pool_address.starts_with("0x") {
+            reasons.push("Pool address doesn't start with 0x".to_string());
+        }
+
+        for (side, token) in [("in", &self.token_in), ("out", &self.token_out)] {
+            if token.address.is_empty() {
+                reasons.push(format!("Token {} address is empty", side));
+            } else if !token.address.starts_with("0x") {
+                reasons.push(format!("Token {} address doesn't start with 0x", side));
+            }
+            if token.symbol.is_empty() {
+                reasons.push(format!("Token {} symbol is empty", side));
+            }
+        }
+
+        for (side, amount) in [("in", &self.amount_in), ("out", &self.amount_out)] {
+            if amount.is_empty() {
+                reasons.push(format!("Amount {} is empty", side));
+            } else if !is_numeric(amount) {
+                reasons.push(format!("Amount {} is not numeric", side));
+            }
+        }
+
+        if self.user_address.is_empty() {
+            reasons.push("User address is empty".to_string());
+        } else if !self.user_address.starts_with("0x") {
+            reasons.push("User address doesn't start with 0x".to_string());
+        }
+
+        reasons
+    }
+
+    /// Split a batch into valid events and invalid events with their reasons
+    ///
+    /// Input order is preserved within each half.
+    pub fn partition_valid(
+        events: Vec<SwapEvent>,
+    ) -> (Vec<SwapEvent>, Vec<(SwapEvent, Vec<String>)>) {
+        let mut valid = Vec::new();
+        let mut invalid = Vec::new();
+
+        for event in events {
+            let reasons = event.validate();
+            if reasons.is_empty() {
+                valid.push(event);
+            } else {
+                invalid.push((event, reasons));
+            }
+        }
+
+        (valid, invalid)
+    }
+
     #[allow(dead_code)]
     pub fn set_fee_info(&mut self, fee_amount: String, fee_usd: f64) {
         self.fee_amount = Some(fee_amount);
@@ -667,6 +1236,121 @@ impl SwapEvent {
     }
 }
 
+impl BlockSummary {
+    /// Group events by `block_number` into one summary per block, in block order
+    ///
+    /// Events without `SwapEvent::volume_usd` contribute no volume.
+    pub fn from_events(events: &[SwapEvent]) -> Vec<BlockSummary> {
+        let mut blocks: std::collections::BTreeMap<u64, BlockSummary> =
+            std::collections::BTreeMap::new();
+
+        for event in events {
+            let summary = blocks
+                .entry(event.block_number)
+                .or_insert_with(|| BlockSummary {
+                    block: event.block_number,
+                    swap_count: 0,
+                    total_volume_usd: 0.0,
+                    pools_touched: Vec::new(),
+                });
+
+            summary.swap_count += 1;
+            summary.total_volume_usd += event.volume_usd().unwrap_or(0.0);
+            if !summary.pools_touched.contains(&event.pool_address) {
+                summary.pools_touched.push(event.pool_address.clone());
+            }
+        }
+
+        blocks
+            .into_values()
+            .map(|mut summary| {
+                summary.pools_touched.sort();
+                summary
+            })
+            .collect()
+    }
+
+    /// Add the swaps of another summary of the same block
+    pub fn merge(&mut self, other: BlockSummary) {
+        self.swap_count += other.swap_count;
+        self.total_volume_usd += other.total_volume_usd;
+        for pool in other.pools_touched {
+            if !self.pools_touched.contains(&pool) {
+                self.pools_touched.push(pool);
+            }
+        }
+        self.pools_touched.sort();
+    }
+}
+
+/// Keep `current` unless `newer` carries a value
+fn prefer_newer<T>(current: &mut Option<T>, newer: Option<T>) {
+    if newer.is_some() {
+        *current = newer;
+    }
+}
+
+impl EnrichedData {
+    /// Merge newer enrichment into this one, preferring newer non-None values
+    pub fn merge(&mut self, newer: EnrichedData) {
+        for (token, metadata) in newer.token_metadata {
+            match self.token_metadata.get_mut(&token) {
+                Some(existing) => existing.merge(metadata),
+                None => {
+                    self.token_metadata.insert(token, metadata);
+                }
+            }
+        }
+
+        match (self.market_data.as_mut(), newer.market_data) {
+            (Some(existing), Some(market_data)) => existing.merge(market_data),
+            (None, market_data) => self.market_data = market_data,
+            (Some(_), None) => {}
+        }
+
+        match (self.risk_metrics.as_mut(), newer.risk_metrics) {
+            (Some(existing), Some(risk_metrics)) => existing.merge(risk_metrics),
+            (None, risk_metrics) => self.risk_metrics = risk_metrics,
+            (Some(_), None) => {}
+        }
+    }
+}
+
+impl TokenMetadata {
+    /// Merge newer token metadata into this one, preferring newer non-None values
+    pub fn merge(&mut self, newer: TokenMetadata) {
+        prefer_newer(&mut self.total_supply, newer.total_supply);
+        prefer_newer(&mut self.circulating_supply, newer.circulating_supply);
+        prefer_newer(&mut self.holders_count, newer.holders_count);
+        prefer_newer(&mut self.transfers_count_24h, newer.transfers_count_24h);
+        prefer_newer(&mut self.volume_24h, newer.volume_24h);
+    }
+}
+
+impl MarketData {
+    /// Merge newer market data into this one, preferring newer non-None values
+    pub fn merge(&mut self, newer: MarketData) {
+        prefer_newer(&mut self.price_change_24h, newer.price_change_24h);
+        prefer_newer(&mut self.price_change_7d, newer.price_change_7d);
+        prefer_newer(&mut self.volume_change_24h, newer.volume_change_24h);
+        prefer_newer(&mut self.market_cap_rank, newer.market_cap_rank);
+        prefer_newer(
+            &mut self.fully_diluted_valuation,
+            newer.fully_diluted_valuation,
+        );
+    }
+}
+
+impl RiskMetrics {
+    /// Merge newer risk metrics into this one, preferring newer non-None values
+    pub fn merge(&mut self, newer: RiskMetrics) {
+        prefer_newer(&mut self.impermanent_loss_risk, newer.impermanent_loss_risk);
+        prefer_newer(&mut self.volatility_score, newer.volatility_score);
+        prefer_newer(&mut self.liquidity_score, newer.liquidity_score);
+        prefer_newer(&mut self.smart_contract_risk, newer.smart_contract_risk);
+    }
+}
+
 impl std::fmt::Display for UniswapVersion {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -710,27 +1394,12 @@ impl SwapEventBuilder {
     }
 
     pub fn pool_address(mut self, pool_address: String) -> Self {
-        if pool_address.is_empty() {
-            eprintln!("SwapEventBuilder: Warning - pool address is empty");
-        } else if !pool_address.starts_with("0x") {
-            eprintln!(
-                "SwapEventBuilder: Warning - pool address doesn't start with 0x: {}",
-                pool_address
-            );
-        }
-        self.pool_address = Some(pool_address);
+        self.pool_address = Some(Self::checksummed("pool", pool_address));
         self
     }
 
-    pub fn token_in(mut self, token_in: TokenInfo) -> Self {
-        if token_in.address.is_empty() {
-            eprintln!("SwapEventBuilder: Warning - token in address is empty");
-        } else if !token_in.address.starts_with("0x") {
-            eprintln!(
-                "SwapEventBuilder: Warning - token in address doesn't start with 0x: {}",
-                token_in.address
-            );
-        }
+    pub fn token_in(mut self, mut token_in: TokenInfo) -> Self {
+        token_in.address = Self::checksummed("token in", token_in.address);
         if token_in.symbol.is_empty() {
             eprintln!("SwapEventBuilder: Warning - token in symbol is empty");
         }
@@ -738,15 +1407,8 @@ impl SwapEventBuilder {
         self
     }
 
-    pub fn token_out(mut self, token_out: TokenInfo) -> Self {
-        if token_out.address.is_empty() {
-            eprintln!("SwapEventBuilder: Warning - token out address is empty");
-        } else if !token_out.address.starts_with("0x") {
-            eprintln!(
-                "SwapEventBuilder: Warning - token out address doesn't start with 0x: {}",
-                token_out.address
-            );
-        }
+    pub fn token_out(mut self, mut token_out: TokenInfo) -> Self {
+        token_out.address = Self::checksummed("token out", token_out.address);
         if token_out.symbol.is_empty() {
             eprintln!("SwapEventBuilder: Warning - token out symbol is empty");
         }
@@ -781,16 +1443,23 @@ impl SwapEventBuilder {
     }
 
     pub fn user_address(mut self, user_address: String) -> Self {
-        if user_address.is_empty() {
-            eprintln!("SwapEventBuilder: Warning - user address is empty");
-        } else if !user_address.starts_with("0x") {
+        self.user_address = Some(Self::checksummed("user", user_address));
+        self
+    }
+
+    /// EIP-55 form of `address`, or `address` unchanged (with a warning) if invalid
+    fn checksummed(field: &str, address: String) -> String {
+        if address.is_empty() {
+            eprintln!("SwapEventBuilder: Warning - {} address is empty", field);
+            return address;
+        }
+        normalize_address(&address).unwrap_or_else(|e| {
             eprintln!(
-                "SwapEventBuilder: Warning - user address doesn't start with 0x: {}",
-                user_address
+                "SwapEventBuilder: Warning - invalid {} address: {}",
+                field, e
             );
-        }
-        self.user_address = Some(user_address);
-        self
+            address
+        })
     }
 
     /// Validate the current builder state and return any warnings
@@ -901,7 +1570,7 @@ impl SwapEventBuilder {
     /// Test the builder with sample data
     pub fn test_builder() -> Result<SwapEvent, String> {
         let token_in = TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             decimals: 6,
             name: "USD Coin".to_string(),
@@ -1038,6 +1707,17 @@ impl SwapEventBuilder {
             return Err("Token addresses cannot be empty".to_string());
         }
 
+        // The setters checksum valid addresses, so anything left here is malformed
+        for (field, address) in [
+            ("Pool", &pool_address),
+            ("Token in", &token_in.address),
+            ("Token out", &token_out.address),
+            ("User", &user_address),
+        ] {
+            normalize_address(address)
+                .map_err(|e| format!("{} address is invalid: {}", field, e))?;
+        }
+
         // Validate amounts are numeric
         if !amount_in.chars().all(|c| c.is_ascii_digit() || c == '.') {
             return Err("Amount in must be a valid numeric value".to_string());
@@ -1057,12 +1737,15 @@ impl SwapEventBuilder {
             version,
             timestamp: Utc::now(),
             block_number: 0,
+            log_index: None,
             transaction_hash,
             pool_address,
             token_in,
             token_out,
             amount_in,
             amount_out,
+            amount_in_decimal: None,
+            amount_out_decimal: None,
             amount_in_usd: None,
             amount_out_usd: None,
             fee_amount: None,
@@ -1073,6 +1756,669 @@ impl SwapEventBuilder {
             gas_cost_usd: None,
             pool_info: None,
             enriched_data: None,
+            direction: None,
+            v2_reserves: None,
+            tags: Vec::new(),
+        })
+    }
+}
+
+/// Subgraph scalars, which arrive as JSON strings (BigInt, BigDecimal) or numbers
+mod graphql_scalar {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+    use serde_json::Value;
+    use std::fmt::Display;
+    use std::str::FromStr;
+
+    fn text<E: Error>(value: Value) -> Result<String, E> {
+        match value {
+            Value::String(text) => Ok(text),
+            Value::Number(number) => Ok(number.to_string()),
+            other => Err(E::custom(format!(
+                "expected a string or number, got {}",
+                other
+            ))),
+        }
+    }
+
+    fn parse<T, E>(text: &str) -> Result<T, E>
+    where
+        T: FromStr,
+        T::Err: Display,
+        E: Error,
+    {
+        text.trim()
+            .parse()
+            .map_err(|e| E::custom(format!("invalid number {:?}: {}", text, e)))
+    }
+
+    pub fn string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        text(Value::deserialize(deserializer)?)
+    }
+
+    pub fn opt_string<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<String>, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Null => Ok(None),
+            value => text(value).map(Some),
+        }
+    }
+
+    pub fn integer<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        parse(&string(deserializer)?)
+    }
+
+    pub fn opt_integer<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        opt_string(deserializer)?
+            .map(|text| parse(&text))
+            .transpose()
+    }
+}
+
+/// Borsh encoding of timestamps as unix milliseconds
+mod unix_millis {
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use chrono::{DateTime, Utc};
+    use std::io::{Error, ErrorKind, Read, Result, Write};
+
+    pub fn serialize<W: Write>(timestamp: &DateTime<Utc>, writer: &mut W) -> Result<()> {
+        timestamp.timestamp_millis().serialize(writer)
+    }
+
+    pub fn deserialize<R: Read>(reader: &mut R) -> Result<DateTime<Utc>> {
+        from_millis(i64::deserialize_reader(reader)?)
+    }
+
+    pub fn serialize_option<W: Write>(
+        timestamp: &Option<DateTime<Utc>>,
+        writer: &mut W,
+    ) -> Result<()> {
+        timestamp
+            .map(|timestamp| timestamp.timestamp_millis())
+            .serialize(writer)
+    }
+
+    pub fn deserialize_option<R: Read>(reader: &mut R) -> Result<Option<DateTime<Utc>>> {
+        Option::<i64>::deserialize_reader(reader)?
+            .map(from_millis)
+            .transpose()
+    }
+
+    fn from_millis(millis: i64) -> Result<DateTime<Utc>> {
+        DateTime::from_timestamp_millis(millis).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("timestamp out of range: {}", millis),
+            )
         })
     }
 }
+
+/// Whether a subgraph amount is zero ("0", "0.0") or missing
+fn is_zero_amount(value: &str) -> bool {
+    value.trim().is_empty() || value.trim().parse::<f64>().is_ok_and(|value| value == 0.0)
+}
+
+/// Parse an amount, rejecting zero, negative and unparsable values
+fn positive_amount(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|value| *value > 0.0)
+}
+
+/// Shift an integer amount in the token's smallest unit by `decimals`
+///
+/// Trailing zeros are trimmed, e.g. `1500000` with 6 decimals becomes `1.5`.
+/// Amounts that are not plain (optionally negative) integers are returned as is.
+fn normalize_amount(raw: &str, decimals: u8) -> String {
+    let (sign, digits) = match raw.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", raw),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return raw.to_string();
+    }
+
+    let decimals = decimals as usize;
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let whole = whole.trim_start_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event_with_reserves(
+        reserve_in: &str,
+        reserve_out: &str,
+        amount_in: &str,
+        amount_out: &str,
+    ) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.amount_in = amount_in.to_string();
+        event.amount_out = amount_out.to_string();
+        event.amount_in_decimal = Some(amount_in.to_string());
+        event.amount_out_decimal = Some(amount_out.to_string());
+        event.v2_reserves = Some(V2Reserves {
+            reserve_in: reserve_in.to_string(),
+            reserve_out: reserve_out.to_string(),
+        });
+        event
+    }
+
+    #[test]
+    fn test_price_impact_from_reserves() {
+        // 10 into a 1000/1000 pool returns 1000 * 10 / 1010 without fees
+        let small = event_with_reserves("1000", "1000", "10", "9.900990099009901");
+        assert!((small.price_impact().unwrap() - 10.0 / 1010.0).abs() < 1e-12);
+
+        // Selling the whole reserve halves the execution price
+        let large = event_with_reserves("100", "200", "100", "100");
+        assert!((large.price_impact().unwrap() - 0.5).abs() < 1e-12);
+
+        // The 0.3% fee shows up as extra impact
+        let with_fee = event_with_reserves("1000", "1000", "10", "9.87158");
+        let impact = with_fee.price_impact().unwrap();
+        assert!(impact > 10.0 / 1010.0 && impact < 0.0129);
+    }
+
+    #[test]
+    fn test_price_impact_requires_reserves() {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.v2_reserves = None;
+        assert_eq!(event.price_impact(), None);
+
+        assert_eq!(
+            event_with_reserves("0", "1000", "10", "9").price_impact(),
+            None
+        );
+        assert_eq!(
+            event_with_reserves("1000", "n/a", "10", "9").price_impact(),
+            None
+        );
+    }
+
+    /// 3,000 USDC for WETH in a 30M USDC / 10k WETH pair, after the 0.3% fee
+    fn usdc_to_weth_swap() -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.token_in.symbol = "USDC".to_string();
+        event.token_in.decimals = 6;
+        event.token_out.symbol = "WETH".to_string();
+        event.token_out.decimals = 18;
+        event.amount_in = "3000000000".to_string();
+        event.amount_out = "996900609009281774".to_string();
+        event.normalize_amounts();
+        event.v2_reserves = Some(V2Reserves {
+            reserve_in: "30000000".to_string(),
+            reserve_out: "10000".to_string(),
+        });
+        event
+    }
+
+    #[test]
+    fn test_effective_price_and_impact_for_usdc_to_weth() {
+        let event = usdc_to_weth_swap();
+
+        let price = event.effective_price().unwrap();
+        // ~0.000332 WETH per USDC, an ETH price of ~3009 USDC
+        assert!((price - 0.99690060900928 / 3000.0).abs() < 1e-15);
+
+        // About 1 bp of size impact on top of the 30 bp fee
+        let impact = event.price_impact_bps().unwrap();
+        assert!((impact - 30.9939).abs() < 1e-3, "impact {}", impact);
+        // The fraction is in the same decimal-adjusted units
+        let fraction = event.price_impact().unwrap();
+        assert!((fraction - 0.00309939).abs() < 1e-7, "impact {}", fraction);
+    }
+
+    #[test]
+    fn test_effective_price_and_impact_require_inputs() {
+        let mut event = usdc_to_weth_swap();
+        event.v2_reserves = None;
+        assert!(event.effective_price().is_some());
+        assert_eq!(event.price_impact_bps(), None);
+
+        let mut unnormalized = usdc_to_weth_swap();
+        unnormalized.amount_in_decimal = None;
+        assert_eq!(unnormalized.effective_price(), None);
+        assert_eq!(unnormalized.price_impact_bps(), None);
+
+        let mut zero_in = usdc_to_weth_swap();
+        zero_in.amount_in = "0".to_string();
+        zero_in.normalize_amounts();
+        assert_eq!(zero_in.effective_price(), None);
+    }
+
+    fn event_for_round_trip() -> SwapEvent {
+        let mut event = usdc_to_weth_swap();
+        event.timestamp = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()
+            + chrono::Duration::milliseconds(678);
+        event.log_index = Some(42);
+        event.direction = Some(Direction::Buy);
+        event.tags = vec!["whale".to_string()];
+        event.add_pool_info(PoolInfo {
+            address: event.pool_address.clone(),
+            token0: event.token_in.address.clone(),
+            token1: event.token_out.address.clone(),
+            fee_tier: Some(3000),
+            liquidity: Some("123456789".to_string()),
+            volume_24h: None,
+            fees_24h: None,
+            apy: Some(4.2),
+            created_at: Some(Utc.with_ymd_and_hms(2020, 5, 5, 0, 0, 0).unwrap()),
+        });
+        event
+    }
+
+    #[test]
+    fn test_encode_round_trips_in_both_formats() {
+        let event = event_for_round_trip();
+
+        for format in [SerializationFormat::Json, SerializationFormat::Borsh] {
+            let bytes = event.encode(format, AmountFormat::Raw).unwrap();
+            let decoded: SwapEvent = match format {
+                SerializationFormat::Json => serde_json::from_slice(&bytes).unwrap(),
+                SerializationFormat::Borsh => borsh::from_slice(&bytes).unwrap(),
+            };
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&event).unwrap(),
+                "{:?} round trip",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_borsh_timestamps_are_unix_millis() {
+        let event = event_for_round_trip();
+        let bytes = borsh::to_vec(&event).unwrap();
+
+        // id, then the version's variant index, then the timestamp
+        let offset = 4 + event.id.len() + 1;
+        let millis = i64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+        assert_eq!(millis, event.timestamp.timestamp_millis());
+        assert!(
+            bytes.len()
+                < event
+                    .encode(SerializationFormat::Json, AmountFormat::Raw)
+                    .unwrap()
+                    .len()
+        );
+    }
+
+    #[test]
+    fn test_partition_key_per_strategy() {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.pool_address = "0xPOOL".to_string();
+        event.token_in.address = "0xBBBB".to_string();
+        event.token_out.address = "0xaaaa".to_string();
+        event.user_address = "0xUser".to_string();
+
+        assert_eq!(event.partition_key(PartitionKeyStrategy::Pool), "0xpool");
+        assert_eq!(
+            event.partition_key(PartitionKeyStrategy::Pair),
+            "0xaaaa-0xbbbb"
+        );
+        assert_eq!(event.partition_key(PartitionKeyStrategy::TokenIn), "0xbbbb");
+        assert_eq!(event.partition_key(PartitionKeyStrategy::User), "0xuser");
+
+        // The reverse swap on the same pair lands on the same partition
+        let mut reverse = event.clone();
+        std::mem::swap(&mut reverse.token_in, &mut reverse.token_out);
+        assert_eq!(
+            reverse.partition_key(PartitionKeyStrategy::Pair),
+            event.partition_key(PartitionKeyStrategy::Pair)
+        );
+        assert_ne!(
+            reverse.partition_key(PartitionKeyStrategy::TokenIn),
+            event.partition_key(PartitionKeyStrategy::TokenIn)
+        );
+    }
+
+    fn token_metadata(total_supply: Option<&str>, holders_count: Option<u64>) -> TokenMetadata {
+        TokenMetadata {
+            total_supply: total_supply.map(|s| s.to_string()),
+            circulating_supply: None,
+            holders_count,
+            transfers_count_24h: None,
+            volume_24h: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_enrichment_unions_partial_data() {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+
+        // First stage: supply for USDC plus price data
+        event.merge_enrichment(EnrichedData {
+            token_metadata: HashMap::from([(
+                "USDC".to_string(),
+                token_metadata(Some("1000"), None),
+            )]),
+            market_data: Some(MarketData {
+                price_change_24h: Some(1.5),
+                price_change_7d: None,
+                volume_change_24h: None,
+                market_cap_rank: Some(7),
+                fully_diluted_valuation: None,
+            }),
+            risk_metrics: None,
+        });
+
+        // Second stage: holders for USDC, a new token and risk data
+        event.merge_enrichment(EnrichedData {
+            token_metadata: HashMap::from([
+                ("USDC".to_string(), token_metadata(None, Some(42))),
+                ("WETH".to_string(), token_metadata(Some("5000"), Some(9))),
+            ]),
+            market_data: Some(MarketData {
+                price_change_24h: Some(2.0),
+                price_change_7d: Some(-3.0),
+                volume_change_24h: None,
+                market_cap_rank: None,
+                fully_diluted_valuation: None,
+            }),
+            risk_metrics: Some(RiskMetrics {
+                impermanent_loss_risk: None,
+                volatility_score: Some(0.3),
+                liquidity_score: None,
+                smart_contract_risk: None,
+            }),
+        });
+
+        let enriched = event.enriched_data.unwrap();
+        let usdc = &enriched.token_metadata["USDC"];
+        assert_eq!(usdc.total_supply.as_deref(), Some("1000"));
+        assert_eq!(usdc.holders_count, Some(42));
+        assert_eq!(
+            enriched.token_metadata["WETH"].total_supply.as_deref(),
+            Some("5000")
+        );
+
+        let market_data = enriched.market_data.unwrap();
+        assert_eq!(market_data.price_change_24h, Some(2.0));
+        assert_eq!(market_data.price_change_7d, Some(-3.0));
+        assert_eq!(market_data.market_cap_rank, Some(7));
+
+        assert_eq!(enriched.risk_metrics.unwrap().volatility_score, Some(0.3));
+    }
+
+    #[test]
+    fn test_block_summaries_group_by_block() {
+        let event = |block_number: u64, pool: &str, amount_in_usd: Option<f64>| {
+            let mut event = SwapEventBuilder::test_builder().unwrap();
+            event.block_number = block_number;
+            event.pool_address = pool.to_string();
+            event.amount_in_usd = amount_in_usd;
+            event
+        };
+
+        let events = vec![
+            event(101, "0xpoolb", Some(50.0)),
+            event(100, "0xpoola", Some(100.0)),
+            event(100, "0xpoolb", Some(25.5)),
+            event(101, "0xpoolb", None),
+            event(100, "0xpoola", Some(10.0)),
+        ];
+
+        let summaries = BlockSummary::from_events(&events);
+
+        assert_eq!(
+            summaries,
+            vec![
+                BlockSummary {
+                    block: 100,
+                    swap_count: 3,
+                    total_volume_usd: 135.5,
+                    pools_touched: vec!["0xpoola".to_string(), "0xpoolb".to_string()],
+                },
+                BlockSummary {
+                    block: 101,
+                    swap_count: 2,
+                    total_volume_usd: 50.0,
+                    pools_touched: vec!["0xpoolb".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sparse_event_omits_null_fields_and_round_trips() {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.add_pool_info(PoolInfo {
+            address: event.pool_address.clone(),
+            token0: event.token_in.address.clone(),
+            token1: event.token_out.address.clone(),
+            fee_tier: Some(3000),
+            liquidity: None,
+            volume_24h: None,
+            fees_24h: None,
+            apy: None,
+            created_at: None,
+        });
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(!json.contains("null"), "unexpected null in {}", json);
+        assert!(!json.contains("fee_usd"));
+        assert!(!json.contains("market_cap"));
+        assert!(json.contains("\"fee_tier\":3000"));
+
+        let decoded: SwapEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.id, event.id);
+        assert_eq!(decoded.fee_usd, None);
+        assert_eq!(decoded.token_in.market_cap, None);
+        let pool_info = decoded.pool_info.as_ref().unwrap();
+        assert_eq!(pool_info.fee_tier, Some(3000));
+        assert_eq!(pool_info.liquidity, None);
+        assert_eq!(serde_json::to_string(&decoded).unwrap().len(), json.len());
+    }
+
+    fn usdc_to_weth_event() -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.token_in.decimals = 6;
+        event.token_out.decimals = 18;
+        event.amount_in = "1500000".to_string();
+        event.amount_out = "2000000000000000".to_string();
+        event
+    }
+
+    fn published(event: &SwapEvent, format: AmountFormat) -> serde_json::Value {
+        serde_json::from_str(&event.to_json_with_format(format).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_raw_amount_format_publishes_amounts_unchanged() {
+        let json = published(&usdc_to_weth_event(), AmountFormat::Raw);
+        assert_eq!(json["amount_in"], "1500000");
+        assert_eq!(json["amount_out"], "2000000000000000");
+        assert!(json.get("amount_in_decimal").is_none());
+        assert!(json.get("amount_out_decimal").is_none());
+    }
+
+    #[test]
+    fn test_decimal_amount_format_replaces_amounts() {
+        let json = published(&usdc_to_weth_event(), AmountFormat::Decimal);
+        assert_eq!(json["amount_in"], "1.5");
+        assert_eq!(json["amount_out"], "0.002");
+        assert!(json.get("amount_in_decimal").is_none());
+        assert!(json.get("amount_out_decimal").is_none());
+    }
+
+    #[test]
+    fn test_both_amount_format_publishes_raw_and_decimal() {
+        let json = published(&usdc_to_weth_event(), AmountFormat::Both);
+        assert_eq!(json["amount_in"], "1500000");
+        assert_eq!(json["amount_out"], "2000000000000000");
+        assert_eq!(json["amount_in_decimal"], "1.5");
+        assert_eq!(json["amount_out_decimal"], "0.002");
+
+        let decoded: SwapEvent = serde_json::from_str(&json.to_string()).unwrap();
+        assert_eq!(decoded.amount_in_decimal.as_deref(), Some("1.5"));
+    }
+
+    #[test]
+    fn test_normalize_amounts_fills_decimal_fields() {
+        let mut event = usdc_to_weth_event();
+        event.amount_out = "123456789012345678901234567".to_string();
+        event.normalize_amounts();
+        assert_eq!(event.amount_in_decimal.as_deref(), Some("1.5"));
+        assert_eq!(
+            event.amount_out_decimal.as_deref(),
+            Some("123456789.012345678901234567")
+        );
+
+        // Zero, 0-decimal tokens and float-looking V3 amounts
+        event.amount_in = "0".to_string();
+        event.token_out.decimals = 0;
+        event.amount_out = "-0.25".to_string();
+        event.normalize_amounts();
+        assert_eq!(event.amount_in_decimal.as_deref(), Some("0"));
+        assert_eq!(event.amount_out_decimal.as_deref(), Some("-0.25"));
+        event.amount_out = "77".to_string();
+        event.normalize_amounts();
+        assert_eq!(event.amount_out_decimal.as_deref(), Some("77"));
+    }
+
+    #[test]
+    fn test_normalize_amount() {
+        assert_eq!(normalize_amount("1500000", 6), "1.5");
+        assert_eq!(normalize_amount("42", 6), "0.000042");
+        assert_eq!(normalize_amount("-2500", 3), "-2.5");
+        assert_eq!(normalize_amount("1000", 0), "1000");
+        assert_eq!(normalize_amount("0", 18), "0");
+        // Already decimal-adjusted or unparsable amounts pass through
+        assert_eq!(normalize_amount("0.5", 18), "0.5");
+        assert_eq!(normalize_amount("n/a", 18), "n/a");
+    }
+
+    const USDC: &str = "0xA0b86991c6218b36c1d19d4a2e9eB0cE3606eB48";
+
+    fn event_between(token_in: &str, token_out: &str) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.token_in.address = token_in.to_string();
+        event.token_out.address = token_out.to_string();
+        event
+    }
+
+    #[test]
+    fn test_direction_buy_when_reference_is_token_out() {
+        let event = event_between("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", USDC);
+        assert_eq!(
+            event.direction_vs(&USDC.to_lowercase()),
+            Some(Direction::Buy)
+        );
+    }
+
+    #[test]
+    fn test_direction_sell_when_reference_is_token_in() {
+        let event = event_between(USDC, "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+        assert_eq!(event.direction_vs(USDC), Some(Direction::Sell));
+    }
+
+    #[test]
+    fn test_direction_not_involved_without_reference_token() {
+        let mut event = event_between(
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "0x6b175474e89094c44da98b954eedeac495271d0f",
+        );
+        assert_eq!(event.direction_vs(USDC), Some(Direction::NotInvolved));
+        assert_eq!(event.direction_vs(""), None);
+
+        event.direction = event.direction_vs(USDC);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["direction"], "not_involved");
+    }
+
+    #[test]
+    fn test_partition_valid_splits_mixed_batch() {
+        let valid = SwapEventBuilder::test_builder().unwrap();
+        let mut bad_pool = SwapEventBuilder::test_builder().unwrap();
+        bad_pool.pool_address = "pool".to_string();
+        let mut bad_amounts = SwapEventBuilder::test_builder().unwrap();
+        bad_amounts.amount_in = String::new();
+        bad_amounts.amount_out = "1e18".to_string();
+
+        let (valid_events, invalid) = SwapEvent::partition_valid(vec![
+            bad_pool.clone(),
+            valid.clone(),
+            bad_amounts.clone(),
+            valid.clone(),
+        ]);
+
+        assert_eq!(valid_events.len(), 2);
+        assert_eq!(invalid.len(), 2);
+        assert_eq!(invalid[0].0.pool_address, "pool");
+        assert_eq!(invalid[0].1, vec!["Pool address doesn't start with 0x"]);
+        assert_eq!(
+            invalid[1].1,
+            vec!["Amount in is empty", "Amount out is not numeric"]
+        );
+    }
+
+    #[test]
+    fn test_builder_checksums_and_rejects_addresses() {
+        let event = SwapEventBuilder::test_builder().unwrap();
+        assert_eq!(
+            event.pool_address,
+            "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8"
+        );
+
+        let err = SwapEvent::builder()
+            .version(UniswapVersion::V2)
+            .transaction_hash(event.transaction_hash.clone())
+            .pool_address("0x8ad599c3a0ff1de082011efddc58f1908eb6e6".to_string())
+            .token_in(event.token_in.clone())
+            .token_out(event.token_out.clone())
+            .amount_in("1".to_string())
+            .amount_out("2".to_string())
+            .user_address(event.user_address.clone())
+            .build()
+            .unwrap_err();
+        assert!(err.starts_with("Pool address is invalid"), "{}", err);
+    }
+
+    #[test]
+    fn test_batch_ndjson_parses_back_line_by_line() {
+        let mut events: Vec<SwapEvent> = (0..3)
+            .map(|_| SwapEventBuilder::test_builder().unwrap())
+            .collect();
+        events[1].tags = vec!["line\nbreak".to_string()];
+        events[2].set_usd_amounts(1234.5, 1234.5);
+
+        let mut buffer = Vec::new();
+        SwapEvent::serialize_batch_ndjson(&events, &mut buffer).unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert!(output.ends_with('\n'));
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), events.len());
+        for (line, event) in lines.iter().zip(&events) {
+            let parsed: SwapEvent = serde_json::from_str(line).unwrap();
+            assert_eq!(
+                serde_json::to_value(&parsed).unwrap(),
+                serde_json::to_value(event).unwrap()
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod publisher;
+pub mod subscriber;
 
 pub use publisher::RedisPublisher;
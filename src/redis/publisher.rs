@@ -1,17 +1,33 @@
-use crate::config::AppConfig;
-use crate::error::{RedisError, Result};
-use crate::model::SwapEvent;
+use crate::config::{AppConfig, RedisConfig, RedisOutputMode};
+use crate::error::{DAppError, RedisError, Result};
+use crate::model::{
+    BlockSummary, ControlMessage, OhlcCandle, QuarantinedSwap, SerializationFormat, SwapEvent,
+};
+use crate::sink::EventSink;
+use crate::telemetry::MetricsCollector;
+use crate::utils::backoff::ExponentialBackoff;
+use async_trait::async_trait;
 use redis::{aio::ConnectionManager, AsyncCommands, RedisResult};
+use std::future::Future;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::{debug, error, info};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
 
 /// Redis publisher for swap events
 #[derive(Clone)]
 pub struct RedisPublisher {
-    connection_manager: Arc<ConnectionManager>,
+    /// `redis.connection_pool_size` connections, handed out round-robin
+    connections: Arc<[ConnectionManager]>,
+    next_connection: Arc<AtomicUsize>,
+    /// Serializes appends to and drains of `redis.dead_letter_path`
+    dead_letter_lock: Arc<tokio::sync::Mutex<()>>,
     channel: String,
     config: AppConfig,
+    skipped_events: Arc<AtomicU64>,
 }
 
 impl RedisPublisher {
@@ -20,17 +36,50 @@ impl RedisPublisher {
         let client = redis::Client::open(config.redis.url.clone())
             .map_err(|e| RedisError::Connection(e.to_string()))?;
 
-        let connection_manager = ConnectionManager::new(client)
-            .await
-            .map_err(|e| RedisError::Connection(e.to_string()))?;
+        let pool_size = config.redis.connection_pool_size.max(1) as usize;
+        let connections = futures::future::try_join_all(
+            (0..pool_size).map(|_| ConnectionManager::new(client.clone())),
+        )
+        .await
+        .map_err(|e| RedisError::Connection(e.to_string()))?;
 
-        info!("Redis publisher initialized successfully");
+        info!(
+            "Redis publisher initialized successfully with {} connections",
+            pool_size
+        );
 
         Ok(Self {
-            connection_manager: Arc::new(connection_manager),
+            connections: connections.into(),
+            next_connection: Arc::new(AtomicUsize::new(0)),
+            dead_letter_lock: Arc::new(tokio::sync::Mutex::new(())),
             channel: config.redis.channel.clone(),
             config,
+            skipped_events: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Create a publisher from just the Redis section
+    ///
+    /// Every other section keeps its default.
+    #[allow(dead_code)]
+    pub async fn from_redis_config(config: &RedisConfig) -> Result<Self> {
+        Self::new(AppConfig {
+            redis: config.clone(),
+            ..AppConfig::default()
         })
+        .await
+    }
+
+    /// Next connection from the pool, round-robin
+    fn connection(&self) -> ConnectionManager {
+        let index = self.next_connection.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+        self.connections[index].clone()
+    }
+
+    /// Number of pooled connections
+    #[allow(dead_code)]
+    pub fn pool_size(&self) -> usize {
+        self.connections.len()
     }
 
     /// Publish a single swap event
@@ -79,17 +128,40 @@ impl RedisPublisher {
             ));
         }
 
-        let event_json =
-            serde_json::to_string(event).map_err(|e| RedisError::Serialization(e.to_string()))?;
+        if !self.has_enough_subscribers(1).await? {
+            return Ok(());
+        }
+        if self
+            .claim_unseen(std::slice::from_ref(event))
+            .await?
+            .is_empty()
+        {
+            return Ok(());
+        }
 
+        let payload = event
+            .encode(
+                self.config.redis.serialization_format,
+                self.config.redis.amount_format,
+            )
+            .map_err(|e| RedisError::Serialization(e.to_string()))?;
+
+        let channel = self.config.event_channel(&event.version);
         debug!(
             "Publishing event to Redis channel {}: {}",
-            self.channel, event.id
+            channel, event.id
         );
 
         #[allow(unused_mut)]
-        let mut conn = (*self.connection_manager).clone();
-        let result: RedisResult<()> = conn.publish(&self.channel, event_json).await;
+        let mut conn = self.connection();
+        let result: RedisResult<()> = match self.config.redis.output_mode {
+            RedisOutputMode::PubSub => conn.publish(&channel, payload).await,
+            RedisOutputMode::Stream { maxlen } => {
+                Self::xadd(&channel, maxlen, &event.id, payload)
+                    .query_async(&mut conn)
+                    .await
+            }
+        };
 
         match result {
             Ok(_) => {
@@ -98,6 +170,7 @@ impl RedisPublisher {
             }
             Err(e) => {
                 error!("Failed to publish event {}: {}", event.id, e);
+                self.release_claims(std::slice::from_ref(event)).await;
 
                 // Check if this is a timeout error
                 if e.to_string().contains("timeout") || e.to_string().contains("timed out") {
@@ -115,28 +188,123 @@ impl RedisPublisher {
     }
 
     /// Publish multiple events in a batch
+    ///
+    /// A batch that still fails after retries is appended to
+    /// `redis.dead_letter_path`, if set, instead of failing.
     pub async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        if events.is_empty() || !self.has_enough_subscribers(events.len()).await? {
+            return Ok(());
+        }
+
+        let events = self.claim_unseen(events).await?;
         if events.is_empty() {
             return Ok(());
         }
 
-        debug!("Publishing batch of {} events to Redis", events.len());
+        match self.send_batch(&events).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.release_claims(&events).await;
+                self.dead_letter(&events, e).await
+            }
+        }
+    }
 
-        let mut conn = (*self.connection_manager).clone();
+    /// `redis.dedup_ttl_seconds` key marking an event as published
+    fn seen_key(&self, event_id: &str) -> String {
+        format!("{}:seen:{}", self.channel, event_id)
+    }
+
+    /// The events not published before, claiming each with `SET NX EX`
+    ///
+    /// Returns every event when `redis.dedup_ttl_seconds` is 0. The keys
+    /// outlive the process, so an event is skipped even if another instance
+    /// or an earlier run published it within the TTL.
+    async fn claim_unseen(&self, events: &[SwapEvent]) -> Result<Vec<SwapEvent>> {
+        let ttl = self.config.redis.dedup_ttl_seconds;
+        if ttl == 0 {
+            return Ok(events.to_vec());
+        }
 
-        // Use pipeline for batch publishing
         let mut pipe = redis::pipe();
         for event in events {
-            let event_json = serde_json::to_string(event).map_err(|e| {
-                // Use EventParsing error for JSON serialization failures
-                crate::error::DAppError::Ethereum(crate::error::EthereumError::EventParsing(
-                    format!("Failed to serialize event to JSON: {}", e),
-                ))
-            })?;
-            pipe.publish(&self.channel, event_json);
+            pipe.cmd("SET")
+                .arg(self.seen_key(&event.id))
+                .arg(1)
+                .arg("NX")
+                .arg("EX")
+                .arg(ttl);
         }
+        let claimed: Vec<Option<String>> = retry_with_backoff(&self.config, "dedup claim", || {
+            let mut conn = self.connection();
+            let pipe = &pipe;
+            async move { pipe.query_async(&mut conn).await }
+        })
+        .await
+        .map_err(|e| RedisError::Publish(format!("Failed to claim event ids: {}", e)))?;
 
-        let result: RedisResult<()> = pipe.query_async(&mut conn).await;
+        let unseen: Vec<SwapEvent> = events
+            .iter()
+            .zip(claimed)
+            .filter(|(_, claimed)| claimed.is_some())
+            .map(|(event, _)| event.clone())
+            .collect();
+        if unseen.len() < events.len() {
+            debug!(
+                "Skipping {} events already published to {}",
+                events.len() - unseen.len(),
+                self.channel
+            );
+        }
+        Ok(unseen)
+    }
+
+    /// Drop the dedup keys of events that failed to publish so a retry is not skipped
+    async fn release_claims(&self, events: &[SwapEvent]) {
+        if self.config.redis.dedup_ttl_seconds == 0 {
+            return;
+        }
+
+        let keys: Vec<String> = events
+            .iter()
+            .map(|event| self.seen_key(&event.id))
+            .collect();
+        let mut conn = self.connection();
+        if let Err(e) = conn.del::<_, ()>(keys).await {
+            warn!("Failed to release dedup keys of unpublished events: {}", e);
+        }
+    }
+
+    /// Send a batch, retrying with backoff
+    async fn send_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        debug!("Publishing batch of {} events to Redis", events.len());
+
+        // Use pipeline for batch publishing
+        let mut pipe = redis::pipe();
+        match self.config.redis.output_mode {
+            RedisOutputMode::PubSub => {
+                for (channel, message) in Self::batch_messages(&self.config, events)? {
+                    pipe.publish(channel, message);
+                }
+            }
+            RedisOutputMode::Stream { maxlen } => {
+                for (event, message) in events
+                    .iter()
+                    .zip(Self::event_messages(&self.config, events)?)
+                {
+                    let stream = self.config.event_channel(&event.version);
+                    pipe.add_command(Self::xadd(&stream, maxlen, &event.id, message))
+                        .ignore();
+                }
+            }
+        }
+
+        let result: RedisResult<()> = retry_with_backoff(&self.config, "batch publish", || {
+            let mut conn = self.connection();
+            let pipe = &pipe;
+            async move { pipe.query_async(&mut conn).await }
+        })
+        .await;
 
         match result {
             Ok(_) => {
@@ -145,20 +313,272 @@ impl RedisPublisher {
             }
             Err(e) => {
                 error!("Failed to publish batch: {}", e);
+                Err(RedisError::Publish(e.to_string()).into())
+            }
+        }
+    }
 
-                // Check if this is a timeout error
-                if e.to_string().contains("timeout") || e.to_string().contains("timed out") {
-                    Err(crate::error::DAppError::Redis(
-                        crate::error::RedisError::timeout_error(format!(
-                            "Redis batch publish timeout: {}",
-                            e
-                        )),
-                    ))
-                } else {
-                    Err(RedisError::Publish(e.to_string()).into())
-                }
+    /// Append a batch that failed to publish to the dead-letter file
+    ///
+    /// Returns `error` if no dead-letter file is configured or it cannot be written.
+    async fn dead_letter(&self, events: &[SwapEvent], error: DAppError) -> Result<()> {
+        let Some(path) = &self.config.redis.dead_letter_path else {
+            return Err(error);
+        };
+
+        let _guard = self.dead_letter_lock.lock().await;
+        match append_dead_letter(path, events) {
+            Ok(()) => {
+                warn!(
+                    "Wrote {} unpublished events to dead-letter file {}: {}",
+                    events.len(),
+                    path,
+                    error
+                );
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to write dead-letter file {}: {}", path, e);
+                Err(error)
+            }
+        }
+    }
+
+    /// Publish the events in the dead-letter file and truncate it
+    ///
+    /// The file is left untouched if publishing fails. Returns the number of
+    /// events published.
+    #[allow(dead_code)]
+    pub async fn drain_dead_letter(&self) -> Result<u64> {
+        let Some(path) = &self.config.redis.dead_letter_path else {
+            return Ok(0);
+        };
+
+        let _guard = self.dead_letter_lock.lock().await;
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(DAppError::Internal(format!(
+                    "Failed to read dead-letter file {}: {}",
+                    path, e
+                )))
+            }
+        };
+
+        let mut events = Vec::new();
+        for (line_number, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<SwapEvent>(line) {
+                Ok(event) => events.push(event),
+                Err(e) => warn!(
+                    "Dropping unreadable line {} of dead-letter file {}: {}",
+                    line_number + 1,
+                    path,
+                    e
+                ),
+            }
+        }
+
+        if !events.is_empty() {
+            self.send_batch(&events).await?;
+        }
+        std::fs::File::create(path).map_err(|e| {
+            DAppError::Internal(format!(
+                "Failed to truncate dead-letter file {}: {}",
+                path, e
+            ))
+        })?;
+
+        info!(
+            "Replayed {} events from dead-letter file {}",
+            events.len(),
+            path
+        );
+        Ok(events.len() as u64)
+    }
+
+    /// `(channel, message)` pairs a batch is published as: one per event, or
+    /// a single array per channel when `redis.batch_delivery` is set
+    ///
+    /// A JSON array, or with Borsh a `Vec<SwapEvent>` (u32 length prefix
+    /// followed by the encoded events).
+    fn batch_messages(config: &AppConfig, events: &[SwapEvent]) -> Result<Vec<(String, Vec<u8>)>> {
+        let messages = events
+            .iter()
+            .map(|event| config.event_channel(&event.version))
+            .zip(Self::event_messages(config, events)?);
+        if !config.redis.batch_delivery {
+            return Ok(messages.collect());
+        }
+
+        let mut by_channel: Vec<(String, Vec<Vec<u8>>)> = Vec::new();
+        for (channel, message) in messages {
+            match by_channel
+                .iter_mut()
+                .find(|(existing, _)| *existing == channel)
+            {
+                Some((_, grouped)) => grouped.push(message),
+                None => by_channel.push((channel, vec![message])),
             }
         }
+        Ok(by_channel
+            .into_iter()
+            .map(|(channel, grouped)| {
+                let message = match config.redis.serialization_format {
+                    SerializationFormat::Json => {
+                        [b"[".to_vec(), grouped.join(&b","[..]), b"]".to_vec()].concat()
+                    }
+                    SerializationFormat::Borsh => [
+                        (grouped.len() as u32).to_le_bytes().to_vec(),
+                        grouped.concat(),
+                    ]
+                    .concat(),
+                };
+                (channel, message)
+            })
+            .collect())
+    }
+
+    /// `XADD` appending an event to the stream `key`, trimmed to about `maxlen` entries
+    fn xadd(key: &str, maxlen: Option<usize>, event_id: &str, payload: Vec<u8>) -> redis::Cmd {
+        let mut cmd = redis::cmd("XADD");
+        cmd.arg(key);
+        if let Some(maxlen) = maxlen {
+            cmd.arg("MAXLEN").arg("~").arg(maxlen);
+        }
+        cmd.arg("*")
+            .arg("id")
+            .arg(event_id)
+            .arg("event")
+            .arg(payload);
+        cmd
+    }
+
+    /// Each event encoded in the configured serialization and amount format
+    fn event_messages(config: &AppConfig, events: &[SwapEvent]) -> Result<Vec<Vec<u8>>> {
+        events
+            .iter()
+            .map(|event| {
+                event
+                    .encode(
+                        config.redis.serialization_format,
+                        config.redis.amount_format,
+                    )
+                    .map_err(|e| {
+                        // Use EventParsing error for serialization failures
+                        crate::error::DAppError::Ethereum(
+                            crate::error::EthereumError::EventParsing(format!(
+                                "Failed to serialize event: {}",
+                                e
+                            )),
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// Publish block summaries to the block summary channel
+    pub async fn publish_block_summaries(&self, summaries: &[BlockSummary]) -> Result<()> {
+        if summaries.is_empty() {
+            return Ok(());
+        }
+
+        let channel = self.config.block_summary_channel();
+        debug!(
+            "Publishing {} block summaries to Redis channel {}",
+            summaries.len(),
+            channel
+        );
+
+        let mut conn = self.connection();
+
+        let mut pipe = redis::pipe();
+        for summary in summaries {
+            let summary_json = serde_json::to_string(summary)
+                .map_err(|e| RedisError::Serialization(e.to_string()))?;
+            pipe.publish(&channel, summary_json);
+        }
+
+        let result: RedisResult<()> = pipe.query_async(&mut conn).await;
+        result.map_err(|e| {
+            error!("Failed to publish block summaries: {}", e);
+            RedisError::Publish(e.to_string()).into()
+        })
+    }
+
+    /// Publish completed OHLC candles to the candle channel
+    pub async fn publish_candles(&self, candles: &[OhlcCandle]) -> Result<()> {
+        if candles.is_empty() {
+            return Ok(());
+        }
+
+        let channel = self.config.candle_channel();
+        debug!(
+            "Publishing {} candles to Redis channel {}",
+            candles.len(),
+            channel
+        );
+
+        let mut conn = self.connection();
+
+        let mut pipe = redis::pipe();
+        for candle in candles {
+            let candle_json = serde_json::to_string(candle)
+                .map_err(|e| RedisError::Serialization(e.to_string()))?;
+            pipe.publish(&channel, candle_json);
+        }
+
+        let result: RedisResult<()> = pipe.query_async(&mut conn).await;
+        result.map_err(|e| {
+            error!("Failed to publish candles: {}", e);
+            RedisError::Publish(e.to_string()).into()
+        })
+    }
+
+    /// Publish swaps that failed to parse to `redis.quarantine_channel`, if set
+    pub async fn publish_quarantined(&self, swaps: &[QuarantinedSwap]) -> Result<()> {
+        let Some(channel) = &self.config.redis.quarantine_channel else {
+            return Ok(());
+        };
+        if swaps.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.connection();
+
+        let mut pipe = redis::pipe();
+        for swap in swaps {
+            let swap_json = serde_json::to_string(swap)
+                .map_err(|e| RedisError::Serialization(e.to_string()))?;
+            pipe.publish(channel, swap_json);
+        }
+
+        let result: RedisResult<()> = pipe.query_async(&mut conn).await;
+        result.map_err(|e| {
+            error!("Failed to publish quarantined swaps: {}", e);
+            RedisError::Publish(e.to_string()).into()
+        })
+    }
+
+    /// Publish a control message on every event channel
+    pub async fn publish_control(&self, message: &ControlMessage) -> Result<()> {
+        let message_json =
+            serde_json::to_string(message).map_err(|e| RedisError::Serialization(e.to_string()))?;
+
+        let mut conn = self.connection();
+        for channel in self.config.event_channels() {
+            conn.publish::<_, _, ()>(&channel, &message_json)
+                .await
+                .map_err(|e| {
+                    RedisError::Publish(format!("Failed to publish control message: {}", e))
+                })?;
+            debug!("Published control message to {}", channel);
+        }
+
+        Ok(())
     }
 
     /// Start publishing events from a channel receiver
@@ -170,7 +590,7 @@ impl RedisPublisher {
         info!("Starting Redis publisher for channel: {}", self.channel);
 
         // Test if we can access the channel by checking if it exists
-        let mut conn = (*self.connection_manager).clone();
+        let mut conn = self.connection();
         let channel_exists: RedisResult<bool> = conn.exists(&self.channel).await;
 
         if let Err(e) = channel_exists {
@@ -193,9 +613,82 @@ impl RedisPublisher {
         Ok(())
     }
 
+    /// Write the liveness key with the given TTL
+    pub async fn write_liveness_key(&self, key: &str, ttl_seconds: u64) -> Result<()> {
+        let mut conn = self.connection();
+
+        conn.set_ex::<_, _, ()>(key, chrono::Utc::now().to_rfc3339(), ttl_seconds)
+            .await
+            .map_err(|e| RedisError::Publish(format!("Failed to write liveness key: {}", e)))?;
+
+        debug!("Refreshed liveness key {} (ttl {}s)", key, ttl_seconds);
+        Ok(())
+    }
+
+    /// Periodically refresh the liveness key if `liveness_key_interval_seconds` is set
+    ///
+    /// The key's TTL is slightly longer than the refresh interval, so it expires
+    /// shortly after the refresher stops (e.g. the process dies or the returned
+    /// handle is aborted).
+    pub fn start_liveness_refresher(&self) -> Option<JoinHandle<()>> {
+        let interval_seconds = self.config.redis.liveness_key_interval_seconds;
+        if interval_seconds == 0 {
+            return None;
+        }
+
+        let publisher = self.clone();
+        let key = self.config.liveness_key();
+        let ttl_seconds = liveness_ttl_seconds(interval_seconds);
+        info!(
+            "Refreshing liveness key {} every {}s (ttl {}s)",
+            key, interval_seconds, ttl_seconds
+        );
+
+        Some(tokio::spawn(async move {
+            let mut interval_timer = tokio::time::interval(Duration::from_secs(interval_seconds));
+            loop {
+                interval_timer.tick().await;
+                if let Err(e) = publisher.write_liveness_key(&key, ttl_seconds).await {
+                    error!("Failed to refresh liveness key {}: {}", key, e);
+                }
+            }
+        }))
+    }
+
+    /// Periodically record the channel's subscriber count as a metrics gauge
+    ///
+    /// Runs every `monitoring.metrics_interval_seconds` when metrics are
+    /// enabled and events go out over pub/sub; streams have no subscribers to count.
+    pub fn start_subscriber_gauge(
+        &self,
+        metrics_collector: MetricsCollector,
+    ) -> Option<JoinHandle<()>> {
+        if !self.config.monitoring.enable_metrics
+            || self.config.redis.output_mode != RedisOutputMode::PubSub
+        {
+            return None;
+        }
+
+        let publisher = self.clone();
+        let interval_seconds = self.config.monitoring.metrics_interval_seconds.max(1);
+        Some(tokio::spawn(async move {
+            let mut interval_timer = tokio::time::interval(Duration::from_secs(interval_seconds));
+            loop {
+                interval_timer.tick().await;
+                match publisher.get_subscriber_count().await {
+                    Ok(count) => metrics_collector.record_redis_subscribers(count),
+                    Err(e) => warn!(
+                        "Failed to count subscribers on {}: {}",
+                        publisher.channel, e
+                    ),
+                }
+            }
+        }))
+    }
+
     /// Test Redis connection
     pub async fn test_connection(&self) -> Result<()> {
-        let mut conn = (*self.connection_manager).clone();
+        let mut conn = self.connection();
 
         // Use a simple command to test connection
         let result: RedisResult<()> = conn.set("test_connection", "ok").await;
@@ -223,10 +716,26 @@ impl RedisPublisher {
         }
     }
 
+    /// Delete `key`, e.g. a stale stream or test data
+    ///
+    /// Deleting a key that does not exist is not an error.
+    #[allow(dead_code)]
+    pub async fn delete_key(&self, key: &str) -> Result<()> {
+        let mut conn = self.connection();
+
+        let _: u64 = conn
+            .del(key)
+            .await
+            .map_err(|e| RedisError::Connection(format!("DEL {} failed: {}", key, e)))?;
+        debug!("Deleted Redis key {}", key);
+
+        Ok(())
+    }
+
     /// Get Redis server info
     #[allow(dead_code)]
     pub async fn get_info(&self) -> Result<String> {
-        let mut _conn = (*self.connection_manager).clone();
+        let mut _conn = self.connection();
 
         // Use a simple command to get basic info
         let result: RedisResult<String> = _conn.get("redis_version").await;
@@ -240,14 +749,48 @@ impl RedisPublisher {
         }
     }
 
-    /// Get subscriber count for the channel
-    #[allow(dead_code)]
+    /// Get subscriber count across the event channels
     pub async fn get_subscriber_count(&self) -> Result<u64> {
-        let _conn = (*self.connection_manager).clone();
+        let mut conn = self.connection();
+
+        let counts: Vec<(String, u64)> = redis::cmd("PUBSUB")
+            .arg("NUMSUB")
+            .arg(self.config.event_channels())
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| RedisError::Connection(format!("PUBSUB NUMSUB failed: {}", e)))?;
+
+        Ok(counts.iter().map(|(_, count)| count).sum())
+    }
 
-        // For now, return a default value since pubsub commands are complex
-        // In a real implementation, you might want to use a different approach
-        Ok(0)
+    /// Events not published because too few subscribers were listening
+    #[allow(dead_code)]
+    pub fn skipped_events(&self) -> u64 {
+        self.skipped_events.load(Ordering::Relaxed)
+    }
+
+    /// Whether `redis.min_subscribers` allows publishing, counting skipped events if not
+    async fn has_enough_subscribers(&self, event_count: usize) -> Result<bool> {
+        // Streams keep events until consumers read them
+        let min_subscribers = self.config.redis.min_subscribers;
+        if min_subscribers == 0 || self.config.redis.output_mode != RedisOutputMode::PubSub {
+            return Ok(true);
+        }
+
+        let subscribers = self.get_subscriber_count().await?;
+        if subscribers >= min_subscribers {
+            return Ok(true);
+        }
+
+        let skipped = self
+            .skipped_events
+            .fetch_add(event_count as u64, Ordering::Relaxed)
+            + event_count as u64;
+        warn!(
+            "Skipping {} events: {} subscribers on {} (min {}), {} skipped in total",
+            event_count, subscribers, self.channel, min_subscribers, skipped
+        );
+        Ok(false)
     }
 
     /// Publish with retry logic
@@ -278,6 +821,95 @@ impl RedisPublisher {
     }
 }
 
+/// Append events as newline-delimited JSON
+fn append_dead_letter(path: &str, events: &[SwapEvent]) -> std::io::Result<()> {
+    let mut lines = Vec::new();
+    SwapEvent::serialize_batch_ndjson(events, &mut lines)?;
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(&lines)?;
+    file.flush()
+}
+
+/// Longest wait between retries of a failed publish
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Run `attempt`, retrying failures up to `redis.retry_attempts` times
+///
+/// Delays start at `redis.retry_delay_ms` and double after each retry, up to
+/// `MAX_RETRY_DELAY`. Returns the last error once retries are exhausted.
+async fn retry_with_backoff<T, F, Fut>(
+    config: &AppConfig,
+    operation: &str,
+    mut attempt: F,
+) -> RedisResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = RedisResult<T>>,
+{
+    let mut backoff = ExponentialBackoff::new(
+        Duration::from_millis(config.redis.retry_delay_ms),
+        MAX_RETRY_DELAY,
+        2.0,
+        config.redis.retry_attempts,
+    );
+
+    loop {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) => match backoff.next_delay() {
+                Some(delay) => {
+                    warn!(
+                        "Redis {} failed (retry {}/{} in {:?}): {}",
+                        operation,
+                        backoff.current_attempt(),
+                        config.redis.retry_attempts,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(e),
+            },
+        }
+    }
+}
+
+/// TTL for the liveness key: the refresh interval plus half of it (at least 1s)
+fn liveness_ttl_seconds(interval_seconds: u64) -> u64 {
+    interval_seconds + (interval_seconds / 2).max(1)
+}
+
+#[async_trait]
+impl EventSink for RedisPublisher {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        RedisPublisher::publish_batch(self, events).await
+    }
+
+    async fn publish_block_summaries(&self, summaries: &[BlockSummary]) -> Result<()> {
+        RedisPublisher::publish_block_summaries(self, summaries).await
+    }
+
+    async fn publish_candles(&self, candles: &[OhlcCandle]) -> Result<()> {
+        RedisPublisher::publish_candles(self, candles).await
+    }
+
+    async fn publish_quarantined(&self, swaps: &[QuarantinedSwap]) -> Result<()> {
+        RedisPublisher::publish_quarantined(self, swaps).await
+    }
+
+    async fn publish_control(&self, message: &ControlMessage) -> Result<()> {
+        RedisPublisher::publish_control(self, message).await
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        RedisPublisher::test_connection(self).await
+    }
+}
+
 /// Pool of Redis publishers for load balancing
 #[allow(dead_code)]
 pub struct RedisPublisherPool {
@@ -316,3 +948,114 @@ impl RedisPublisherPool {
         &self.publishers
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{SwapEventBuilder, UniswapVersion};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_batch_delivery_publishes_one_array_message() {
+        let mut config = AppConfig::default();
+        let events: Vec<SwapEvent> = (0..3)
+            .map(|_| SwapEventBuilder::test_builder().unwrap())
+            .collect();
+        assert_eq!(
+            RedisPublisher::batch_messages(&config, &events)
+                .unwrap()
+                .len(),
+            3
+        );
+
+        config.redis.batch_delivery = true;
+        let messages = RedisPublisher::batch_messages(&config, &events).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].0, config.redis.channel);
+        let decoded: Vec<SwapEvent> = serde_json::from_slice(&messages[0].1).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[0].id, events[0].id);
+
+        config.redis.serialization_format = SerializationFormat::Borsh;
+        let messages = RedisPublisher::batch_messages(&config, &events).unwrap();
+        let decoded: Vec<SwapEvent> = borsh::from_slice(&messages[0].1).unwrap();
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[2].id, events[2].id);
+    }
+
+    #[test]
+    fn test_per_version_channels_split_batches() {
+        let mut config = AppConfig::default();
+        config.redis.per_version_channels = true;
+        config.redis.batch_delivery = true;
+        let v3 = SwapEventBuilder::test_builder().unwrap();
+        let mut v2 = v3.clone();
+        v2.version = UniswapVersion::V2;
+
+        let messages = RedisPublisher::batch_messages(&config, &[v2, v3.clone(), v3]).unwrap();
+        let channels: Vec<&str> = messages
+            .iter()
+            .map(|(channel, _)| channel.as_str())
+            .collect();
+        assert_eq!(channels, ["swap_events.v2", "swap_events.v3"]);
+        let decoded: Vec<SwapEvent> = serde_json::from_slice(&messages[1].1).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(
+            config.event_channels(),
+            ["swap_events.v2", "swap_events.v3"]
+        );
+    }
+
+    fn connection_refused() -> redis::RedisError {
+        redis::RedisError::from((redis::ErrorKind::IoError, "Connection refused"))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batch_retried_until_connection_recovers() {
+        let mut config = AppConfig::default();
+        config.redis.retry_attempts = 3;
+        config.redis.retry_delay_ms = 100;
+        let events: Vec<SwapEvent> = (0..2)
+            .map(|_| SwapEventBuilder::test_builder().unwrap())
+            .collect();
+        let failures_left = AtomicU64::new(2);
+        let landed = Mutex::new(Vec::new());
+
+        let started = tokio::time::Instant::now();
+        let result = retry_with_backoff(&config, "batch publish", || async {
+            if failures_left.load(Ordering::Relaxed) > 0 {
+                failures_left.fetch_sub(1, Ordering::Relaxed);
+                return Err(connection_refused());
+            }
+            landed.lock().unwrap().extend(events.iter().cloned());
+            Ok(())
+        })
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(landed.lock().unwrap().len(), 2);
+        // 100ms, then 200ms
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retries_exhausted_return_last_error() {
+        let mut config = AppConfig::default();
+        config.redis.retry_attempts = 2;
+        config.redis.retry_delay_ms = 10;
+        let attempts = AtomicU64::new(0);
+
+        let result: RedisResult<()> = retry_with_backoff(&config, "batch publish", || async {
+            let attempt = attempts.fetch_add(1, Ordering::Relaxed) + 1;
+            Err(redis::RedisError::from((
+                redis::ErrorKind::IoError,
+                "Connection refused",
+                format!("attempt {}", attempt),
+            )))
+        })
+        .await;
+
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+        assert!(result.unwrap_err().to_string().contains("attempt 3"));
+    }
+}
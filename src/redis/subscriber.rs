@@ -0,0 +1,105 @@
+use crate::error::{RedisError, Result};
+use crate::model::{ControlMessage, SwapEvent};
+use futures::stream::{BoxStream, StreamExt};
+use std::collections::VecDeque;
+use tracing::debug;
+
+/// Subscribes to a channel and decodes the swap events published on it
+///
+/// Accepts both one event per message and `redis.batch_delivery` arrays.
+pub struct RedisSubscriber {
+    channel: String,
+    messages: BoxStream<'static, redis::Msg>,
+    pending: VecDeque<SwapEvent>,
+}
+
+#[allow(dead_code)]
+impl RedisSubscriber {
+    /// Connect to `url` and subscribe to `channel`
+    pub async fn connect(url: &str, channel: &str) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| RedisError::Connection(format!("Invalid Redis URL: {}", e)))?;
+        let mut pubsub = client
+            .get_async_connection()
+            .await
+            .map_err(|e| RedisError::Connection(e.to_string()))?
+            .into_pubsub();
+        pubsub.subscribe(channel).await.map_err(|e| {
+            RedisError::Subscribe(format!("Failed to subscribe to {}: {}", channel, e))
+        })?;
+
+        debug!("Subscribed to Redis channel {}", channel);
+        Ok(Self {
+            channel: channel.to_string(),
+            messages: pubsub.into_on_message().boxed(),
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Channel this subscriber listens on
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// Wait for the next event; `None` once the connection is closed
+    ///
+    /// Control messages (such as the startup `hello`) are skipped.
+    pub async fn next_event(&mut self) -> Result<Option<SwapEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+
+            let Some(payload) = self.next_payload().await? else {
+                return Ok(None);
+            };
+
+            if serde_json::from_str::<ControlMessage>(&payload).is_ok() {
+                debug!("Skipping control message on {}", self.channel);
+                continue;
+            }
+
+            self.pending = decode_events(&payload)?.into();
+        }
+    }
+
+    /// Wait for the next raw message payload; `None` once the connection is closed
+    pub async fn next_payload(&mut self) -> Result<Option<String>> {
+        let Some(message) = self.messages.next().await else {
+            return Ok(None);
+        };
+
+        let payload = message
+            .get_payload()
+            .map_err(|e| RedisError::Serialization(e.to_string()))?;
+        Ok(Some(payload))
+    }
+}
+
+/// Events in a payload: a single event object or a batch array
+fn decode_events(payload: &str) -> Result<Vec<SwapEvent>> {
+    let decoded = if payload.trim_start().starts_with('[') {
+        serde_json::from_str(payload)
+    } else {
+        serde_json::from_str(payload).map(|event| vec![event])
+    };
+    decoded.map_err(|e| RedisError::Serialization(format!("Invalid event payload: {}", e)).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+
+    #[test]
+    fn test_decodes_single_and_batched_payloads() {
+        let event = SwapEventBuilder::test_builder().unwrap();
+        let json = serde_json::to_string(&event).unwrap();
+
+        assert_eq!(decode_events(&json).unwrap().len(), 1);
+        let batch = decode_events(&format!("[{},{}]", json, json)).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[1].id, event.id);
+        assert!(decode_events("{\"not\": \"an event\"}").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::{DAppError, Result};
+use crate::model::UniswapVersion;
+use std::fmt;
+
+/// One-shot ingestion of a historical time range
+///
+/// Parsed from `backfill --from <ts> --to <ts> [--version v2|v3] [--pool <address>]`,
+/// with unix-second bounds covering `from < timestamp <= to`. Without
+/// `--version` both subgraphs are backfilled; without `--pool` every pool is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillRequest {
+    pub from: u64,
+    pub to: u64,
+    pub versions: Vec<UniswapVersion>,
+    pub pool: Option<String>,
+}
+
+impl BackfillRequest {
+    /// Parse the `backfill` subcommand from the arguments after the program name
+    ///
+    /// Returns `None` when the first argument is not `backfill`.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Option<Result<Self>> {
+        if args.next().as_deref() != Some("backfill") {
+            return None;
+        }
+        Some(Self::parse_options(args))
+    }
+
+    fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut from = None;
+        let mut to = None;
+        let mut versions = vec![UniswapVersion::V2, UniswapVersion::V3];
+        let mut pool = None;
+
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let value = inline
+                .or_else(|| args.next())
+                .ok_or_else(|| usage(format!("{} needs a value", flag)))?;
+            match flag.as_str() {
+                "--from" => from = Some(parse_timestamp(&flag, &value)?),
+                "--to" => to = Some(parse_timestamp(&flag, &value)?),
+                "--version" => {
+                    versions = match value.to_ascii_lowercase().as_str() {
+                        "v2" => vec![UniswapVersion::V2],
+                        "v3" => vec![UniswapVersion::V3],
+                        _ => return Err(usage(format!("unknown version {:?}", value))),
+                    }
+                }
+                "--pool" => pool = Some(value),
+                _ => return Err(usage(format!("unknown option {}", flag))),
+            }
+        }
+
+        let from = from.ok_or_else(|| usage("--from is required".to_string()))?;
+        let to = to.ok_or_else(|| usage("--to is required".to_string()))?;
+        if from >= to {
+            return Err(usage(format!(
+                "--from ({}) must be before --to ({})",
+                from, to
+            )));
+        }
+
+        Ok(Self {
+            from,
+            to,
+            versions,
+            pool,
+        })
+    }
+}
+
+fn parse_timestamp(flag: &str, value: &str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|_| usage(format!("{} expects unix seconds, got {:?}", flag, value)))
+}
+
+fn usage(problem: String) -> DAppError {
+    DAppError::Config(format!(
+        "{}; usage: backfill --from <unix seconds> --to <unix seconds> [--version v2|v3] [--pool <address>]",
+        problem
+    ))
+}
+
+/// Outcome of a backfill run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackfillSummary {
+    /// Swaps returned by the subgraphs for the range
+    pub fetched: usize,
+    /// Events published to the sink
+    pub published: usize,
+    /// Swaps that failed to parse, plus batches that failed to publish
+    pub errors: usize,
+}
+
+impl fmt::Display for BackfillSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} swaps fetched, {} events published, {} errors",
+            self.fetched, self.published, self.errors
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Option<Result<BackfillRequest>> {
+        BackfillRequest::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parses_backfill_subcommand() {
+        let request = parse(&[
+            "backfill",
+            "--from",
+            "1700000000",
+            "--to=1700003600",
+            "--version",
+            "V3",
+            "--pool",
+            "0xpool",
+        ])
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            request,
+            BackfillRequest {
+                from: 1_700_000_000,
+                to: 1_700_003_600,
+                versions: vec![UniswapVersion::V3],
+                pool: Some("0xpool".to_string()),
+            }
+        );
+
+        let both = parse(&["backfill", "--from", "1", "--to", "2"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(both.versions, vec![UniswapVersion::V2, UniswapVersion::V3]);
+    }
+
+    #[test]
+    fn test_other_commands_are_not_backfills() {
+        assert!(parse(&[]).is_none());
+        assert!(parse(&["--capture-to-file", "events.ndjson"]).is_none());
+    }
+
+    #[test]
+    fn test_rejects_invalid_backfill_options() {
+        for args in [
+            &["backfill", "--to", "2"][..],
+            &["backfill", "--from", "2", "--to", "1"],
+            &["backfill", "--from", "yesterday", "--to", "2"],
+            &["backfill", "--from", "1", "--to", "2", "--version", "v4"],
+            &["backfill", "--from", "1", "--to", "2", "--limit", "5"],
+            &["backfill", "--from", "1", "--to"],
+        ] {
+            assert!(
+                matches!(parse(args), Some(Err(DAppError::Config(_)))),
+                "accepted {:?}",
+                args
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::config::AppConfig;
+use crate::model::{BlockSummary, SwapEvent, UniswapVersion};
+use crate::service::cursor::CursorStore;
+use crate::service::swap_collector::PoolFilter;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Accumulates delivered swaps into one summary per block across polling tasks
+///
+/// Each task reports the block through which it has delivered every swap it
+/// covers. A block's summary is emitted once every tracked task has reported
+/// progress up to it, so the V2 and V3 tasks and consecutive polls all add to
+/// a single summary. Swaps for a block that was already emitted are ignored.
+pub struct BlockSummaryAggregator {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Cursor keys of the tasks whose progress gates emission
+    tasks: Vec<String>,
+    /// Block through which each task has delivered every swap
+    progress: HashMap<String, u64>,
+    open: BTreeMap<u64, BlockSummary>,
+    /// Every block up to this one has been emitted
+    emitted_through: Option<u64>,
+}
+
+impl BlockSummaryAggregator {
+    /// Create an aggregator if `redis.emit_block_summaries` is set
+    ///
+    /// Tracks the default V2 and V3 tasks until `track` names others.
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        config.redis.emit_block_summaries.then(|| {
+            Self::new(
+                [UniswapVersion::V2, UniswapVersion::V3]
+                    .iter()
+                    .map(|version| CursorStore::key(version, &PoolFilter::All))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Create an aggregator gated on the tasks with cursor keys `tasks`
+    pub fn new(tasks: Vec<String>) -> Self {
+        Self {
+            state: Mutex::new(State {
+                tasks,
+                ..State::default()
+            }),
+        }
+    }
+
+    /// Gate emission on the tasks with cursor keys `tasks` instead
+    pub fn track(&self, tasks: Vec<String>) {
+        self.state.lock().unwrap().tasks = tasks;
+    }
+
+    /// Add swaps delivered by `task`, which has now delivered every swap
+    /// through `through_block`, and return the summaries of blocks every
+    /// task has delivered, oldest first
+    pub fn add_events(
+        &self,
+        task: &str,
+        events: &[SwapEvent],
+        through_block: Option<u64>,
+    ) -> Vec<BlockSummary> {
+        let mut state = self.state.lock().unwrap();
+
+        for summary in BlockSummary::from_events(events) {
+            if state
+                .emitted_through
+                .is_some_and(|emitted| summary.block <= emitted)
+            {
+                debug!(
+                    "Ignoring {} late swaps for emitted block {}",
+                    summary.swap_count, summary.block
+                );
+                continue;
+            }
+            match state.open.entry(summary.block) {
+                Entry::Vacant(entry) => {
+                    entry.insert(summary);
+                }
+                Entry::Occupied(mut entry) => entry.get_mut().merge(summary),
+            }
+        }
+
+        if let Some(through_block) = through_block {
+            let progress = state.progress.entry(task.to_string()).or_default();
+            *progress = (*progress).max(through_block);
+        }
+
+        // A task without progress holds back every block
+        let Some(final_through) = state
+            .tasks
+            .iter()
+            .map(|task| state.progress.get(task).copied())
+            .min()
+            .flatten()
+        else {
+            return Vec::new();
+        };
+
+        let pending = state.open.split_off(&(final_through + 1));
+        let completed = std::mem::replace(&mut state.open, pending);
+        state.emitted_through = state.emitted_through.max(Some(final_through));
+        completed.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+
+    fn swap(block: u64, pool: &str) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.block_number = block;
+        event.pool_address = pool.to_string();
+        event
+    }
+
+    #[test]
+    fn test_block_emitted_once_every_task_delivered_it() {
+        let aggregator = BlockSummaryAggregator::new(vec!["v2".to_string(), "v3".to_string()]);
+
+        // V2 is through block 101, but V3 has not reported yet
+        let emitted = aggregator.add_events("v2", &[swap(100, "0xa"), swap(101, "0xa")], Some(101));
+        assert!(emitted.is_empty());
+
+        // V3 adds to block 101 and is only through it
+        let emitted = aggregator.add_events("v3", &[swap(101, "0xb"), swap(102, "0xb")], Some(101));
+        assert_eq!(
+            emitted
+                .iter()
+                .map(|summary| (
+                    summary.block,
+                    summary.swap_count,
+                    summary.pools_touched.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (100, 1, vec!["0xa".to_string()]),
+                (101, 2, vec!["0xa".to_string(), "0xb".to_string()]),
+            ]
+        );
+
+        // A later V2 poll completes block 102 with its own swap
+        let emitted = aggregator.add_events("v2", &[swap(102, "0xa")], Some(103));
+        assert!(emitted.is_empty());
+        let emitted = aggregator.add_events("v3", &[], Some(103));
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(emitted[0].block, 102);
+        assert_eq!(emitted[0].swap_count, 2);
+    }
+
+    #[test]
+    fn test_late_swaps_for_emitted_blocks_ignored() {
+        let aggregator = BlockSummaryAggregator::new(vec!["v2".to_string()]);
+        assert_eq!(
+            aggregator
+                .add_events("v2", &[swap(100, "0xa")], Some(100))
+                .len(),
+            1
+        );
+
+        assert!(aggregator
+            .add_events("v2", &[swap(100, "0xa")], Some(100))
+            .is_empty());
+    }
+}
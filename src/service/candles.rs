@@ -0,0 +1,192 @@
+use crate::config::AppConfig;
+use crate::model::{OhlcCandle, SwapEvent};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::debug;
+
+/// Accumulates swaps into per-pool OHLC candles
+///
+/// Each pool has at most one open candle. A candle is completed once a swap
+/// for a later interval arrives or the clock passes the end of its interval,
+/// whichever comes first. Swaps older than the pool's open candle are
+/// ignored, since their candle has already been emitted.
+pub struct CandleAggregator {
+    interval_seconds: i64,
+    open: Mutex<HashMap<String, OhlcCandle>>,
+}
+
+impl CandleAggregator {
+    /// Create an aggregator if `redis.emit_candles` is set
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        config
+            .redis
+            .emit_candles
+            .then(|| Self::new(config.redis.candle_interval_seconds))
+    }
+
+    /// Create an aggregator with candles of `interval_seconds`
+    pub fn new(interval_seconds: u64) -> Self {
+        Self {
+            interval_seconds: interval_seconds.max(1) as i64,
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Add swaps and return the candles completed as of `now`, oldest first
+    pub fn add_events(&self, events: &[SwapEvent], now: DateTime<Utc>) -> Vec<OhlcCandle> {
+        let mut open = self.open.lock().unwrap();
+        let mut completed = Vec::new();
+
+        for event in events {
+            let Some((price, volume)) = Self::oriented_price(event) else {
+                debug!("Skipping swap {} without an execution price", event.id);
+                continue;
+            };
+            let interval_start = self.interval_start(event.timestamp);
+            let pool = event.pool_address.to_lowercase();
+
+            match open.get_mut(&pool) {
+                Some(candle) if candle.interval_start == interval_start => {
+                    candle.high = candle.high.max(price);
+                    candle.low = candle.low.min(price);
+                    candle.close = price;
+                    candle.volume += volume;
+                    candle.count += 1;
+                    continue;
+                }
+                Some(candle) if candle.interval_start > interval_start => {
+                    debug!("Ignoring late swap {} for closed candle", event.id);
+                    continue;
+                }
+                _ => {}
+            }
+
+            let candle = OhlcCandle {
+                pool: pool.clone(),
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume,
+                count: 1,
+                interval_start,
+            };
+            if let Some(previous) = open.insert(pool, candle) {
+                completed.push(previous);
+            }
+        }
+
+        let current_start = self.interval_start(now);
+        let expired: Vec<String> = open
+            .iter()
+            .filter(|(_, candle)| candle.interval_start < current_start)
+            .map(|(pool, _)| pool.clone())
+            .collect();
+        completed.extend(expired.iter().filter_map(|pool| open.remove(pool)));
+
+        completed.sort_by(|a, b| {
+            a.interval_start
+                .cmp(&b.interval_start)
+                .then_with(|| a.pool.cmp(&b.pool))
+        });
+        completed
+    }
+
+    fn interval_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = timestamp.timestamp();
+        let start = seconds - seconds.rem_euclid(self.interval_seconds);
+        DateTime::from_timestamp(start, 0).unwrap_or(timestamp)
+    }
+
+    /// Price in the second token per first token (by lowercase address), and
+    /// the amount of the first token traded
+    fn oriented_price(event: &SwapEvent) -> Option<(f64, f64)> {
+        let execution_price = event.execution_price()?;
+        let amount_in: f64 = event.amount_in.parse().ok()?;
+        let amount_out: f64 = event.amount_out.parse().ok()?;
+
+        if event.token_in.address.to_lowercase() <= event.token_out.address.to_lowercase() {
+            Some((execution_price, amount_in))
+        } else {
+            Some((1.0 / execution_price, amount_out))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+    use chrono::TimeZone;
+
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_040 + seconds, 0).unwrap()
+    }
+
+    /// Swap of `weth` for USDC at `price` USDC per WETH; `sell_weth` picks the direction
+    fn swap(seconds: i64, price: f64, weth: f64, sell_weth: bool) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.pool_address = "0xPOOL".to_string();
+        event.timestamp = at(seconds);
+        let usdc = (price * weth).to_string();
+        let weth = weth.to_string();
+        if sell_weth {
+            event.token_in.address = WETH.to_string();
+            event.token_out.address = USDC.to_string();
+            event.amount_in = weth;
+            event.amount_out = usdc;
+        } else {
+            event.token_in.address = USDC.to_string();
+            event.token_out.address = WETH.to_string();
+            event.amount_in = usdc;
+            event.amount_out = weth;
+        }
+        event
+    }
+
+    #[test]
+    fn test_candle_tracks_open_high_low_close() {
+        let aggregator = CandleAggregator::new(60);
+        // 1_700_000_040 is a minute boundary
+        let swaps = vec![
+            swap(0, 2000.0, 1.0, true),
+            swap(10, 2100.0, 0.5, false),
+            swap(20, 1950.0, 2.0, true),
+            swap(59, 2050.0, 1.0, false),
+        ];
+
+        assert!(aggregator.add_events(&swaps, at(59)).is_empty());
+
+        // The next minute's swap completes the candle
+        let completed = aggregator.add_events(&[swap(61, 2060.0, 1.0, true)], at(61));
+        assert_eq!(completed.len(), 1);
+        let candle = &completed[0];
+        assert_eq!(candle.pool, "0xpool");
+        assert_eq!(candle.interval_start, at(0));
+        assert_eq!(candle.count, 4);
+        // USDC sorts before WETH, so prices are WETH per USDC
+        let close_to = |a: f64, b: f64| (a - b).abs() < 1e-12;
+        assert!(close_to(candle.open, 1.0 / 2000.0));
+        assert!(close_to(candle.high, 1.0 / 1950.0));
+        assert!(close_to(candle.low, 1.0 / 2100.0));
+        assert!(close_to(candle.close, 1.0 / 2050.0));
+        let usdc_volume = 2000.0 + 2100.0 * 0.5 + 1950.0 * 2.0 + 2050.0;
+        assert!(close_to(candle.volume, usdc_volume));
+    }
+
+    #[test]
+    fn test_candle_completed_once_interval_passes() {
+        let aggregator = CandleAggregator::new(60);
+        aggregator.add_events(&[swap(5, 2000.0, 1.0, true)], at(5));
+
+        assert!(aggregator.add_events(&[], at(59)).is_empty());
+        let completed = aggregator.add_events(&[], at(60));
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].count, 1);
+        assert!(aggregator.add_events(&[], at(120)).is_empty());
+    }
+}
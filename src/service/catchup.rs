@@ -0,0 +1,180 @@
+use crate::config::AppConfig;
+use crate::model::SwapEvent;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use tracing::info;
+
+/// Orders the handoff from backfill to live collection
+///
+/// Live events are held back until the backfill has reached the live cursor
+/// (the timestamp live collection started from). The buffer is then flushed
+/// in `(timestamp, id)` order, skipping swaps the backfill already delivered,
+/// and later live events pass straight through. This keeps delivery
+/// monotonic in time across the handoff.
+#[derive(Debug)]
+pub struct OrderedCatchup {
+    live_cursor: DateTime<Utc>,
+    buffer: Vec<SwapEvent>,
+    delivered_at_cursor: HashSet<String>,
+    caught_up: bool,
+}
+
+impl OrderedCatchup {
+    /// Create a catch-up buffer for live collection starting at `live_cursor`
+    pub fn new(live_cursor: DateTime<Utc>) -> Self {
+        Self {
+            live_cursor,
+            buffer: Vec::new(),
+            delivered_at_cursor: HashSet::new(),
+            caught_up: false,
+        }
+    }
+
+    /// Create a catch-up buffer if `subgraph.ordered_catchup` is enabled
+    pub fn from_config(config: &AppConfig, live_cursor: DateTime<Utc>) -> Option<Self> {
+        config
+            .subgraph
+            .ordered_catchup
+            .then(|| Self::new(live_cursor))
+    }
+
+    /// Whether the backfill has reached the live cursor
+    #[allow(dead_code)]
+    pub fn is_caught_up(&self) -> bool {
+        self.caught_up
+    }
+
+    /// Number of live events waiting for the backfill
+    #[allow(dead_code)]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Accept a batch of live events, returning those that may be published now
+    pub fn push_live(&mut self, events: Vec<SwapEvent>) -> Vec<SwapEvent> {
+        if self.caught_up {
+            return events;
+        }
+
+        self.buffer.extend(events);
+        Vec::new()
+    }
+
+    /// Accept a batch of backfill events, returning those to publish in order
+    ///
+    /// Once a backfill event at or past the live cursor is seen, the buffered
+    /// live events are appended to the output.
+    pub fn push_backfill(&mut self, mut events: Vec<SwapEvent>) -> Vec<SwapEvent> {
+        sort_events(&mut events);
+        if self.caught_up {
+            return events;
+        }
+
+        for event in &events {
+            if event.timestamp >= self.live_cursor {
+                self.delivered_at_cursor.insert(event.id.clone());
+            }
+        }
+
+        let reached_cursor = events
+            .last()
+            .is_some_and(|event| event.timestamp >= self.live_cursor);
+        if reached_cursor {
+            events.extend(self.flush());
+        }
+
+        events
+    }
+
+    /// Mark the backfill as finished and flush any buffered live events
+    pub fn complete_backfill(&mut self) -> Vec<SwapEvent> {
+        if self.caught_up {
+            return Vec::new();
+        }
+
+        self.flush()
+    }
+
+    fn flush(&mut self) -> Vec<SwapEvent> {
+        self.caught_up = true;
+
+        let mut buffered = std::mem::take(&mut self.buffer);
+        let delivered = std::mem::take(&mut self.delivered_at_cursor);
+        buffered.retain(|event| !delivered.contains(&event.id));
+        sort_events(&mut buffered);
+
+        info!(
+            "Backfill caught up with live cursor {}, flushing {} buffered live events",
+            self.live_cursor,
+            buffered.len()
+        );
+        buffered
+    }
+}
+
+/// Sort events by `(timestamp, id)`
+fn sort_events(events: &mut [SwapEvent]) {
+    events.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.id.cmp(&b.id)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+    use chrono::{Duration, TimeZone};
+
+    fn event_at(id: &str, seconds: i64) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.id = id.to_string();
+        event.timestamp = Utc.timestamp_opt(1_700_000_000, 0).unwrap() + Duration::seconds(seconds);
+        event
+    }
+
+    fn ids(events: &[SwapEvent]) -> Vec<&str> {
+        events.iter().map(|event| event.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_live_events_wait_for_backfill_handoff() {
+        let live_cursor = Utc.timestamp_opt(1_700_000_100, 0).unwrap();
+        let mut catchup = OrderedCatchup::new(live_cursor);
+
+        // Live collection starts before the backfill has caught up
+        assert!(catchup
+            .push_live(vec![event_at("live-2", 110), event_at("live-1", 100)])
+            .is_empty());
+
+        // Backfill batches interleave with live ones and are published immediately
+        assert_eq!(
+            ids(&catchup.push_backfill(vec![event_at("back-2", 20), event_at("back-1", 10)])),
+            vec!["back-1", "back-2"]
+        );
+        assert!(catchup.push_live(vec![event_at("live-3", 120)]).is_empty());
+        assert_eq!(catchup.buffered(), 3);
+
+        // The final backfill batch reaches the cursor and overlaps the live stream
+        let handoff = catchup.push_backfill(vec![event_at("back-3", 90), event_at("live-1", 100)]);
+        assert_eq!(ids(&handoff), vec!["back-3", "live-1", "live-2", "live-3"]);
+        assert!(catchup.is_caught_up());
+
+        // Afterwards live events stream straight through
+        assert_eq!(
+            ids(&catchup.push_live(vec![event_at("live-4", 130)])),
+            vec!["live-4"]
+        );
+    }
+
+    #[test]
+    fn test_complete_backfill_flushes_buffer() {
+        let live_cursor = Utc.timestamp_opt(1_700_000_100, 0).unwrap();
+        let mut catchup = OrderedCatchup::new(live_cursor);
+
+        catchup.push_live(vec![event_at("live-1", 100)]);
+        assert_eq!(
+            ids(&catchup.push_backfill(vec![event_at("back-1", 50)])),
+            vec!["back-1"]
+        );
+        assert_eq!(ids(&catchup.complete_backfill()), vec!["live-1"]);
+        assert!(catchup.complete_backfill().is_empty());
+    }
+}
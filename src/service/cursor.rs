@@ -0,0 +1,190 @@
+use crate::config::AppConfig;
+use crate::model::UniswapVersion;
+use crate::service::swap_collector::PoolFilter;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Resume position in a swap stream: the last delivered `(timestamp, id)`
+///
+/// Several swaps can share a timestamp, so the id breaks ties. Queries resume
+/// strictly after this pair, which neither skips nor repeats swaps that share
+/// the boundary timestamp.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapCursor {
+    pub timestamp: u64,
+    pub id: String,
+}
+
+impl SwapCursor {
+    /// Cursor position of a raw subgraph swap
+    pub fn of_swap(swap_data: &Value) -> Option<Self> {
+        let timestamp = match swap_data.get("timestamp")? {
+            Value::String(timestamp) => timestamp.parse().ok()?,
+            Value::Number(timestamp) => timestamp.as_u64()?,
+            _ => return None,
+        };
+        let id = swap_data.get("id")?.as_str()?.to_string();
+
+        Some(Self { timestamp, id })
+    }
+
+    /// Add the "strictly after this cursor" condition to a swaps `where` filter
+    ///
+    /// Uses `timestamp_gt OR (timestamp AND id_gt)` with the base filter
+    /// applied to both branches.
+    pub fn where_clause(&self, base: Value) -> Value {
+        let mut later = base.clone();
+        let mut same_timestamp = base;
+        later["timestamp_gt"] = self.timestamp.to_string().into();
+        same_timestamp["timestamp"] = self.timestamp.to_string().into();
+        same_timestamp["id_gt"] = self.id.clone().into();
+
+        serde_json::json!({ "or": [later, same_timestamp] })
+    }
+
+    fn cmp_key(&self, other: &Self) -> Ordering {
+        self.timestamp
+            .cmp(&other.timestamp)
+            .then_with(|| self.id.cmp(&other.id))
+    }
+}
+
+/// Composite cursors per polling task, optionally persisted to a JSON file
+pub struct CursorStore {
+    enabled: bool,
+    /// Seconds before the first poll that a missing cursor is seeded at
+    seed_lookback: Option<u64>,
+    path: Option<String>,
+    cursors: Mutex<HashMap<String, SwapCursor>>,
+}
+
+impl CursorStore {
+    /// Create a store from `subgraph.composite_cursor` / `subgraph.cursor_path`
+    ///
+    /// Cursors saved by a previous run are loaded so collection resumes after
+    /// the last delivered swap.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let path = config.subgraph.cursor_path.clone();
+        let cursors = path
+            .as_deref()
+            .filter(|_| config.subgraph.composite_cursor)
+            .map(Self::load)
+            .unwrap_or_default();
+
+        Self {
+            enabled: config.subgraph.composite_cursor,
+            seed_lookback: config
+                .subgraph
+                .seed_cursor_from_now
+                .then_some(config.subgraph.polling_interval_seconds),
+            path,
+            cursors: Mutex::new(cursors),
+        }
+    }
+
+    /// Cursor key for a polling task
+    pub fn key(version: &UniswapVersion, pool_filter: &PoolFilter) -> String {
+        match pool_filter {
+            PoolFilter::Only(pools) => format!("{}:{}", version, pools.join(",")),
+            PoolFilter::All | PoolFilter::Exclude(_) => version.to_string(),
+        }
+    }
+
+    /// Current cursor for `key`, if cursoring is enabled and one is recorded
+    pub fn get(&self, key: &str) -> Option<SwapCursor> {
+        if !self.enabled {
+            return None;
+        }
+        self.cursors.lock().unwrap().get(key).cloned()
+    }
+
+    /// Cursor a poll at `now` resumes from
+    ///
+    /// Like `get`, but with `subgraph.seed_cursor_from_now` a missing cursor is
+    /// seeded one polling interval before `now`, so the first poll only picks
+    /// up recent swaps. The seed is kept until a delivered swap replaces it.
+    pub fn resume_from(&self, key: &str, now: DateTime<Utc>) -> Option<SwapCursor> {
+        let cursor = self.get(key);
+        let (None, true, Some(lookback)) = (&cursor, self.enabled, self.seed_lookback) else {
+            return cursor;
+        };
+
+        let seed = SwapCursor {
+            timestamp: (now.timestamp().max(0) as u64).saturating_sub(lookback),
+            id: String::new(),
+        };
+        debug!("Seeding {} cursor at {:?}", key, seed);
+        let mut cursors = self.cursors.lock().unwrap();
+        Some(cursors.entry(key.to_string()).or_insert(seed).clone())
+    }
+
+    /// Raw swaps after `cursor`, in `(timestamp, id)` order
+    ///
+    /// Guards against endpoints that ignore the cursor filter. Without a cursor
+    /// the swaps are returned unchanged.
+    pub fn unseen<'a>(cursor: Option<&SwapCursor>, swaps: &'a [Value]) -> Vec<&'a Value> {
+        let Some(cursor) = cursor else {
+            return swaps.iter().collect();
+        };
+
+        let mut unseen: Vec<(SwapCursor, &Value)> = swaps
+            .iter()
+            .filter_map(|swap| SwapCursor::of_swap(swap).map(|position| (position, swap)))
+            .filter(|(position, _)| position.cmp_key(cursor) == Ordering::Greater)
+            .collect();
+        unseen.sort_by(|(a, _), (b, _)| a.cmp_key(b));
+        unseen.into_iter().map(|(_, swap)| swap).collect()
+    }
+
+    /// Move the cursor for `key` to the latest of `swaps` and persist it
+    pub fn advance(&self, key: &str, swaps: &[&Value]) {
+        if !self.enabled {
+            return;
+        }
+
+        let Some(latest) = swaps
+            .iter()
+            .filter_map(|swap| SwapCursor::of_swap(swap))
+            .max_by(|a, b| a.cmp_key(b))
+        else {
+            return;
+        };
+
+        let mut cursors = self.cursors.lock().unwrap();
+        let current = cursors.get(key);
+        if current.is_some_and(|current| current.cmp_key(&latest) != Ordering::Less) {
+            return;
+        }
+
+        debug!("Advancing {} cursor to {:?}", key, latest);
+        cursors.insert(key.to_string(), latest);
+
+        if let Some(path) = &self.path {
+            if let Err(e) = Self::save(path, &cursors) {
+                warn!("Failed to persist swap cursors to {}: {}", path, e);
+            }
+        }
+    }
+
+    fn load(path: &str) -> HashMap<String, SwapCursor> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable swap cursor file {}: {}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    fn save(path: &str, cursors: &HashMap<String, SwapCursor>) -> std::io::Result<()> {
+        let contents = serde_json::to_string_pretty(cursors)?;
+        let tmp_path = format!("{}.tmp", path);
+        std::fs::write(&tmp_path, contents)?;
+        std::fs::rename(tmp_path, path)
+    }
+}
@@ -0,0 +1,478 @@
+use crate::config::AppConfig;
+use crate::model::SwapEvent;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+/// Fixed-size bloom filter over string ids
+///
+/// Uses FNV-1a with double hashing so a persisted filter stays valid across
+/// builds (std's hashers make no such guarantee).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    inserted: usize,
+}
+
+impl BloomFilter {
+    /// Size a filter for `capacity` ids at `false_positive_rate`
+    fn with_rate(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let rate = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = (-(capacity * rate.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / capacity) * ln2).round().max(1.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+            inserted: 0,
+        }
+    }
+
+    fn positions(&self, id: &str) -> impl Iterator<Item = u64> + '_ {
+        let first = fnv1a(id.as_bytes(), 0xcbf2_9ce4_8422_2325);
+        let second = fnv1a(id.as_bytes(), 0x8422_2325_cbf2_9ce4) | 1;
+        (0..self.num_hashes as u64)
+            .map(move |i| first.wrapping_add(i.wrapping_mul(second)) % self.num_bits)
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.positions(id)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    fn insert(&mut self, id: &str) {
+        let positions: Vec<u64> = self.positions(id).collect();
+        for bit in positions {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.inserted += 1;
+    }
+}
+
+fn fnv1a(bytes: &[u8], offset_basis: u64) -> u64 {
+    bytes.iter().fold(offset_basis, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Two filter generations: ids are checked against both and inserted into
+/// the current one, which replaces the previous once it reaches capacity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SeenIds {
+    current: BloomFilter,
+    previous: Option<BloomFilter>,
+}
+
+impl SeenIds {
+    fn contains(&self, id: &str) -> bool {
+        self.current.contains(id) || self.previous.as_ref().is_some_and(|f| f.contains(id))
+    }
+}
+
+/// Suppresses events whose id was already published, across restarts
+///
+/// Seen ids live in a bloom filter, optionally persisted to `dedup.path` by
+/// `persist` (after each collection cycle and at shutdown) and reloaded on
+/// startup. The
+/// filter trades a configurable false-positive rate (an unseen event is
+/// occasionally dropped) for a small, fixed footprint. Two generations of
+/// `dedup.capacity` ids are remembered, so old ids eventually age out.
+///
+/// With `dedup.cross_version`, events are keyed on (chain, transaction hash,
+/// log index) rather than their id, which includes the version, so one
+/// on-chain log is published once whichever subgraph reported it.
+///
+/// Keys are reserved before publishing, so a log reported by two tasks at
+/// once is only published by the first; the reservation becomes a seen id
+/// once delivered or is released if publishing fails.
+pub struct EventDeduplicator {
+    path: Option<String>,
+    /// Chain id for cross-version keys, `None` to key on the event id
+    cross_version_chain: Option<u64>,
+    capacity: usize,
+    false_positive_rate: f64,
+    state: Mutex<DedupState>,
+    /// Held while the filter is written, so writes never interleave
+    persisting: tokio::sync::Mutex<()>,
+}
+
+struct DedupState {
+    seen: SeenIds,
+    /// Keys of events being published but not yet recorded
+    reserved: HashSet<String>,
+    /// Ids were recorded since the filter was last persisted
+    dirty: bool,
+}
+
+impl EventDeduplicator {
+    /// Create a deduplicator if `dedup.enabled` is set, loading any saved state
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let dedup = &config.dedup;
+        if !dedup.enabled {
+            return None;
+        }
+
+        let fresh = || SeenIds {
+            current: BloomFilter::with_rate(dedup.capacity, dedup.false_positive_rate),
+            previous: None,
+        };
+        let seen = dedup
+            .path
+            .as_deref()
+            .and_then(Self::load)
+            .unwrap_or_else(fresh);
+
+        Some(Self {
+            path: dedup.path.clone(),
+            cross_version_chain: dedup.cross_version.then_some(config.subgraph.chain_id),
+            capacity: dedup.capacity.max(1),
+            false_positive_rate: dedup.false_positive_rate,
+            state: Mutex::new(DedupState {
+                seen,
+                reserved: HashSet::new(),
+                dirty: false,
+            }),
+            persisting: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// Whether `id` was (probably) published before
+    #[allow(dead_code)]
+    pub fn is_duplicate(&self, id: &str) -> bool {
+        self.state.lock().unwrap().seen.contains(id)
+    }
+
+    /// Key an event is remembered by
+    ///
+    /// Cross-version keys need the log index; events without one fall back
+    /// to their id.
+    fn key<'a>(&self, event: &'a SwapEvent) -> Cow<'a, str> {
+        match (self.cross_version_chain, event.log_index) {
+            (Some(chain_id), Some(log_index)) => {
+                // Subgraph swap ids are `<tx hash>-<log index>` (V2) or `<tx hash>#<log index>` (V3)
+                let tx_hash = event
+                    .transaction_hash
+                    .split(['-', '#'])
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                Cow::Owned(format!("{}:{}:{}", chain_id, tx_hash, log_index))
+            }
+            _ => Cow::Borrowed(&event.id),
+        }
+    }
+
+    /// Drop events that were already published or are being published,
+    /// and reserve the rest; returns how many were dropped
+    ///
+    /// Every kept event must be passed to `record_published` once delivered
+    /// or to `release` if publishing fails.
+    pub fn reserve_unseen(&self, events: &mut Vec<SwapEvent>) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let before = events.len();
+        events.retain(|event| {
+            let key = self.key(event);
+            !state.seen.contains(&key) && state.reserved.insert(key.into_owned())
+        });
+        let dropped = before - events.len();
+        if dropped > 0 {
+            debug!("Dropped {} already published events", dropped);
+        }
+        dropped
+    }
+
+    /// Give up the reservations of events that were not published
+    pub fn release(&self, events: &[SwapEvent]) {
+        let mut state = self.state.lock().unwrap();
+        for event in events {
+            state.reserved.remove(self.key(event).as_ref());
+        }
+    }
+
+    /// Remember published events until the next `persist`
+    pub fn record_published(&self, events: &[SwapEvent]) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        for event in events {
+            let key = self.key(event);
+            let seen = &mut state.seen;
+            if seen.current.inserted >= self.capacity {
+                let next = BloomFilter::with_rate(self.capacity, self.false_positive_rate);
+                seen.previous = Some(std::mem::replace(&mut seen.current, next));
+            }
+            seen.current.insert(&key);
+            state.reserved.remove(key.as_ref());
+        }
+        state.dirty = true;
+    }
+
+    /// Write the filter to `dedup.path` if ids were recorded since the last write
+    ///
+    /// The filter is several hundred KB of JSON at the default capacity, so
+    /// it is only copied under the lock; serializing and writing run on the
+    /// blocking pool. A failed write is retried by the next call.
+    pub async fn persist(&self) {
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        let _persisting = self.persisting.lock().await;
+        let seen = {
+            let mut state = self.state.lock().unwrap();
+            if !std::mem::take(&mut state.dirty) {
+                return;
+            }
+            state.seen.clone()
+        };
+
+        let saved = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || Self::save(&path, &seen)).await
+        };
+        let error = match saved {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        warn!("Failed to persist seen event ids to {}: {}", path, error);
+        self.state.lock().unwrap().dirty = true;
+    }
+
+    fn load(path: &str) -> Option<SeenIds> {
+        let contents = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str::<SeenIds>(&contents) {
+            Ok(seen) => {
+                info!(
+                    "Loaded seen event ids from {} ({} in current generation)",
+                    path, seen.current.inserted
+                );
+                Some(seen)
+            }
+            Err(e) => {
+                warn!("Ignoring unreadable seen event id file {}: {}", path, e);
+                None
+            }
+        }
+    }
+
+    fn save(path: &str, seen: &SeenIds) -> std::io::Result<()> {
+        let contents = serde_json::to_string(seen)?;
+        let tmp_path = format!("{}.tmp", path);
+        std::fs::write(&tmp_path, contents)?;
+        std::fs::rename(tmp_path, path)
+    }
+}
+
+/// Exact set of the most recently published event ids
+///
+/// Catches the repeats produced by overlapping poll windows without the
+/// bloom filter's false positives. At most `subgraph.dedup_cache_size` ids
+/// are kept in memory; the oldest is evicted first.
+pub struct RecentIdCache {
+    capacity: usize,
+    ids: Mutex<RecentIds>,
+}
+
+#[derive(Default)]
+struct RecentIds {
+    /// Insertion order, oldest first
+    order: VecDeque<Arc<str>>,
+    members: HashSet<Arc<str>>,
+}
+
+impl RecentIdCache {
+    /// Create a cache if `subgraph.dedup_cache_size` is non-zero
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        match config.subgraph.dedup_cache_size {
+            0 => None,
+            capacity => Some(Self {
+                capacity,
+                ids: Mutex::new(RecentIds::default()),
+            }),
+        }
+    }
+
+    /// Drop events whose id is in the cache, returning how many were dropped
+    pub fn filter_unseen(&self, events: &mut Vec<SwapEvent>) -> usize {
+        let ids = self.ids.lock().unwrap();
+        let before = events.len();
+        events.retain(|event| !ids.members.contains(event.id.as_str()));
+        let dropped = before - events.len();
+        if dropped > 0 {
+            debug!("Dropped {} recently published events", dropped);
+        }
+        dropped
+    }
+
+    /// Remember published ids, evicting the oldest beyond capacity
+    pub fn record_published(&self, events: &[SwapEvent]) {
+        let mut ids = self.ids.lock().unwrap();
+        for event in events {
+            if ids.members.contains(event.id.as_str()) {
+                continue;
+            }
+            let id: Arc<str> = Arc::from(event.id.as_str());
+            ids.order.push_back(id.clone());
+            ids.members.insert(id);
+            if ids.order.len() > self.capacity {
+                if let Some(oldest) = ids.order.pop_front() {
+                    ids.members.remove(&oldest);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{SwapEventBuilder, UniswapVersion};
+
+    fn event(id: &str) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.id = id.to_string();
+        event
+    }
+
+    fn dedup_config(path: &str, capacity: usize) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.dedup.enabled = true;
+        config.dedup.path = Some(path.to_string());
+        config.dedup.capacity = capacity;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_seen_ids_suppressed_after_restart() {
+        let path = std::env::temp_dir().join(format!("relay-dedup-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+
+        let published: Vec<SwapEvent> = (0..500).map(|i| event(&format!("0xswap-{}", i))).collect();
+        let before_restart = EventDeduplicator::from_config(&dedup_config(path, 1000)).unwrap();
+        before_restart.record_published(&published);
+        // Recording stays in memory; only `persist` writes the file
+        assert!(!std::path::Path::new(path).exists());
+        before_restart.persist().await;
+        drop(before_restart);
+
+        // A fresh process reloads the filter from disk
+        let after_restart = EventDeduplicator::from_config(&dedup_config(path, 1000)).unwrap();
+        let mut batch = vec![event("0xswap-3"), event("0xswap-new"), event("0xswap-499")];
+        assert_eq!(after_restart.reserve_unseen(&mut batch), 2);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].id, "0xswap-new");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_old_generations_age_out() {
+        let mut config = AppConfig::default();
+        config.dedup.enabled = true;
+        config.dedup.capacity = 10;
+        let dedup = EventDeduplicator::from_config(&config).unwrap();
+
+        dedup.record_published(&[event("0xoldest")]);
+        for i in 0..20 {
+            dedup.record_published(&[event(&format!("0xswap-{}", i))]);
+        }
+
+        assert!(!dedup.is_duplicate("0xoldest"));
+        assert!(dedup.is_duplicate("0xswap-19"));
+    }
+
+    #[test]
+    fn test_recent_id_cache_evicts_oldest() {
+        let mut config = AppConfig::default();
+        config.subgraph.dedup_cache_size = 2;
+        let cache = RecentIdCache::from_config(&config).unwrap();
+
+        cache.record_published(&[event("0xa"), event("0xb")]);
+        cache.record_published(&[event("0xb"), event("0xc")]);
+
+        let mut batch = vec![event("0xa"), event("0xb"), event("0xc")];
+        assert_eq!(cache.filter_unseen(&mut batch), 2);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].id, "0xa");
+        assert!(RecentIdCache::from_config(&AppConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_false_positive_rate_is_close_to_configured() {
+        let mut filter = BloomFilter::with_rate(10_000, 0.01);
+        for i in 0..10_000 {
+            filter.insert(&format!("seen-{}", i));
+        }
+
+        let false_positives = (0..10_000)
+            .filter(|i| filter.contains(&format!("unseen-{}", i)))
+            .count();
+        assert!(false_positives < 200, "{} false positives", false_positives);
+    }
+
+    #[test]
+    fn test_cross_version_keeps_one_event_per_log() {
+        let reported = |version: UniswapVersion, swap_id: &str| {
+            let mut event = event(&format!("{}_{}", version, swap_id));
+            event.version = version;
+            event.transaction_hash = swap_id.to_string();
+            event.log_index = Some(7);
+            event
+        };
+        let v2 = reported(UniswapVersion::V2, "0xABC-7");
+        let v3 = reported(UniswapVersion::V3, "0xabc#7");
+
+        let mut config = AppConfig::default();
+        config.dedup.enabled = true;
+        let per_version = EventDeduplicator::from_config(&config).unwrap();
+        per_version.record_published(std::slice::from_ref(&v2));
+        let mut batch = vec![v3.clone()];
+        assert_eq!(per_version.reserve_unseen(&mut batch), 0);
+
+        config.dedup.cross_version = true;
+        let cross_version = EventDeduplicator::from_config(&config).unwrap();
+        cross_version.record_published(std::slice::from_ref(&v2));
+        let mut batch = vec![v3];
+        assert_eq!(cross_version.reserve_unseen(&mut batch), 1);
+        assert!(batch.is_empty());
+    }
+
+    #[test]
+    fn test_reserved_log_not_published_by_other_version() {
+        let reported = |version: UniswapVersion, swap_id: &str| {
+            let mut event = event(&format!("{}_{}", version, swap_id));
+            event.version = version;
+            event.transaction_hash = swap_id.to_string();
+            event.log_index = Some(7);
+            event
+        };
+        let mut config = AppConfig::default();
+        config.dedup.enabled = true;
+        config.dedup.cross_version = true;
+        let dedup = EventDeduplicator::from_config(&config).unwrap();
+
+        // V2 reserves the log while its publish is in flight
+        let mut v2_batch = vec![reported(UniswapVersion::V2, "0xabc-7")];
+        assert_eq!(dedup.reserve_unseen(&mut v2_batch), 0);
+        let mut v3_batch = vec![reported(UniswapVersion::V3, "0xabc#7")];
+        assert_eq!(dedup.reserve_unseen(&mut v3_batch), 1);
+
+        // A failed publish frees the log for the next attempt
+        dedup.release(&v2_batch);
+        let mut v3_batch = vec![reported(UniswapVersion::V3, "0xabc#7")];
+        assert_eq!(dedup.reserve_unseen(&mut v3_batch), 0);
+        dedup.record_published(&v3_batch);
+        assert_eq!(dedup.reserve_unseen(&mut v2_batch), 1);
+    }
+}
@@ -1 +1,10 @@
+pub mod backfill;
+pub mod block_summaries;
+pub mod candles;
+pub mod catchup;
+pub mod cursor;
+pub mod dedup;
+pub mod retry_budget;
+pub mod shedding;
 pub mod swap_collector;
+pub mod transform;
@@ -0,0 +1,73 @@
+use crate::config::AppConfig;
+use crate::utils::clock::Clock;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+
+/// Token bucket capping retries across every source
+///
+/// Each retry spends one token; tokens refill at
+/// `retry.global_retry_budget_per_second` up to one second's worth. When a
+/// broad outage makes every source fail at once, retries beyond the budget
+/// are skipped and the source waits for its next poll instead.
+pub struct RetryBudget {
+    per_second: f64,
+    clock: Arc<dyn Clock>,
+    bucket: Mutex<(f64, DateTime<Utc>)>,
+}
+
+impl RetryBudget {
+    /// Create a budget from `retry.global_retry_budget_per_second` (0 = unlimited)
+    pub fn from_config(config: &AppConfig, clock: Arc<dyn Clock>) -> Option<Self> {
+        let per_second = config.retry.global_retry_budget_per_second;
+        (per_second > 0.0).then(|| {
+            let now = clock.now();
+            Self {
+                per_second,
+                clock,
+                bucket: Mutex::new((per_second.max(1.0), now)),
+            }
+        })
+    }
+
+    /// Spend a token for one retry; `false` if the budget is exhausted
+    pub fn try_acquire(&self) -> bool {
+        let now = self.clock.now();
+        let mut bucket = self.bucket.lock().unwrap();
+        let (tokens, refilled_at) = &mut *bucket;
+
+        let elapsed = (now - *refilled_at).num_milliseconds().max(0) as f64 / 1000.0;
+        *tokens = (*tokens + elapsed * self.per_second).min(self.per_second.max(1.0));
+        *refilled_at = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::clock::MockClock;
+
+    #[test]
+    fn test_sources_share_budget() {
+        let mut config = AppConfig::default();
+        config.retry.global_retry_budget_per_second = 4.0;
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let budget = RetryBudget::from_config(&config, clock.clone()).unwrap();
+
+        // Three failing sources each want five retries within the same second
+        let granted = (0..3)
+            .flat_map(|_| 0..5)
+            .filter(|_| budget.try_acquire())
+            .count();
+        assert_eq!(granted, 4);
+
+        clock.advance(chrono::Duration::milliseconds(500));
+        assert_eq!((0..10).filter(|_| budget.try_acquire()).count(), 2);
+    }
+}
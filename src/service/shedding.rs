@@ -0,0 +1,89 @@
+use crate::config::AppConfig;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, warn};
+
+/// Turns enrichment off while the relay falls behind the chain
+///
+/// Publish lag is how far the newest swap of a batch trails the clock. Once
+/// it exceeds `enrichment.enrichment_shed_threshold` seconds, enrichment
+/// lookups are skipped so core events go out on time. Enrichment resumes when
+/// the lag drops back under half the threshold, which keeps the shedder from
+/// flapping around the limit.
+pub struct EnrichmentShedder {
+    threshold_seconds: u64,
+    shedding: AtomicBool,
+}
+
+impl EnrichmentShedder {
+    /// Create a shedder from `enrichment.enrichment_shed_threshold` (0 = never shed)
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            threshold_seconds: config.enrichment.enrichment_shed_threshold,
+            shedding: AtomicBool::new(false),
+        }
+    }
+
+    /// Record the lag of the latest batch and return whether to shed enrichment
+    pub fn observe_lag(&self, lag_seconds: u64) -> bool {
+        if self.threshold_seconds == 0 {
+            return false;
+        }
+
+        let was_shedding = self.is_shedding();
+        let shedding = if was_shedding {
+            lag_seconds >= self.threshold_seconds.div_ceil(2)
+        } else {
+            lag_seconds > self.threshold_seconds
+        };
+
+        if shedding != was_shedding {
+            self.shedding.store(shedding, Ordering::Relaxed);
+            if shedding {
+                warn!(
+                    "Publish lag {}s exceeds {}s, shedding enrichment",
+                    lag_seconds, self.threshold_seconds
+                );
+            } else {
+                info!(
+                    "Publish lag recovered to {}s, enrichment restored",
+                    lag_seconds
+                );
+            }
+        }
+        shedding
+    }
+
+    /// Whether enrichment is currently shed
+    pub fn is_shedding(&self) -> bool {
+        self.shedding.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shedder(threshold_seconds: u64) -> EnrichmentShedder {
+        let mut config = AppConfig::default();
+        config.enrichment.enrichment_shed_threshold = threshold_seconds;
+        EnrichmentShedder::from_config(&config)
+    }
+
+    #[test]
+    fn test_sheds_above_threshold_and_restores_below_half() {
+        let shedder = shedder(60);
+
+        assert!(!shedder.observe_lag(60));
+        assert!(shedder.observe_lag(61));
+        // Still behind, even though under the threshold
+        assert!(shedder.observe_lag(45));
+        assert!(!shedder.observe_lag(29));
+        assert!(!shedder.is_shedding());
+    }
+
+    #[test]
+    fn test_zero_threshold_never_sheds() {
+        let shedder = shedder(0);
+        assert!(!shedder.observe_lag(u64::MAX));
+    }
+}
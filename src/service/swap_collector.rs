@@ -1,43 +1,256 @@
 use crate::config::AppConfig;
-use crate::error::Result;
+use crate::error::{DAppError, Result, SerializationError, SubgraphError};
 use crate::model::{
-    GraphQLPair, GraphQLToken, GraphQLV3Pool, PoolInfo, SwapEvent, SwapEventBuilder, TokenInfo,
-    UniswapV2SwapEvent, UniswapV3SwapEvent, UniswapVersion,
+    BlockSummary, GraphQLPair, GraphQLToken, GraphQLV3Pool, OhlcCandle, PoolInfo, QuarantinedSwap,
+    SwapEvent, SwapEventBuilder, TokenInfo, UniswapV2SwapEvent, UniswapV3SwapEvent, UniswapVersion,
+    V2Reserves,
 };
 use crate::redis::RedisPublisher;
+use crate::service::backfill::{BackfillRequest, BackfillSummary};
+use crate::service::block_summaries::BlockSummaryAggregator;
+use crate::service::candles::CandleAggregator;
+use crate::service::catchup::OrderedCatchup;
+use crate::service::cursor::{CursorStore, SwapCursor};
+use crate::service::dedup::{EventDeduplicator, RecentIdCache};
+use crate::service::retry_budget::RetryBudget;
+use crate::service::shedding::EnrichmentShedder;
+use crate::service::transform::EventTransformer;
+use crate::sink::buffered::BufferedSink;
+use crate::sink::capture::CaptureSink;
+use crate::sink::{EventSink, KeyedEvent};
+use crate::subgraph::client::response_snippet;
+use crate::subgraph::transport::SubgraphTransport;
 use crate::subgraph::SubgraphClient;
+use crate::telemetry::stages::PipelineStage;
 use crate::telemetry::MetricsCollector;
-use tokio::time::{interval, Duration};
+use crate::utils::backoff::ExponentialBackoff;
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::retry::retry_async_with;
+use crate::utils::sanitize::{strip_unsafe_chars, truncate_chars};
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, interval_at, Duration, Interval};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+/// Restricts which pools a swap query returns
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoolFilter {
+    /// No restriction
+    All,
+    /// Only swaps in the given pools
+    Only(Vec<String>),
+    /// Swaps in every pool except the given ones
+    Exclude(Vec<String>),
+}
+
+impl PoolFilter {
+    /// Build the `where` argument for the swaps query of the given version
+    ///
+    /// V2 swaps reference their pool as `pair`, V3 swaps as `pool`.
+    pub fn where_clause(&self, version: &UniswapVersion) -> serde_json::Value {
+        let field = match version {
+            UniswapVersion::V2 => "pair",
+            UniswapVersion::V3 => "pool",
+        };
+
+        match self {
+            PoolFilter::All => serde_json::json!({}),
+            PoolFilter::Only(pools) => serde_json::json!({ format!("{}_in", field): pools }),
+            PoolFilter::Exclude(pools) => {
+                serde_json::json!({ format!("{}_not_in", field): pools })
+            }
+        }
+    }
+}
+
+/// Shared dependencies of the collection tasks
+#[derive(Clone)]
+struct CollectionContext {
+    config: AppConfig,
+    subgraph_client: SubgraphClient,
+    sink: Arc<dyn EventSink>,
+    clock: Arc<dyn Clock>,
+    metrics_collector: MetricsCollector,
+    cursors: Arc<CursorStore>,
+    transformer: Option<Arc<EventTransformer>>,
+    dedup: Option<Arc<EventDeduplicator>>,
+    recent_ids: Option<Arc<RecentIdCache>>,
+    shedder: Arc<EnrichmentShedder>,
+    candles: Option<Arc<CandleAggregator>>,
+    block_summaries: Option<Arc<BlockSummaryAggregator>>,
+    retry_budget: Option<Arc<RetryBudget>>,
+    catchup: Arc<tokio::sync::Mutex<Option<OrderedCatchup>>>,
+    last_v2_block: Arc<AtomicU64>,
+    last_v3_block: Arc<AtomicU64>,
+}
+
 /// Service for collecting swap events from Uniswap subgraphs
 pub struct SwapEventCollector {
     config: AppConfig,
     subgraph_client: SubgraphClient,
-    redis_publisher: RedisPublisher,
+    sink: Arc<dyn EventSink>,
+    clock: Arc<dyn Clock>,
     metrics_collector: MetricsCollector,
+    cursors: Arc<CursorStore>,
+    transformer: Option<Arc<EventTransformer>>,
+    dedup: Option<Arc<EventDeduplicator>>,
+    recent_ids: Option<Arc<RecentIdCache>>,
+    shedder: Arc<EnrichmentShedder>,
+    candles: Option<Arc<CandleAggregator>>,
+    block_summaries: Option<Arc<BlockSummaryAggregator>>,
+    retry_budget: Option<Arc<RetryBudget>>,
+    /// Armed while a backfill runs with `subgraph.ordered_catchup`
+    catchup: Arc<tokio::sync::Mutex<Option<OrderedCatchup>>>,
+    /// Set when events are buffered, so stale buffers can be flushed on a timer
+    buffer: Option<Arc<BufferedSink>>,
     is_running: bool,
-    _last_v2_block: u64,
-    _last_v3_block: u64,
+    /// Highest block of the swaps delivered per version
+    last_v2_block: Arc<AtomicU64>,
+    last_v3_block: Arc<AtomicU64>,
+    /// `None` once `with_trigger` hands triggering to an external channel
+    trigger_tx: Option<mpsc::Sender<()>>,
+    trigger_rx: Option<mpsc::Receiver<()>>,
+    poll_trigger: watch::Sender<()>,
+    /// Latest live-safe configuration, picked up by the polling tasks
+    live_config: watch::Sender<AppConfig>,
+    config_updates: Option<mpsc::Receiver<AppConfig>>,
+    /// Cancelled on stop so every background task exits
+    cancel: CancellationToken,
+    tasks: Vec<JoinHandle<()>>,
 }
 
 impl SwapEventCollector {
     /// Create a new swap event collector
+    ///
+    /// Fails when the configured transform script cannot be loaded.
     pub fn new(
         config: AppConfig,
         subgraph_client: SubgraphClient,
         redis_publisher: RedisPublisher,
         metrics_collector: MetricsCollector,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        Self::from_parts(
+            config,
+            subgraph_client,
+            Arc::new(redis_publisher),
+            Arc::new(SystemClock),
+            metrics_collector,
+        )
+    }
+
+    /// Start building a collector with custom sink, clock or transport
+    #[allow(dead_code)]
+    pub fn builder(config: AppConfig) -> SwapEventCollectorBuilder {
+        SwapEventCollectorBuilder::new(config)
+    }
+
+    fn from_parts(
+        config: AppConfig,
+        subgraph_client: SubgraphClient,
+        sink: Arc<dyn EventSink>,
+        clock: Arc<dyn Clock>,
+        metrics_collector: MetricsCollector,
+    ) -> Result<Self> {
+        let (trigger_tx, trigger_rx) = mpsc::channel(16);
+        let (poll_trigger, _) = watch::channel(());
+        let (live_config, _) = watch::channel(config.clone());
+        let cursors = Arc::new(CursorStore::from_config(&config));
+        // Publishing untransformed events could leak ones the script drops
+        let transformer = EventTransformer::from_config(&config)?.map(Arc::new);
+        let dedup = EventDeduplicator::from_config(&config).map(Arc::new);
+        let recent_ids = RecentIdCache::from_config(&config).map(Arc::new);
+        let shedder = Arc::new(EnrichmentShedder::from_config(&config));
+        let candles = CandleAggregator::from_config(&config).map(Arc::new);
+        let block_summaries = BlockSummaryAggregator::from_config(&config).map(Arc::new);
+        let retry_budget = RetryBudget::from_config(&config, clock.clone()).map(Arc::new);
+        let buffer = BufferedSink::from_config(&config, sink.clone(), clock.clone()).map(Arc::new);
+        let sink = match &buffer {
+            Some(buffer) => buffer.clone() as Arc<dyn EventSink>,
+            None => sink,
+        };
+        let sink = match &config.application.capture_to_file {
+            Some(path) => match CaptureSink::open(path, sink.clone()) {
+                Ok(capture) => Arc::new(capture) as Arc<dyn EventSink>,
+                Err(e) => {
+                    error!("Event capture disabled: {}", e);
+                    sink
+                }
+            },
+            None => sink,
+        };
+
+        Ok(Self {
             config,
             subgraph_client,
-            redis_publisher,
+            sink,
+            clock,
             metrics_collector,
+            cursors,
+            transformer,
+            dedup,
+            recent_ids,
+            shedder,
+            candles,
+            block_summaries,
+            retry_budget,
+            catchup: Arc::new(tokio::sync::Mutex::new(None)),
+            buffer,
             is_running: false,
-            _last_v2_block: 0,
-            _last_v3_block: 0,
-        }
+            last_v2_block: Arc::new(AtomicU64::new(0)),
+            last_v3_block: Arc::new(AtomicU64::new(0)),
+            trigger_tx: Some(trigger_tx),
+            trigger_rx: Some(trigger_rx),
+            poll_trigger,
+            live_config,
+            config_updates: None,
+            cancel: CancellationToken::new(),
+            tasks: Vec::new(),
+        })
+    }
+
+    /// Use an externally owned channel to trigger on-demand polls
+    ///
+    /// Replaces the collector's own trigger channel: senders obtained from
+    /// `trigger_handle` before this call no longer have any effect, and
+    /// `trigger_handle` fails afterwards.
+    #[allow(dead_code)]
+    pub fn with_trigger(mut self, trigger: mpsc::Receiver<()>) -> Self {
+        self.trigger_tx = None;
+        self.trigger_rx = Some(trigger);
+        self
+    }
+
+    /// Apply configuration reloaded from disk while running
+    ///
+    /// Only `config::LIVE_CONFIG_FIELDS` are taken from each received config;
+    /// changes to anything else are logged and ignored until restart.
+    /// Typically fed by `AppConfig::watch`.
+    pub fn with_config_updates(mut self, updates: mpsc::Receiver<AppConfig>) -> Self {
+        self.config_updates = Some(updates);
+        self
+    }
+
+    /// Sender that triggers an immediate poll of all sources
+    ///
+    /// Each message causes an extra poll in addition to the regular interval.
+    /// Bursts of triggers are coalesced and spaced according to
+    /// `rate_limiting.max_subgraph_requests_per_second`. Fails once
+    /// `with_trigger` has replaced the collector's channel; send to that
+    /// channel's sender instead.
+    #[allow(dead_code)]
+    pub fn trigger_handle(&self) -> Result<mpsc::Sender<()>> {
+        self.trigger_tx.clone().ok_or_else(|| {
+            DAppError::Config(
+                "Polls are triggered through the channel passed to with_trigger".to_string(),
+            )
+        })
     }
 
     /// Start collecting events from subgraphs
@@ -65,9 +278,30 @@ impl SwapEventCollector {
             warn!("Some builder tests failed: {}", e);
         }
 
+        // Tell subscribers what the stream carries before the first event
+        if self.config.redis.announce_on_startup {
+            self.sink
+                .publish_control(&self.config.hello_message())
+                .await?;
+            info!("Announced stream on startup");
+        }
+
+        if self.config.application.one_shot {
+            return self.run_one_shot().await;
+        }
+
+        // Hot pool tasks cover their pools' blocks alongside the default tasks
+        if let Some(block_summaries) = &self.block_summaries {
+            block_summaries.track(Self::task_keys(&self.config));
+        }
+
         // Start background collection tasks
+        self.start_config_reloading();
+        self.start_trigger_forwarding();
+        self.start_buffer_flushing();
         self.start_v2_collection().await?;
         self.start_v3_collection().await?;
+        self.start_hot_pool_collection().await?;
 
         self.is_running = true;
         info!("Uniswap swap event collection started successfully");
@@ -75,7 +309,20 @@ impl SwapEventCollector {
         Ok(())
     }
 
+    /// Poll every source once, publish and flush, without starting any loops
+    async fn run_one_shot(&self) -> Result<()> {
+        info!("Running a single one-shot collection cycle");
+        self.poll_once().await?;
+        self.sink.flush().await?;
+        info!("One-shot collection complete");
+
+        Ok(())
+    }
+
     /// Stop collecting events
+    ///
+    /// No new polls start. Cycles already fetching or publishing get up to
+    /// `application.shutdown_timeout_seconds` to finish, then are aborted.
     pub async fn stop_collecting(&mut self) -> Result<()> {
         if !self.is_running {
             warn!("Swap event collector not running");
@@ -83,24 +330,319 @@ impl SwapEventCollector {
         }
 
         info!("Stopping Uniswap swap event collection...");
+        self.cancel.cancel();
+
+        // Polling tasks finish their in-flight cycle before exiting
+        let timeout = Duration::from_secs(self.config.application.shutdown_timeout_seconds);
+        let mut tasks = std::mem::take(&mut self.tasks);
+        let drained = tokio::time::timeout(timeout, async {
+            for task in tasks.iter_mut() {
+                if let Err(e) = task.await {
+                    warn!("Collection task ended abnormally: {}", e);
+                }
+            }
+        })
+        .await;
+        if drained.is_err() {
+            warn!(
+                "In-flight collection did not finish within {:?}; aborting it",
+                timeout
+            );
+            for task in &tasks {
+                task.abort();
+            }
+        }
+        // A fresh token lets the collector be started again
+        self.cancel = CancellationToken::new();
         self.is_running = false;
         info!("Uniswap swap event collection stopped");
 
         Ok(())
     }
 
+    /// Dependencies handed to each collection task
+    fn collection_context(&self) -> CollectionContext {
+        CollectionContext {
+            config: self.config.clone(),
+            subgraph_client: self.subgraph_client.clone(),
+            sink: self.sink.clone(),
+            clock: self.clock.clone(),
+            metrics_collector: self.metrics_collector.clone(),
+            cursors: self.cursors.clone(),
+            transformer: self.transformer.clone(),
+            dedup: self.dedup.clone(),
+            recent_ids: self.recent_ids.clone(),
+            shedder: self.shedder.clone(),
+            candles: self.candles.clone(),
+            block_summaries: self.block_summaries.clone(),
+            retry_budget: self.retry_budget.clone(),
+            catchup: self.catchup.clone(),
+            last_v2_block: self.last_v2_block.clone(),
+            last_v3_block: self.last_v3_block.clone(),
+        }
+    }
+
+    /// Run a single collection cycle against both subgraphs
+    ///
+    /// Both versions are polled even if the first fails; the first error is
+    /// returned.
+    pub async fn poll_once(&self) -> Result<()> {
+        let ctx = self.collection_context();
+        let v2 = Self::collect_v2_events_with_retry(&ctx, &PoolFilter::All).await;
+        let v3 = Self::collect_v3_events_with_retry(&ctx, &PoolFilter::All).await;
+
+        v2.and(v3)
+    }
+
+    /// Re-publish events captured as newline-delimited JSON
+    ///
+    /// Reads events in the published format (e.g. written by
+    /// `application.capture_to_file`) and sends them through the normal sink
+    /// path: events failing `SwapEvent::validate` are logged and skipped,
+    /// already published ids are skipped when dedup is enabled, and
+    /// publishing is paced by `application.replay_events_per_second`. Returns
+    /// the number of events published.
+    #[allow(dead_code)]
+    pub async fn replay_from_file(&self, path: &str) -> Result<usize> {
+        let contents = tokio::fs::read_to_string(path).await.map_err(|e| {
+            DAppError::Config(format!("Failed to read replay file {}: {}", path, e))
+        })?;
+
+        let events = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<SwapEvent>(line).map_err(|e| {
+                    SerializationError::Json(format!("{}:{}: {}", path, index + 1, e)).into()
+                })
+            })
+            .collect::<Result<Vec<SwapEvent>>>()?;
+        let read = events.len();
+
+        let (mut events, invalid) = SwapEvent::partition_valid(events);
+        for (event, reasons) in &invalid {
+            warn!(
+                "Skipping invalid replayed event {}: {}",
+                event.id,
+                reasons.join(", ")
+            );
+        }
+
+        if let Some(dedup) = &self.dedup {
+            dedup.reserve_unseen(&mut events);
+        }
+
+        let chunk_size = match self.config.application.replay_events_per_second {
+            0 => events.len().max(1),
+            rate => rate as usize,
+        };
+        let mut pacer = interval(Duration::from_secs(1));
+        for (index, chunk) in events.chunks(chunk_size).enumerate() {
+            pacer.tick().await;
+            if let Err(e) = Self::publish_events(&self.config, self.sink.as_ref(), chunk).await {
+                if let Some(dedup) = &self.dedup {
+                    dedup.release(&events[index * chunk_size..]);
+                }
+                return Err(e);
+            }
+            if let Some(dedup) = &self.dedup {
+                dedup.record_published(chunk);
+            }
+            self.metrics_collector
+                .record_events_processed(chunk.len() as u64);
+        }
+        self.sink.flush().await?;
+        if let Some(dedup) = &self.dedup {
+            dedup.persist().await;
+        }
+
+        info!("Replayed {} of {} events from {}", events.len(), read, path);
+        Ok(events.len())
+    }
+
+    /// Fetch and publish every swap in a historical time range
+    ///
+    /// Pages through each requested subgraph with `swap_pages`, publishing
+    /// each page before fetching the next, so memory is bounded by one page
+    /// whatever the range. The queries count towards `[rate_limiting]` like
+    /// live polling. Swaps go through the live parse and enrichment path
+    /// (sanitizing, quarantine, allowlists, decimals verification, direction,
+    /// transform script, dedup) and are published in batches of
+    /// `subgraph.query_page_size`, logging progress after each. Parse and
+    /// publish failures are counted rather than aborting the run; a failed
+    /// fetch ends it.
+    ///
+    /// With `subgraph.ordered_catchup`, live polls made while the backfill
+    /// runs are held back and published once it reaches the live cursor.
+    pub async fn backfill(&self, request: &BackfillRequest) -> Result<BackfillSummary> {
+        let ctx = self.collection_context();
+        let mut summary = BackfillSummary::default();
+        *self.catchup.lock().await = OrderedCatchup::from_config(&self.config, self.live_cursor());
+
+        let result = self.backfill_versions(&ctx, request, &mut summary).await;
+
+        // Hand off to live collection even when a fetch failed, so buffered
+        // live events are not held back forever
+        let mut catchup = self.catchup.lock().await;
+        if let Some(mut armed) = catchup.take() {
+            let live = armed.complete_backfill();
+            self.publish_backfilled(live, &mut summary).await;
+        }
+        drop(catchup);
+        result?;
+        self.sink.flush().await?;
+
+        Ok(summary)
+    }
+
+    /// Page through each requested version, stopping at the first failed fetch
+    async fn backfill_versions(
+        &self,
+        ctx: &CollectionContext,
+        request: &BackfillRequest,
+        summary: &mut BackfillSummary,
+    ) -> Result<()> {
+        for version in &request.versions {
+            info!(
+                "Backfilling {} swaps between {} and {}",
+                version, request.from, request.to
+            );
+            let mut pages = self.subgraph_client.swap_pages(
+                version.clone(),
+                request.pool.as_deref(),
+                request.from,
+                request.to,
+            );
+            while let Some(swaps) = pages.next_page().await? {
+                summary.fetched += swaps.len();
+                self.backfill_page(ctx, version, &swaps, summary).await;
+            }
+            if let Some(dedup) = &self.dedup {
+                dedup.persist().await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Earliest position the live polling tasks resume from
+    ///
+    /// Falls back to the current time before any task has recorded a cursor.
+    fn live_cursor(&self) -> DateTime<Utc> {
+        Self::task_keys(&self.config)
+            .iter()
+            .filter_map(|key| self.cursors.get(key))
+            .filter_map(|cursor| Utc.timestamp_opt(cursor.timestamp as i64, 0).single())
+            .min()
+            .unwrap_or_else(|| self.clock.now())
+    }
+
+    /// Parse, enrich and publish one page of backfilled swaps
+    async fn backfill_page(
+        &self,
+        ctx: &CollectionContext,
+        version: &UniswapVersion,
+        swaps: &[serde_json::Value],
+        summary: &mut BackfillSummary,
+    ) {
+        let mut events = Vec::new();
+        let mut quarantined = Vec::new();
+        for swap_data in swaps {
+            let parsed = match version {
+                UniswapVersion::V2 => Self::parse_v2_swap_event(swap_data),
+                UniswapVersion::V3 => Self::parse_v3_swap_event(swap_data),
+            };
+            match parsed {
+                Ok(mut event) => {
+                    Self::sanitize_token_metadata(&self.config, &mut event);
+                    events.push(event);
+                }
+                Err(e) => {
+                    warn!("Skipping unparsable {} swap: {}", version, e);
+                    summary.errors += 1;
+                    self.metrics_collector.record_error();
+                    if self.config.redis.quarantine_channel.is_some() {
+                        quarantined.push(QuarantinedSwap {
+                            source: format!("uniswap_{}", version),
+                            reason: e.to_string(),
+                            payload: swap_data.clone(),
+                            quarantined_at: self.clock.now(),
+                        });
+                    }
+                }
+            }
+        }
+        Self::quarantine(self.sink.as_ref(), &quarantined).await;
+        Self::retain_allowlisted(&self.config, &mut events);
+        let mut events = Self::enrich_events(ctx, events).await;
+
+        // Publish under the lock so live events cannot overtake this page
+        let mut catchup = self.catchup.lock().await;
+        if let Some(catchup) = catchup.as_mut() {
+            events = catchup.push_backfill(events);
+        }
+        self.publish_backfilled(events, summary).await;
+    }
+
+    /// Publish backfilled events in batches of `subgraph.query_page_size`
+    async fn publish_backfilled(&self, mut events: Vec<SwapEvent>, summary: &mut BackfillSummary) {
+        if let Some(recent_ids) = &self.recent_ids {
+            recent_ids.filter_unseen(&mut events);
+        }
+        if let Some(dedup) = &self.dedup {
+            dedup.reserve_unseen(&mut events);
+        }
+
+        let batch_size = (self.config.subgraph.query_page_size as usize).max(1);
+        for batch in events.chunks(batch_size) {
+            match Self::publish_events(&self.config, self.sink.as_ref(), batch).await {
+                Ok(()) => {
+                    summary.published += batch.len();
+                    if let Some(dedup) = &self.dedup {
+                        dedup.record_published(batch);
+                    }
+                    if let Some(recent_ids) = &self.recent_ids {
+                        recent_ids.record_published(batch);
+                    }
+                    self.metrics_collector
+                        .record_events_processed(batch.len() as u64);
+                    for event in batch {
+                        self.metrics_collector
+                            .record_pool_events(&event.pool_address, 1);
+                        self.metrics_collector.record_token_events(
+                            &event.token_in.address,
+                            &event.token_in.symbol,
+                            1,
+                        );
+                        self.metrics_collector.record_token_events(
+                            &event.token_out.address,
+                            &event.token_out.symbol,
+                            1,
+                        );
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to publish {} backfilled events: {}", batch.len(), e);
+                    if let Some(dedup) = &self.dedup {
+                        dedup.release(batch);
+                    }
+                    summary.errors += 1;
+                }
+            }
+            info!("Backfill progress: {}", summary);
+        }
+    }
+
     /// Start V2 collection task
     async fn start_v2_collection(&mut self) -> Result<()> {
         let config = self.config.clone();
-        let subgraph_client = self.subgraph_client.clone();
-        let redis_publisher = self.redis_publisher.clone();
+        let mut ctx = self.collection_context();
         let metrics_collector = self.metrics_collector.clone();
-
-        // Use rate limiting config
-        let interval_duration = Duration::from_secs(
-            config.subgraph.polling_interval_seconds.max(1), // Ensure minimum 1 second
-        );
-        let mut interval_timer = interval(interval_duration);
+        let pool_filter = Self::default_pool_filter(&self.config);
+        let mut poll_trigger = Some(self.poll_trigger.subscribe());
+        let mut config_updates = self.live_config.subscribe();
+        let mut interval_timer = interval(Self::polling_interval(&config));
 
         // Log rate limiting configuration
         if config.is_production() {
@@ -126,18 +668,23 @@ impl SwapEventCollector {
             );
         }
 
-        tokio::spawn(async move {
-            loop {
-                interval_timer.tick().await;
+        let cancel = self.cancel.clone();
+        self.spawn_draining_task(async move {
+            while let Some(triggered) = Self::next_poll_with_reload(
+                &cancel,
+                &mut interval_timer,
+                &mut poll_trigger,
+                &mut ctx,
+                &mut config_updates,
+                true,
+            )
+            .await
+            {
+                if triggered {
+                    debug!("Triggered V2 poll");
+                }
 
-                if let Err(e) = Self::collect_v2_events_with_retry(
-                    &subgraph_client,
-                    &redis_publisher,
-                    &metrics_collector,
-                    &config,
-                )
-                .await
-                {
+                if let Err(e) = Self::collect_v2_events_with_retry(&ctx, &pool_filter).await {
                     error!("Error collecting V2 events after retries: {}", e);
                     metrics_collector.record_error();
                 }
@@ -150,15 +697,12 @@ impl SwapEventCollector {
     /// Start V3 collection task
     async fn start_v3_collection(&mut self) -> Result<()> {
         let config = self.config.clone();
-        let subgraph_client = self.subgraph_client.clone();
-        let redis_publisher = self.redis_publisher.clone();
+        let mut ctx = self.collection_context();
         let metrics_collector = self.metrics_collector.clone();
-
-        // Use rate limiting config
-        let interval_duration = Duration::from_secs(
-            config.subgraph.polling_interval_seconds.max(1), // Ensure minimum 1 second
-        );
-        let mut interval_timer = interval(interval_duration);
+        let pool_filter = Self::default_pool_filter(&self.config);
+        let mut poll_trigger = Some(self.poll_trigger.subscribe());
+        let mut config_updates = self.live_config.subscribe();
+        let mut interval_timer = interval(Self::polling_interval(&config));
 
         // Log retry configuration
         if config.is_production() {
@@ -185,18 +729,23 @@ impl SwapEventCollector {
                   config.retry.backoff_multiplier);
         }
 
-        tokio::spawn(async move {
-            loop {
-                interval_timer.tick().await;
+        let cancel = self.cancel.clone();
+        self.spawn_draining_task(async move {
+            while let Some(triggered) = Self::next_poll_with_reload(
+                &cancel,
+                &mut interval_timer,
+                &mut poll_trigger,
+                &mut ctx,
+                &mut config_updates,
+                true,
+            )
+            .await
+            {
+                if triggered {
+                    debug!("Triggered V3 poll");
+                }
 
-                if let Err(e) = Self::collect_v3_events_with_retry(
-                    &subgraph_client,
-                    &redis_publisher,
-                    &metrics_collector,
-                    &config,
-                )
-                .await
-                {
+                if let Err(e) = Self::collect_v3_events_with_retry(&ctx, &pool_filter).await {
                     error!("Error collecting V3 events after retries: {}", e);
                     metrics_collector.record_error();
                 }
@@ -206,204 +755,442 @@ impl SwapEventCollector {
         Ok(())
     }
 
-    /// Collect V2 swap events with retry logic
-    async fn collect_v2_events_with_retry(
-        subgraph_client: &SubgraphClient,
-        redis_publisher: &RedisPublisher,
-        metrics_collector: &MetricsCollector,
-        config: &AppConfig,
-    ) -> Result<()> {
-        let mut attempts = 0;
-        let mut delay = config.retry.initial_delay_ms;
-
-        loop {
-            match Self::collect_v2_events(subgraph_client, redis_publisher, metrics_collector).await
-            {
-                Ok(()) => return Ok(()),
-                Err(e) => {
-                    attempts += 1;
-                    if attempts >= config.retry.max_attempts {
-                        return Err(e);
-                    }
-
-                    // Apply exponential backoff with max delay limit
-                    delay = (delay as f64 * config.retry.backoff_multiplier) as u64;
-                    delay = delay.min(config.retry.max_delay_ms);
-
-                    warn!(
-                        "V2 collection attempt {} failed, retrying in {}ms: {}",
-                        attempts, delay, e
-                    );
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-                }
-            }
-        }
-    }
+    /// Start dedicated polling tasks for hot pools
+    ///
+    /// Pools listed in `subgraph.hot_pool_intervals` are grouped by interval and
+    /// each group is polled on its own timer against both subgraphs. The default
+    /// V2/V3 tasks exclude these pools so their swaps are not fetched twice.
+    async fn start_hot_pool_collection(&mut self) -> Result<()> {
+        for (interval_duration, pools) in Self::hot_pool_groups(&self.config) {
+            let mut ctx = self.collection_context();
+            let metrics_collector = self.metrics_collector.clone();
+            let pool_filter = PoolFilter::Only(pools);
+            let mut poll_trigger = Some(self.poll_trigger.subscribe());
+            let mut config_updates = self.live_config.subscribe();
 
-    /// Collect V2 swap events
-    async fn collect_v2_events(
-        subgraph_client: &SubgraphClient,
-        redis_publisher: &RedisPublisher,
-        metrics_collector: &MetricsCollector,
-    ) -> Result<()> {
-        let query = r#"
-            query GetRecentSwaps($first: Int!) {
-                swaps(
-                    first: $first
-                    orderBy: timestamp
-                    orderDirection: desc
-                ) {
-                    id
-                    timestamp
-                    pair {
-                        id
-                        token0 {
-                            id
-                            symbol
-                            name
-                            decimals
-                        }
-                        token1 {
-                            id
-                            symbol
-                            name
-                            decimals
-                        }
-                        reserve0
-                        reserve1
-                        volume_usd
-                    }
-                    sender
-                    amount0_in
-                    amount1_in
-                    amount0_out
-                    amount1_out
-                    to
-                    log_index
-                    amount_usd
-                }
-            }
-        "#;
+            info!(
+                "Hot pool collection every {}s for {:?}",
+                interval_duration.as_secs(),
+                pool_filter
+            );
 
-        let variables = serde_json::json!({
-            "first": 100
-        });
+            let mut interval_timer = interval(interval_duration);
 
-        let result = subgraph_client
-            .query_uniswap_v2(query, Some(variables))
-            .await
-            .map_err(|e| {
-                // Check if this looks like a DNS resolution error
-                if e.to_string().contains("dns")
-                    || e.to_string().contains("resolve")
-                    || e.to_string().contains("lookup")
+            let cancel = self.cancel.clone();
+            self.spawn_draining_task(async move {
+                while let Some(triggered) = Self::next_poll_with_reload(
+                    &cancel,
+                    &mut interval_timer,
+                    &mut poll_trigger,
+                    &mut ctx,
+                    &mut config_updates,
+                    false,
+                )
+                .await
                 {
-                    crate::error::DAppError::Network(
-                        crate::error::NetworkError::dns_resolution_error(format!(
-                            "DNS resolution error in V2 query: {}",
-                            e
-                        )),
-                    )
-                }
-                // Check if this looks like a Solana program error
-                else if e.to_string().contains("solana") && e.to_string().contains("program") {
-                    crate::error::DAppError::Solana(crate::error::SolanaError::program_error(
-                        format!("Solana program error in V2 query: {}", e),
-                    ))
-                } else {
-                    // Use Transaction error for query failures that might be transaction-related
-                    crate::error::DAppError::Ethereum(crate::error::EthereumError::Transaction(
-                        format!("V2 subgraph query failed: {}", e),
-                    ))
-                }
-            })?;
-
-        if let Some(data) = result.data {
-            if let Some(swaps) = data.get("swaps") {
-                if let Some(swaps_array) = swaps.as_array() {
-                    let mut events = Vec::new();
-
-                    for swap_data in swaps_array {
-                        match Self::parse_v2_swap_event(swap_data) {
-                            Ok(swap_event) => events.push(swap_event),
-                            Err(e) => {
-                                // Use EventParsing error for parsing failures
-                                let eth_error = crate::error::EthereumError::EventParsing(format!(
-                                    "Failed to parse V2 swap event: {}",
-                                    e
-                                ));
-                                error!("{}", eth_error);
-                                metrics_collector.record_error();
-                            }
-                        }
+                    if triggered {
+                        debug!("Triggered hot pool poll");
                     }
 
-                    if !events.is_empty() {
-                        debug!("Collected {} V2 swap events", events.len());
-
-                        // Publish events to Redis
-                        redis_publisher.publish_batch(&events).await?;
+                    if let Err(e) = Self::collect_v2_events_with_retry(&ctx, &pool_filter).await {
+                        error!("Error collecting hot pool V2 events after retries: {}", e);
+                        metrics_collector.record_error();
+                    }
 
-                        // Update metrics
-                        metrics_collector.record_events_processed(events.len() as u64);
+                    if let Err(e) = Self::collect_v3_events_with_retry(&ctx, &pool_filter).await {
+                        error!("Error collecting hot pool V3 events after retries: {}", e);
+                        metrics_collector.record_error();
                     }
                 }
-            }
+            });
         }
 
         Ok(())
     }
 
-    /// Collect V3 swap events with retry logic
-    async fn collect_v3_events_with_retry(
-        subgraph_client: &SubgraphClient,
-        redis_publisher: &RedisPublisher,
-        metrics_collector: &MetricsCollector,
-        config: &AppConfig,
-    ) -> Result<()> {
-        let mut attempts = 0;
-        let mut delay = config.retry.initial_delay_ms;
-
-        loop {
-            match Self::collect_v3_events(subgraph_client, redis_publisher, metrics_collector).await
-            {
-                Ok(()) => return Ok(()),
-                Err(e) => {
-                    attempts += 1;
-                    if attempts >= config.retry.max_attempts {
-                        return Err(e);
-                    }
-
-                    // Apply exponential backoff with max delay limit
-                    delay = (delay as f64 * config.retry.backoff_multiplier) as u64;
-                    delay = delay.min(config.retry.max_delay_ms);
+    /// Merge configs received via `with_config_updates` into the live config
+    fn start_config_reloading(&mut self) {
+        let Some(mut updates) = self.config_updates.take() else {
+            return;
+        };
 
+        let live_config = self.live_config.clone();
+        let subgraph_client = self.subgraph_client.clone();
+        self.spawn_task(async move {
+            while let Some(reloaded) = updates.recv().await {
+                let (updated, ignored) = live_config.borrow().with_live_updates(&reloaded);
+                if !ignored.is_empty() {
                     warn!(
-                        "V3 collection attempt {} failed, retrying in {}ms: {}",
-                        attempts, delay, e
+                        "Reloaded config changes {} which only take effect after a restart",
+                        ignored.join(", ")
                     );
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
                 }
+                subgraph_client
+                    .rate_limiter()
+                    .reconfigure(&updated.rate_limiting);
+                live_config.send_replace(updated);
+                info!("Applied reloaded configuration");
             }
-        }
+            debug!("Config update channel closed");
+        });
     }
 
-    /// Collect V3 swap events
-    async fn collect_v3_events(
-        subgraph_client: &SubgraphClient,
-        redis_publisher: &RedisPublisher,
-        metrics_collector: &MetricsCollector,
+    /// Forward on-demand poll triggers to the polling tasks
+    fn start_trigger_forwarding(&mut self) {
+        let Some(trigger_rx) = self.trigger_rx.take() else {
+            return;
+        };
+
+        let min_gap = Self::trigger_min_gap(&self.config);
+        let forwarding = Self::forward_triggers(trigger_rx, self.poll_trigger.clone(), min_gap);
+        self.spawn_task(forwarding);
+    }
+
+    /// Deliver buffered events that have waited `redis.buffer_max_age_ms`
+    ///
+    /// Size and volume triggers fire as events arrive; this catches buffers
+    /// left behind when the stream goes quiet.
+    fn start_buffer_flushing(&mut self) {
+        let Some(buffer) = self.buffer.clone() else {
+            return;
+        };
+
+        let metrics_collector = self.metrics_collector.clone();
+        let mut timer = interval(buffer.max_age().max(Duration::from_millis(10)));
+        self.spawn_task(async move {
+            loop {
+                timer.tick().await;
+                if let Err(e) = buffer.flush_expired().await {
+                    error!("Error flushing buffered events: {}", e);
+                    metrics_collector.record_error();
+                }
+            }
+        });
+    }
+
+    /// Spawn a task that watches the cancellation token itself
+    ///
+    /// Used by polling loops, which stop taking new ticks once cancelled but
+    /// finish the cycle (and publish) already in progress.
+    fn spawn_draining_task(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        self.tasks.push(tokio::spawn(task));
+    }
+
+    /// Spawn a background task that exits once the collector is stopped
+    fn spawn_task(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        let cancel = self.cancel.clone();
+        self.tasks.push(tokio::spawn(async move {
+            tokio::select! {
+                biased;
+                _ = cancel.cancelled() => debug!("Collection task cancelled"),
+                _ = task => {}
+            }
+        }));
+    }
+
+    /// Minimum spacing between triggered polls, derived from the rate limit
+    fn trigger_min_gap(config: &AppConfig) -> Duration {
+        Duration::from_secs_f64(
+            1.0 / f64::from(config.rate_limiting.max_subgraph_requests_per_second.max(1)),
+        )
+    }
+
+    /// Relay trigger messages to every polling task
+    ///
+    /// Triggers that arrive within `min_gap` of the previous one are coalesced
+    /// into a single poll so external callers cannot exceed the rate limit.
+    async fn forward_triggers(
+        mut trigger_rx: mpsc::Receiver<()>,
+        poll_trigger: watch::Sender<()>,
+        min_gap: Duration,
+    ) {
+        while trigger_rx.recv().await.is_some() {
+            poll_trigger.send_replace(());
+            tokio::time::sleep(min_gap).await;
+            while trigger_rx.try_recv().is_ok() {}
+        }
+        debug!("Poll trigger channel closed");
+    }
+
+    /// `next_poll`, or `None` once `cancel` fires
+    async fn next_poll_until_cancelled(
+        cancel: &CancellationToken,
+        interval_timer: &mut Interval,
+        poll_trigger: &mut Option<watch::Receiver<()>>,
+    ) -> Option<bool> {
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => None,
+            triggered = Self::next_poll(interval_timer, poll_trigger) => Some(triggered),
+        }
+    }
+
+    /// `next_poll_until_cancelled`, adopting reloaded configuration meanwhile
+    ///
+    /// With `reset_interval`, a changed `subgraph.polling_interval_seconds`
+    /// restarts `interval_timer` with the new period.
+    async fn next_poll_with_reload(
+        cancel: &CancellationToken,
+        interval_timer: &mut Interval,
+        poll_trigger: &mut Option<watch::Receiver<()>>,
+        ctx: &mut CollectionContext,
+        config_updates: &mut watch::Receiver<AppConfig>,
+        reset_interval: bool,
+    ) -> Option<bool> {
+        loop {
+            tokio::select! {
+                next = Self::next_poll_until_cancelled(cancel, interval_timer, poll_trigger) => {
+                    return next;
+                }
+                Ok(()) = config_updates.changed() => {}
+            }
+
+            let reloaded = config_updates.borrow_and_update().clone();
+            let period = Self::polling_interval(&reloaded);
+            if reset_interval && period != Self::polling_interval(&ctx.config) {
+                info!("Polling every {}s", period.as_secs());
+                *interval_timer = interval_at(tokio::time::Instant::now() + period, period);
+            }
+            ctx.config = reloaded;
+        }
+    }
+
+    /// Wait for the next interval tick or on-demand trigger
+    ///
+    /// Returns `true` if the poll was triggered on demand. Once the trigger
+    /// source is gone only the interval timer is used.
+    async fn next_poll(
+        interval_timer: &mut Interval,
+        poll_trigger: &mut Option<watch::Receiver<()>>,
+    ) -> bool {
+        if let Some(trigger) = poll_trigger {
+            tokio::select! {
+                _ = interval_timer.tick() => return false,
+                changed = trigger.changed() => {
+                    if changed.is_ok() {
+                        return true;
+                    }
+                }
+            }
+            *poll_trigger = None;
+        }
+
+        interval_timer.tick().await;
+        false
+    }
+
+    /// Interval of the default polling tasks
+    fn polling_interval(config: &AppConfig) -> Duration {
+        Duration::from_secs(config.subgraph.polling_interval_seconds.max(1)) // Ensure minimum 1 second
+    }
+
+    /// Group hot pools by their polling interval
+    fn hot_pool_groups(config: &AppConfig) -> Vec<(Duration, Vec<String>)> {
+        let mut groups: BTreeMap<u64, Vec<String>> = BTreeMap::new();
+        for (pool, seconds) in &config.subgraph.hot_pool_intervals {
+            groups
+                .entry((*seconds).max(1)) // Ensure minimum 1 second
+                .or_default()
+                .push(pool.to_lowercase());
+        }
+
+        groups
+            .into_iter()
+            .map(|(seconds, mut pools)| {
+                pools.sort();
+                (Duration::from_secs(seconds), pools)
+            })
+            .collect()
+    }
+
+    /// Cursor keys of every polling task `start_collecting` runs
+    fn task_keys(config: &AppConfig) -> Vec<String> {
+        let default_filter = Self::default_pool_filter(config);
+        let hot_filters: Vec<PoolFilter> = Self::hot_pool_groups(config)
+            .into_iter()
+            .map(|(_, pools)| PoolFilter::Only(pools))
+            .collect();
+
+        [UniswapVersion::V2, UniswapVersion::V3]
+            .iter()
+            .flat_map(|version| {
+                std::iter::once(&default_filter)
+                    .chain(&hot_filters)
+                    .map(move |filter| CursorStore::key(version, filter))
+            })
+            .collect()
+    }
+
+    /// Pool filter for the default polling tasks
+    fn default_pool_filter(config: &AppConfig) -> PoolFilter {
+        if config.subgraph.hot_pool_intervals.is_empty() {
+            return PoolFilter::All;
+        }
+
+        let mut hot_pools: Vec<String> = config
+            .subgraph
+            .hot_pool_intervals
+            .keys()
+            .map(|pool| pool.to_lowercase())
+            .collect();
+        hot_pools.sort();
+        PoolFilter::Exclude(hot_pools)
+    }
+
+    /// Variables for the swaps query of a polling task
+    ///
+    /// Without a cursor the latest swaps are fetched newest first; with one the
+    /// query resumes after it in ascending order.
+    fn swaps_query_variables(
+        config: &AppConfig,
+        version: &UniswapVersion,
+        pool_filter: &PoolFilter,
+        cursor: Option<&SwapCursor>,
+    ) -> serde_json::Value {
+        let where_clause = pool_filter.where_clause(version);
+        let page_size = config.subgraph.query_page_size;
+
+        match cursor {
+            Some(cursor) => serde_json::json!({
+                "first": page_size,
+                "where": cursor.where_clause(where_clause),
+                "orderDirection": "asc"
+            }),
+            None => serde_json::json!({
+                "first": page_size,
+                "where": where_clause,
+                "orderDirection": "desc"
+            }),
+        }
+    }
+
+    /// Replace token decimals with values cross-checked across both subgraphs
+    ///
+    /// Each distinct token is looked up once, concurrently; the client
+    /// coalesces lookups and bounds how many run at once.
+    async fn verify_decimals(subgraph_client: &SubgraphClient, events: &mut [SwapEvent]) {
+        let mut tokens: Vec<(String, u8)> = Vec::new();
+        for event in events.iter() {
+            for token in [&event.token_in, &event.token_out] {
+                let address = token.address.to_lowercase();
+                if !tokens.iter().any(|(known, _)| *known == address) {
+                    tokens.push((address, token.decimals));
+                }
+            }
+        }
+
+        let resolved = futures::future::join_all(tokens.iter().map(|(address, reported)| {
+            subgraph_client.verified_token_decimals(address, *reported)
+        }))
+        .await;
+        let decimals: HashMap<&str, u8> = tokens
+            .iter()
+            .map(|(address, _)| address.as_str())
+            .zip(resolved)
+            .collect();
+
+        for event in events.iter_mut() {
+            for token in [&mut event.token_in, &mut event.token_out] {
+                if let Some(verified) = decimals.get(token.address.to_lowercase().as_str()) {
+                    token.decimals = *verified;
+                }
+            }
+        }
+    }
+
+    /// Backoff between collection retries, allowing `retry.max_attempts` attempts in total
+    fn collection_backoff(config: &AppConfig) -> ExponentialBackoff {
+        ExponentialBackoff::new(
+            Duration::from_millis(config.retry.initial_delay_ms),
+            Duration::from_millis(config.retry.max_delay_ms),
+            config.retry.backoff_multiplier,
+            config.retry.max_attempts.saturating_sub(1),
+        )
+    }
+
+    /// How long to wait before retrying a failed collection, if at all
+    ///
+    /// Permanent errors are not retried, and each retry draws from the
+    /// global retry budget when one is configured. A rate-limited subgraph's
+    /// `Retry-After` replaces the backoff `delay`.
+    fn collection_retry_delay(
+        ctx: &CollectionContext,
+        source: &str,
+        error: &DAppError,
+        delay: Duration,
+    ) -> Option<Duration> {
+        if !error.is_retryable() {
+            return None;
+        }
+        if ctx
+            .retry_budget
+            .as_ref()
+            .is_some_and(|budget| !budget.try_acquire())
+        {
+            warn!(
+                "Global retry budget exhausted, not retrying {} collection: {}",
+                source, error
+            );
+            return None;
+        }
+
+        let delay = error.retry_after().unwrap_or(delay);
+        warn!(
+            "{} collection failed, retrying in {}ms: {}",
+            source,
+            delay.as_millis(),
+            error
+        );
+        Some(delay)
+    }
+
+    /// Collect V2 swap events with retry logic
+    async fn collect_v2_events_with_retry(
+        ctx: &CollectionContext,
+        pool_filter: &PoolFilter,
     ) -> Result<()> {
+        retry_async_with(
+            Self::collection_backoff(&ctx.config),
+            || async {
+                let result = Self::collect_v2_events(ctx, pool_filter).await;
+                ctx.metrics_collector
+                    .record_source_result("uniswap_v2", result.is_err());
+                result
+            },
+            |e, delay| Self::collection_retry_delay(ctx, "V2", e, delay),
+        )
+        .await
+    }
+
+    /// Collect V2 swap events
+    async fn collect_v2_events(ctx: &CollectionContext, pool_filter: &PoolFilter) -> Result<()> {
+        let CollectionContext {
+            config,
+            subgraph_client,
+            sink,
+            clock,
+            metrics_collector,
+            cursors,
+            dedup,
+            recent_ids,
+            shedder,
+            candles,
+            block_summaries,
+            catchup,
+            last_v2_block,
+            ..
+        } = ctx;
         let query = r#"
-            query GetRecentSwaps($first: Int!) {
+            query GetRecentSwaps($first: Int!, $where: Swap_filter, $orderDirection: OrderDirection) {
                 swaps(
                     first: $first
+                    where: $where
                     orderBy: timestamp
-                    orderDirection: desc
+                    orderDirection: $orderDirection
                 ) {
                     id
                     timestamp
-                    pool {
+                    pair {
                         id
                         token0 {
                             id
@@ -417,423 +1204,771 @@ impl SwapEventCollector {
                             name
                             decimals
                         }
-                        fee_tier
-                        liquidity
+                        reserve0
+                        reserve1
+                        token0_price
+                        token1_price
                         volume_usd
-                        fees_usd
-                        total_value_locked_usd
                     }
-                    token0
-                    token1
                     sender
-                    recipient
-                    origin
-                    amount0
-                    amount1
+                    amount0_in
+                    amount1_in
+                    amount0_out
+                    amount1_out
+                    to
+                    log_index
                     amount_usd
-                    sqrt_price_x96
-                    liquidity
-                    tick
+                    transaction {
+                        block_number
+                        timestamp
+                    }
+                }
+                _meta {
+                    block {
+                        number
+                    }
                 }
             }
         "#;
 
-        let variables = serde_json::json!({
-            "first": 100
-        });
+        let cursor_key = CursorStore::key(&UniswapVersion::V2, pool_filter);
+        let cursor = cursors.resume_from(&cursor_key, clock.now());
+        let variables =
+            Self::swaps_query_variables(config, &UniswapVersion::V2, pool_filter, cursor.as_ref());
 
+        let fetch_started = Instant::now();
         let result = subgraph_client
-            .query_uniswap_v3(query, Some(variables))
+            .query_uniswap_v2(query, Some(variables))
             .await
-            .map_err(|e| {
-                // Check if this looks like a DNS resolution error
-                if e.to_string().contains("dns")
-                    || e.to_string().contains("resolve")
-                    || e.to_string().contains("lookup")
-                {
-                    crate::error::DAppError::Network(
-                        crate::error::NetworkError::dns_resolution_error(format!(
-                            "DNS resolution error in V3 query: {}",
-                            e
-                        )),
-                    )
-                }
-                // Check if this looks like a Solana transaction error
-                else if e.to_string().contains("solana") && e.to_string().contains("transaction")
-                {
-                    crate::error::DAppError::Solana(crate::error::SolanaError::transaction_error(
-                        format!("Solana transaction error in V3 query: {}", e),
-                    ))
-                } else {
-                    // Use Transaction error for query failures that might be transaction-related
-                    crate::error::DAppError::Ethereum(crate::error::EthereumError::Transaction(
-                        format!("V3 subgraph query failed: {}", e),
-                    ))
-                }
-            })?;
+            .map_err(classify_query_error)?;
+        metrics_collector.record_request_latency(fetch_started.elapsed());
+        metrics_collector.record_stage(PipelineStage::Fetch, fetch_started.elapsed());
+
+        if config.subgraph.validate_response_shape {
+            validate_swaps_shape(
+                &UniswapVersion::V2,
+                result.data.as_ref(),
+                config.subgraph.max_invalid_swap_percent,
+            )?;
+        }
 
         if let Some(data) = result.data {
             if let Some(swaps) = data.get("swaps") {
                 if let Some(swaps_array) = swaps.as_array() {
+                    let fetched = swaps_array.len();
+                    if let Some(lag) = publish_lag_seconds(swaps_array, clock.now()) {
+                        metrics_collector.set_enrichment_shed(shedder.observe_lag(lag));
+                    }
+                    let swaps_array = CursorStore::unseen(cursor.as_ref(), swaps_array);
+                    let swaps_array =
+                        confirmed_swaps(&data, swaps_array, config.subgraph.min_confirmations);
+                    let parse_started = Instant::now();
                     let mut events = Vec::new();
+                    let mut quarantined = Vec::new();
 
-                    for swap_data in swaps_array {
-                        match Self::parse_v3_swap_event(swap_data) {
-                            Ok(swap_event) => events.push(swap_event),
+                    for swap_data in swaps_array.iter().copied() {
+                        match Self::parse_v2_swap_event(swap_data) {
+                            Ok(mut swap_event) => {
+                                Self::sanitize_token_metadata(config, &mut swap_event);
+                                events.push(swap_event);
+                            }
                             Err(e) => {
                                 // Use EventParsing error for parsing failures
                                 let eth_error = crate::error::EthereumError::EventParsing(format!(
-                                    "Failed to parse V3 swap event: {}",
+                                    "Failed to parse V2 swap event: {}",
                                     e
                                 ));
                                 error!("{}", eth_error);
                                 metrics_collector.record_error();
+                                if config.redis.quarantine_channel.is_some() {
+                                    quarantined.push(QuarantinedSwap {
+                                        source: "uniswap_v2".to_string(),
+                                        reason: e.to_string(),
+                                        payload: swap_data.clone(),
+                                        quarantined_at: clock.now(),
+                                    });
+                                }
                             }
                         }
                     }
+                    Self::quarantine(sink.as_ref(), &quarantined).await;
+                    let filtered = Self::retain_allowlisted(config, &mut events);
+                    metrics_collector.record_events_filtered(filtered as u64);
+
+                    metrics_collector.record_stage(PipelineStage::Parse, parse_started.elapsed());
 
                     if !events.is_empty() {
-                        debug!("Collected {} V3 swap events", events.len());
+                        debug!("Collected {} V2 swap events", events.len());
+
+                        events = Self::enrich_events(ctx, events).await;
+
+                        // Hold live events back while a backfill catches up to them
+                        if let Some(catchup) = catchup.lock().await.as_mut() {
+                            events = catchup.push_live(events);
+                        }
+
+                        // Skip ids already published, including before a restart, and
+                        // reserve the rest so a concurrent task cannot publish them too
+                        if let Some(recent_ids) = recent_ids {
+                            recent_ids.filter_unseen(&mut events);
+                        }
+                        if let Some(dedup) = dedup {
+                            dedup.reserve_unseen(&mut events);
+                        }
+
+                        // Publish events to the sink
+                        let publish_started = Instant::now();
+                        if let Err(e) = Self::publish_events(config, sink.as_ref(), &events).await {
+                            if let Some(dedup) = dedup {
+                                dedup.release(&events);
+                            }
+                            return Err(e);
+                        }
+                        metrics_collector.record_request_latency(publish_started.elapsed());
+
+                        // Only remember ids once delivered, so a failed publish is retried
+                        if let Some(dedup) = dedup {
+                            dedup.record_published(&events);
+                        }
+                        if let Some(recent_ids) = recent_ids {
+                            recent_ids.record_published(&events);
+                        }
+                        if let Some(block) = events.iter().map(|event| event.block_number).max() {
+                            last_v2_block.fetch_max(block, Ordering::Relaxed);
+                        }
 
-                        // Publish events to Redis
-                        redis_publisher.publish_batch(&events).await?;
+                        // Publish candles completed by this batch
+                        if let Some(candles) = candles {
+                            let completed = candles.add_events(&events, clock.now());
+                            Self::publish_candles(ctx, &completed).await;
+                        }
+                        metrics_collector
+                            .record_stage(PipelineStage::Publish, publish_started.elapsed());
 
                         // Update metrics
                         metrics_collector.record_events_processed(events.len() as u64);
+                        for event in &events {
+                            metrics_collector.record_pool_events(&event.pool_address, 1);
+                            metrics_collector.record_token_events(
+                                &event.token_in.address,
+                                &event.token_in.symbol,
+                                1,
+                            );
+                            metrics_collector.record_token_events(
+                                &event.token_out.address,
+                                &event.token_out.symbol,
+                                1,
+                            );
+                        }
+                    }
+
+                    // Summarize blocks once every polling task has delivered them
+                    if let Some(block_summaries) = block_summaries {
+                        let page_full =
+                            cursor.is_some() && fetched >= config.subgraph.query_page_size as usize;
+                        let through_block = delivered_through(
+                            &data,
+                            &swaps_array,
+                            page_full,
+                            config.subgraph.min_confirmations,
+                        );
+                        let summaries =
+                            block_summaries.add_events(&cursor_key, &events, through_block);
+                        Self::publish_block_summaries(ctx, &summaries).await;
                     }
+
+                    // Resume after the newest swap once the batch is delivered
+                    cursors.advance(&cursor_key, &swaps_array);
                 }
             }
         }
 
+        if let Some(dedup) = dedup {
+            dedup.persist().await;
+        }
         Ok(())
     }
 
-    /// Parse V2 swap event from subgraph data
-    fn parse_v2_swap_event(swap_data: &serde_json::Value) -> Result<SwapEvent> {
-        // Check for Solana-style instruction data that might be mixed in
-        if let Some(instruction_data) = swap_data.get("instruction") {
-            if instruction_data.as_str().unwrap_or("").contains("solana") {
-                return Err(crate::error::DAppError::Solana(
-                    crate::error::SolanaError::instruction_error(
-                        "Solana instruction data found in Ethereum V2 swap event".to_string(),
-                    ),
-                ));
-            }
+    /// Verify decimals, set directions and apply the transform script
+    ///
+    /// Shared by live collection and backfill so both publish the same shape.
+    /// Decimals verification is skipped while enrichment is shed.
+    async fn enrich_events(ctx: &CollectionContext, mut events: Vec<SwapEvent>) -> Vec<SwapEvent> {
+        let config = &ctx.config;
+        let enrich_started = Instant::now();
+        if config.subgraph.verify_decimals && !ctx.shedder.is_shedding() {
+            Self::verify_decimals(&ctx.subgraph_client, &mut events).await;
         }
 
-        let pair = swap_data
-            .get("pair")
-            .ok_or_else(|| crate::error::DAppError::Internal("Missing pair data".to_string()))?;
-
-        let token0 = pair
-            .get("token0")
-            .ok_or_else(|| crate::error::DAppError::Internal("Missing token0 data".to_string()))?;
-
-        // Check for Solana-style public keys that might be mixed in
-        if let Some(token_id) = token0.get("id") {
-            if let Some(id_str) = token_id.as_str() {
-                if id_str.len() == 44 && id_str.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                    return Err(crate::error::DAppError::Solana(
-                        crate::error::SolanaError::invalid_public_key(
-                            "Solana-style public key found in Ethereum token0".to_string(),
-                        ),
-                    ));
-                }
+        if let Some(reference) = &config.application.reference_token {
+            for event in events.iter_mut() {
+                event.direction = event.direction_vs(reference);
             }
         }
 
-        let token1 = pair
-            .get("token1")
-            .ok_or_else(|| crate::error::DAppError::Internal("Missing token1 data".to_string()))?;
+        if let Some(transformer) = &ctx.transformer {
+            events = transformer.transform_batch(events);
+        }
+        ctx.metrics_collector
+            .record_stage(PipelineStage::Enrich, enrich_started.elapsed());
+        events
+    }
 
-        // Check for Solana-style public keys that might be mixed in
-        if let Some(token_id) = token1.get("id") {
-            if let Some(id_str) = token_id.as_str() {
-                if id_str.len() == 44 && id_str.chars().all(|c| c.is_alphanumeric() || c == '_') {
-                    return Err(crate::error::DAppError::Solana(
-                        crate::error::SolanaError::invalid_public_key(
-                            "Solana-style public key found in Ethereum token1".to_string(),
-                        ),
-                    ));
+    /// Clean token names and symbols per `subgraph.sanitize_token_metadata`,
+    /// then bound them to `max_token_name_len`/`max_token_symbol_len`
+    fn sanitize_token_metadata(config: &AppConfig, event: &mut SwapEvent) {
+        let subgraph = &config.subgraph;
+        let id = &event.id;
+        for token in [&mut event.token_in, &mut event.token_out] {
+            let fields = [
+                ("symbol", &mut token.symbol, subgraph.max_token_symbol_len),
+                ("name", &mut token.name, subgraph.max_token_name_len),
+            ];
+            for (field, value, max_len) in fields {
+                if subgraph.sanitize_token_metadata {
+                    if let Some(clean) = strip_unsafe_chars(value) {
+                        warn!(
+                            "Sanitized token {} {} in swap {}: {:?} -> {:?}",
+                            token.address, field, id, value, clean
+                        );
+                        *value = clean;
+                    }
+                }
+                if let Some(truncated) = truncate_chars(value, max_len) {
+                    debug!(
+                        "Truncated token {} {} in swap {} to {} characters",
+                        token.address, field, id, max_len
+                    );
+                    *value = truncated;
                 }
             }
         }
+    }
 
-        let token_in = TokenInfo {
-            address: token0
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            symbol: token0
-                .get("symbol")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            name: token0
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            decimals: token0
-                .get("decimals")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(18) as u8,
-            logo_uri: None,
-            price_usd: None,
-            market_cap: None,
-        };
-
-        let token_out = TokenInfo {
-            address: token1
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            symbol: token1
-                .get("symbol")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            name: token1
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            decimals: token1
-                .get("decimals")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(18) as u8,
-            logo_uri: None,
-            price_usd: None,
-            market_cap: None,
-        };
-
-        let amount_in = swap_data
-            .get("amount0_in")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
-        let amount_out = swap_data
-            .get("amount1_out")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
-        let user_address = swap_data
-            .get("sender")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let pool_address = pair
-            .get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        // Use the builder pattern for better validation and error handling
-        let mut swap_event = SwapEvent::builder()
-            .version(UniswapVersion::V2)
-            .transaction_hash(
-                swap_data
-                    .get("id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-            )
-            .pool_address(pool_address)
-            .token_in(token_in)
-            .token_out(token_out)
-            .amount_in(amount_in)
-            .amount_out(amount_out)
-            .user_address(user_address)
-            .build()
-            .map_err(|e| {
-                error!("Failed to build SwapEvent using builder: {}", e);
-                crate::error::DAppError::Internal(format!("SwapEvent builder failed: {}", e))
-            })?;
-
-        // Add pool information
-        if let Some(pool_info) = Self::extract_pool_info(pair) {
-            swap_event.add_pool_info(pool_info);
+    /// Drop events outside `subgraph.pool_allowlist`/`token_allowlist`, returning how many
+    ///
+    /// An event passes the token allowlist if either of its tokens is on it.
+    /// Empty allowlists let everything through.
+    fn retain_allowlisted(config: &AppConfig, events: &mut Vec<SwapEvent>) -> usize {
+        let pools = &config.subgraph.pool_allowlist;
+        let tokens = &config.subgraph.token_allowlist;
+        if pools.is_empty() && tokens.is_empty() {
+            return 0;
         }
 
-        Ok(swap_event)
+        let listed = |list: &[String], address: &str| {
+            list.iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(address))
+        };
+        let before = events.len();
+        events.retain(|event| {
+            (pools.is_empty() || listed(pools, &event.pool_address))
+                && (tokens.is_empty()
+                    || listed(tokens, &event.token_in.address)
+                    || listed(tokens, &event.token_out.address))
+        });
+        before - events.len()
     }
 
-    /// Parse V3 swap event from subgraph data
-    fn parse_v3_swap_event(swap_data: &serde_json::Value) -> Result<SwapEvent> {
-        // Check for Solana-style instruction data that might be mixed in
-        if let Some(instruction_data) = swap_data.get("instruction") {
-            if instruction_data.as_str().unwrap_or("").contains("solana") {
-                return Err(crate::error::DAppError::Solana(
-                    crate::error::SolanaError::instruction_error(
-                        "Solana instruction data found in Ethereum V3 swap event".to_string(),
-                    ),
-                ));
-            }
+    /// Hand swaps that failed to parse to the sink's quarantine
+    ///
+    /// A failed quarantine is logged rather than failing the batch, which
+    /// would hold back the swaps that did parse.
+    async fn quarantine(sink: &dyn EventSink, swaps: &[QuarantinedSwap]) {
+        if swaps.is_empty() {
+            return;
         }
+        if let Err(e) = sink.publish_quarantined(swaps).await {
+            warn!("Failed to quarantine {} swaps: {}", swaps.len(), e);
+        }
+    }
 
-        let pool = swap_data
-            .get("pool")
-            .ok_or_else(|| crate::error::DAppError::Internal("Missing pool data".to_string()))?;
-
-        let token0 = pool
-            .get("token0")
-            .ok_or_else(|| crate::error::DAppError::Internal("Missing token0 data".to_string()))?;
-
-        let token1 = pool
-            .get("token1")
-            .ok_or_else(|| crate::error::DAppError::Internal("Missing token1 data".to_string()))?;
-
-        let token_in = TokenInfo {
-            address: token0
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            symbol: token0
-                .get("symbol")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            name: token0
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            decimals: token0
-                .get("decimals")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(18) as u8,
-            logo_uri: None,
-            price_usd: None,
-            market_cap: None,
-        };
-
-        let token_out = TokenInfo {
-            address: token1
-                .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            symbol: token1
-                .get("symbol")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            name: token1
-                .get("name")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            decimals: token1
-                .get("decimals")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(18) as u8,
-            logo_uri: None,
-            price_usd: None,
-            market_cap: None,
+    /// Publish a batch, keyed by the configured partition strategy if any
+    async fn publish_events(
+        config: &AppConfig,
+        sink: &dyn EventSink,
+        events: &[SwapEvent],
+    ) -> Result<()> {
+        let Some(strategy) = config.application.partition_key else {
+            return sink.publish_batch(events).await;
         };
 
-        let amount_in = swap_data
-            .get("amount0")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
-        let amount_out = swap_data
-            .get("amount1")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0")
-            .to_string();
-        let user_address = swap_data
-            .get("sender")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let pool_address = pool
-            .get("id")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        // Use the builder pattern for better validation and error handling
-        let mut swap_event = SwapEvent::builder()
-            .version(UniswapVersion::V3)
-            .transaction_hash(
-                swap_data
-                    .get("id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string(),
-            )
-            .pool_address(pool_address)
-            .token_in(token_in)
-            .token_out(token_out)
-            .amount_in(amount_in)
-            .amount_out(amount_out)
-            .user_address(user_address)
-            .build()
-            .map_err(|e| {
-                error!("Failed to build SwapEvent using builder: {}", e);
-                crate::error::DAppError::Internal(format!("SwapEvent builder failed: {}", e))
-            })?;
-
-        // Add pool information
-        if let Some(pool_info) = Self::extract_pool_info(pool) {
-            swap_event.add_pool_info(pool_info);
-        }
-
-        Ok(swap_event)
+        let keys: Vec<String> = events
+            .iter()
+            .map(|event| event.partition_key(strategy))
+            .collect();
+        let keyed: Vec<KeyedEvent<'_>> = events
+            .iter()
+            .zip(&keys)
+            .map(|(event, key)| KeyedEvent {
+                key: Some(key),
+                event,
+            })
+            .collect();
+        sink.publish_keyed_batch(&keyed).await
     }
 
-    /// Extract pool information from subgraph data
-    fn extract_pool_info(pool_data: &serde_json::Value) -> Option<PoolInfo> {
-        let token0 = pool_data.get("token0")?;
-        let token1 = pool_data.get("token1")?;
-
-        Some(PoolInfo {
-            address: pool_data.get("id")?.as_str()?.to_string(),
-            token0: token0.get("id")?.as_str()?.to_string(),
-            token1: token1.get("id")?.as_str()?.to_string(),
-            fee_tier: pool_data
-                .get("fee_tier")
-                .and_then(|v| v.as_u64())
-                .map(|v| v as u32),
-            liquidity: pool_data
-                .get("liquidity")
-                .and_then(|v| v.as_str())
-                .map(|v| v.to_string()),
-            volume_24h: pool_data
-                .get("volume_usd")
-                .and_then(|v| v.as_str())
-                .map(|v| v.to_string()),
-            fees_24h: pool_data
-                .get("fees_usd")
-                .and_then(|v| v.as_str())
-                .map(|v| v.to_string()),
-            apy: None,        // Would need to calculate from historical data
-            created_at: None, // Would need to parse timestamp
-        })
+    /// Publish completed candles
+    ///
+    /// Failures are logged rather than returned, as for block summaries: the
+    /// swaps are already published, so the candles are dropped instead.
+    async fn publish_candles(ctx: &CollectionContext, candles: &[OhlcCandle]) {
+        if candles.is_empty() {
+            return;
+        }
+        if let Err(e) = ctx.sink.publish_candles(candles).await {
+            error!("Failed to publish {} candles: {}", candles.len(), e);
+            ctx.metrics_collector.record_error();
+        }
     }
 
-    /// Get collector status
-    #[allow(dead_code)]
-    pub fn status(&self) -> CollectorStatus {
-        CollectorStatus {
-            is_running: self.is_running,
-            last_v2_block: self._last_v2_block,
-            last_v3_block: self._last_v3_block,
+    /// Publish completed block summaries
+    ///
+    /// Failures are logged rather than returned: the swaps are already
+    /// published, and retrying the cycle would publish them again.
+    async fn publish_block_summaries(ctx: &CollectionContext, summaries: &[BlockSummary]) {
+        if summaries.is_empty() {
+            return;
+        }
+        if let Err(e) = ctx.sink.publish_block_summaries(summaries).await {
+            error!(
+                "Failed to publish {} block summaries: {}",
+                summaries.len(),
+                e
+            );
+            ctx.metrics_collector.record_error();
         }
     }
 
-    /// Test JSON event creation
-    pub fn test_json_event_creation(&self) -> Result<()> {
+    /// Collect V3 swap events with retry logic
+    async fn collect_v3_events_with_retry(
+        ctx: &CollectionContext,
+        pool_filter: &PoolFilter,
+    ) -> Result<()> {
+        retry_async_with(
+            Self::collection_backoff(&ctx.config),
+            || async {
+                let result = Self::collect_v3_events(ctx, pool_filter).await;
+                ctx.metrics_collector
+                    .record_source_result("uniswap_v3", result.is_err());
+                result
+            },
+            |e, delay| Self::collection_retry_delay(ctx, "V3", e, delay),
+        )
+        .await
+    }
+
+    /// Collect V3 swap events
+    async fn collect_v3_events(ctx: &CollectionContext, pool_filter: &PoolFilter) -> Result<()> {
+        let CollectionContext {
+            config,
+            subgraph_client,
+            sink,
+            clock,
+            metrics_collector,
+            cursors,
+            dedup,
+            recent_ids,
+            shedder,
+            candles,
+            block_summaries,
+            catchup,
+            last_v3_block,
+            ..
+        } = ctx;
+        let query = r#"
+            query GetRecentSwaps($first: Int!, $where: Swap_filter, $orderDirection: OrderDirection) {
+                swaps(
+                    first: $first
+                    where: $where
+                    orderBy: timestamp
+                    orderDirection: $orderDirection
+                ) {
+                    id
+                    timestamp
+                    pool {
+                        id
+                        token0 {
+                            id
+                            symbol
+                            name
+                            decimals
+                        }
+                        token1 {
+                            id
+                            symbol
+                            name
+                            decimals
+                        }
+                        fee_tier
+                        liquidity
+                        token0_price
+                        token1_price
+                        volume_usd
+                        fees_usd
+                        total_value_locked_usd
+                    }
+                    token0
+                    token1
+                    sender
+                    recipient
+                    origin
+                    amount0
+                    amount1
+                    amount_usd
+                    sqrt_price_x96
+                    liquidity
+                    tick
+                    log_index
+                    block {
+                        number
+                        timestamp
+                    }
+                }
+                _meta {
+                    block {
+                        number
+                    }
+                }
+            }
+        "#;
+
+        let cursor_key = CursorStore::key(&UniswapVersion::V3, pool_filter);
+        let cursor = cursors.resume_from(&cursor_key, clock.now());
+        let variables =
+            Self::swaps_query_variables(config, &UniswapVersion::V3, pool_filter, cursor.as_ref());
+
+        let fetch_started = Instant::now();
+        let result = subgraph_client
+            .query_uniswap_v3(query, Some(variables))
+            .await
+            .map_err(classify_query_error)?;
+        metrics_collector.record_request_latency(fetch_started.elapsed());
+        metrics_collector.record_stage(PipelineStage::Fetch, fetch_started.elapsed());
+
+        if config.subgraph.validate_response_shape {
+            validate_swaps_shape(
+                &UniswapVersion::V3,
+                result.data.as_ref(),
+                config.subgraph.max_invalid_swap_percent,
+            )?;
+        }
+
+        if let Some(data) = result.data {
+            if let Some(swaps) = data.get("swaps") {
+                if let Some(swaps_array) = swaps.as_array() {
+                    let fetched = swaps_array.len();
+                    if let Some(lag) = publish_lag_seconds(swaps_array, clock.now()) {
+                        metrics_collector.set_enrichment_shed(shedder.observe_lag(lag));
+                    }
+                    let swaps_array = CursorStore::unseen(cursor.as_ref(), swaps_array);
+                    let swaps_array =
+                        confirmed_swaps(&data, swaps_array, config.subgraph.min_confirmations);
+                    let parse_started = Instant::now();
+                    let mut events = Vec::new();
+                    let mut quarantined = Vec::new();
+
+                    for swap_data in swaps_array.iter().copied() {
+                        match Self::parse_v3_swap_event(swap_data) {
+                            Ok(mut swap_event) => {
+                                Self::sanitize_token_metadata(config, &mut swap_event);
+                                events.push(swap_event);
+                            }
+                            Err(e) => {
+                                // Use EventParsing error for parsing failures
+                                let eth_error = crate::error::EthereumError::EventParsing(format!(
+                                    "Failed to parse V3 swap event: {}",
+                                    e
+                                ));
+                                error!("{}", eth_error);
+                                metrics_collector.record_error();
+                                if config.redis.quarantine_channel.is_some() {
+                                    quarantined.push(QuarantinedSwap {
+                                        source: "uniswap_v3".to_string(),
+                                        reason: e.to_string(),
+                                        payload: swap_data.clone(),
+                                        quarantined_at: clock.now(),
+                                    });
+                                }
+                            }
+                        }
+                    }
+                    Self::quarantine(sink.as_ref(), &quarantined).await;
+                    let filtered = Self::retain_allowlisted(config, &mut events);
+                    metrics_collector.record_events_filtered(filtered as u64);
+
+                    metrics_collector.record_stage(PipelineStage::Parse, parse_started.elapsed());
+
+                    if !events.is_empty() {
+                        debug!("Collected {} V3 swap events", events.len());
+
+                        events = Self::enrich_events(ctx, events).await;
+
+                        // Hold live events back while a backfill catches up to them
+                        if let Some(catchup) = catchup.lock().await.as_mut() {
+                            events = catchup.push_live(events);
+                        }
+
+                        // Skip ids already published, including before a restart, and
+                        // reserve the rest so a concurrent task cannot publish them too
+                        if let Some(recent_ids) = recent_ids {
+                            recent_ids.filter_unseen(&mut events);
+                        }
+                        if let Some(dedup) = dedup {
+                            dedup.reserve_unseen(&mut events);
+                        }
+
+                        // Publish events to the sink
+                        let publish_started = Instant::now();
+                        if let Err(e) = Self::publish_events(config, sink.as_ref(), &events).await {
+                            if let Some(dedup) = dedup {
+                                dedup.release(&events);
+                            }
+                            return Err(e);
+                        }
+                        metrics_collector.record_request_latency(publish_started.elapsed());
+
+                        // Only remember ids once delivered, so a failed publish is retried
+                        if let Some(dedup) = dedup {
+                            dedup.record_published(&events);
+                        }
+                        if let Some(recent_ids) = recent_ids {
+                            recent_ids.record_published(&events);
+                        }
+                        if let Some(block) = events.iter().map(|event| event.block_number).max() {
+                            last_v3_block.fetch_max(block, Ordering::Relaxed);
+                        }
+
+                        // Publish candles completed by this batch
+                        if let Some(candles) = candles {
+                            let completed = candles.add_events(&events, clock.now());
+                            Self::publish_candles(ctx, &completed).await;
+                        }
+                        metrics_collector
+                            .record_stage(PipelineStage::Publish, publish_started.elapsed());
+
+                        // Update metrics
+                        metrics_collector.record_events_processed(events.len() as u64);
+                        for event in &events {
+                            metrics_collector.record_pool_events(&event.pool_address, 1);
+                            metrics_collector.record_token_events(
+                                &event.token_in.address,
+                                &event.token_in.symbol,
+                                1,
+                            );
+                            metrics_collector.record_token_events(
+                                &event.token_out.address,
+                                &event.token_out.symbol,
+                                1,
+                            );
+                        }
+                    }
+
+                    // Summarize blocks once every polling task has delivered them
+                    if let Some(block_summaries) = block_summaries {
+                        let page_full =
+                            cursor.is_some() && fetched >= config.subgraph.query_page_size as usize;
+                        let through_block = delivered_through(
+                            &data,
+                            &swaps_array,
+                            page_full,
+                            config.subgraph.min_confirmations,
+                        );
+                        let summaries =
+                            block_summaries.add_events(&cursor_key, &events, through_block);
+                        Self::publish_block_summaries(ctx, &summaries).await;
+                    }
+
+                    // Resume after the newest swap once the batch is delivered
+                    cursors.advance(&cursor_key, &swaps_array);
+                }
+            }
+        }
+
+        if let Some(dedup) = dedup {
+            dedup.persist().await;
+        }
+        Ok(())
+    }
+
+    /// Parse V2 swap event from subgraph data
+    fn parse_v2_swap_event(swap_data: &serde_json::Value) -> Result<SwapEvent> {
+        // Check for Solana-style instruction data that might be mixed in
+        if let Some(instruction_data) = swap_data.get("instruction") {
+            if instruction_data.as_str().unwrap_or("").contains("solana") {
+                return Err(crate::error::DAppError::Solana(
+                    crate::error::SolanaError::instruction_error(
+                        "Solana instruction data found in Ethereum V2 swap event".to_string(),
+                    ),
+                ));
+            }
+        }
+
+        let swap: UniswapV2SwapEvent = serde_json::from_value(swap_data.clone())
+            .map_err(|e| SubgraphError::InvalidResponse(format!("Malformed V2 swap: {}", e)))?;
+
+        // Check for Solana-style public keys that might be mixed in
+        for (name, token) in [("token0", &swap.pair.token0), ("token1", &swap.pair.token1)] {
+            if is_solana_style_key(&token.id) {
+                return Err(crate::error::DAppError::Solana(
+                    crate::error::SolanaError::invalid_public_key(format!(
+                        "Solana-style public key found in Ethereum {}",
+                        name
+                    )),
+                ));
+            }
+        }
+
+        let mut swap_event =
+            SwapEvent::from_v2_subgraph(&swap, swap.pair.id.clone(), swap.sender.clone()).map_err(
+                |e| {
+                    error!("Failed to build SwapEvent using builder: {}", e);
+                    crate::error::DAppError::Internal(format!("SwapEvent builder failed: {}", e))
+                },
+            )?;
+
+        // Add pool information
+        swap_event.add_pool_info(Self::v2_pool_info(&swap.pair));
+
+        // The subgraph values the whole swap, so both legs share one USD amount
+        let token0_in = swap_event
+            .token_in
+            .address
+            .eq_ignore_ascii_case(&swap.pair.token0.id);
+        if let Some(amount_usd) = swap.amount_usd.as_deref().and_then(parse_amount_usd) {
+            swap_event.set_usd_amounts(amount_usd, amount_usd);
+        }
+        if let Some(in_per_out) =
+            pool_price_in_per_out(&swap.pair.token0_price, &swap.pair.token1_price, token0_in)
+        {
+            swap_event.derive_missing_usd_amount(in_per_out);
+        }
+        swap_event.log_index = swap.log_index;
+
+        // Orient the reserves the same way as the swap
+        swap_event.v2_reserves = match (swap.pair.reserve0, swap.pair.reserve1) {
+            (Some(reserve0), Some(reserve1)) if token0_in => Some(V2Reserves {
+                reserve_in: reserve0,
+                reserve_out: reserve1,
+            }),
+            (Some(reserve0), Some(reserve1)) => Some(V2Reserves {
+                reserve_in: reserve1,
+                reserve_out: reserve0,
+            }),
+            _ => None,
+        };
+
+        Ok(swap_event)
+    }
+
+    /// Parse V3 swap event from subgraph data
+    fn parse_v3_swap_event(swap_data: &serde_json::Value) -> Result<SwapEvent> {
+        // Check for Solana-style instruction data that might be mixed in
+        if let Some(instruction_data) = swap_data.get("instruction") {
+            if instruction_data.as_str().unwrap_or("").contains("solana") {
+                return Err(crate::error::DAppError::Solana(
+                    crate::error::SolanaError::instruction_error(
+                        "Solana instruction data found in Ethereum V3 swap event".to_string(),
+                    ),
+                ));
+            }
+        }
+
+        let swap: UniswapV3SwapEvent = serde_json::from_value(swap_data.clone())
+            .map_err(|e| SubgraphError::InvalidResponse(format!("Malformed V3 swap: {}", e)))?;
+
+        // The recipient receives token_out; sender is often just the router
+        let user_address = if swap.recipient.is_empty() {
+            swap.sender.clone()
+        } else {
+            swap.recipient.clone()
+        };
+        let mut swap_event = SwapEvent::from_v3_subgraph(&swap, swap.pool.id.clone(), user_address)
+            .map_err(|e| {
+                error!("Failed to build SwapEvent using builder: {}", e);
+                crate::error::DAppError::Internal(format!("SwapEvent builder failed: {}", e))
+            })?;
+
+        // Add pool information
+        swap_event.add_pool_info(Self::v3_pool_info(&swap.pool));
+
+        let token0_in = swap_event
+            .token_in
+            .address
+            .eq_ignore_ascii_case(&swap.pool.token0.id);
+        if let Some(amount_usd) = swap.amount_usd.as_deref().and_then(parse_amount_usd) {
+            swap_event.set_usd_amounts(amount_usd, amount_usd);
+        }
+        if let Some(in_per_out) =
+            pool_price_in_per_out(&swap.pool.token0_price, &swap.pool.token1_price, token0_in)
+        {
+            swap_event.derive_missing_usd_amount(in_per_out);
+        }
+        swap_event.log_index = swap.log_index;
+
+        Ok(swap_event)
+    }
+
+    /// Pool information from a V2 pair
+    fn v2_pool_info(pair: &GraphQLPair) -> PoolInfo {
+        PoolInfo {
+            address: pair.id.clone(),
+            token0: pair.token0.id.clone(),
+            token1: pair.token1.id.clone(),
+            fee_tier: None,
+            liquidity: None,
+            volume_24h: pair.volume_usd.clone(),
+            fees_24h: None,
+            apy: None,        // Would need to calculate from historical data
+            created_at: None, // Would need to parse timestamp
+        }
+    }
+
+    /// Pool information from a V3 pool
+    fn v3_pool_info(pool: &GraphQLV3Pool) -> PoolInfo {
+        PoolInfo {
+            address: pool.id.clone(),
+            token0: pool.token0.id.clone(),
+            token1: pool.token1.id.clone(),
+            fee_tier: pool.fee_tier,
+            liquidity: pool.liquidity.clone(),
+            volume_24h: pool.volume_usd.clone(),
+            fees_24h: pool.fees_usd.clone(),
+            apy: None,        // Would need to calculate from historical data
+            created_at: None, // Would need to parse timestamp
+        }
+    }
+
+    /// Get collector status
+    #[allow(dead_code)]
+    pub fn status(&self) -> CollectorStatus {
+        CollectorStatus {
+            is_running: self.is_running,
+            last_v2_block: self.last_v2_block.load(Ordering::Relaxed),
+            last_v3_block: self.last_v3_block.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Whether the background collection tasks are running
+    #[allow(dead_code)]
+    pub fn is_running(&self) -> bool {
+        self.is_running
+    }
+
+    /// Metrics recorded by this collector
+    #[allow(dead_code)]
+    pub fn metrics(&self) -> &MetricsCollector {
+        &self.metrics_collector
+    }
+
+    /// Test JSON event creation
+    pub fn test_json_event_creation(&self) -> Result<()> {
         let json_data = r#"{
             "version": "v2",
             "transaction_hash": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
             "pool_address": "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8",
             "token_in": {
-                "address": "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a",
+                "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
                 "symbol": "USDC",
                 "name": "USD Coin",
                 "decimals": 6
@@ -870,7 +2005,7 @@ impl SwapEventCollector {
             UniswapVersion::V3,
             "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890".to_string(),
             "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string(),
-            "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             "USDC".to_string(),
             "USD Coin".to_string(),
             6,
@@ -899,7 +2034,7 @@ impl SwapEventCollector {
     /// Test create_with_builder method
     pub fn test_create_with_builder(&self) -> Result<()> {
         let token_in = TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,
@@ -960,7 +2095,7 @@ impl SwapEventCollector {
             "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
             "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8",
             &TokenInfo {
-                address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                 symbol: "USDC".to_string(),
                 name: "USD Coin".to_string(),
                 decimals: 6,
@@ -989,7 +2124,7 @@ impl SwapEventCollector {
             pair: GraphQLPair {
                 id: "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string(),
                 token0: GraphQLToken {
-                    id: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                    id: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                     symbol: "USDC".to_string(),
                     name: "USD Coin".to_string(),
                     decimals: 6,
@@ -1006,9 +2141,9 @@ impl SwapEventCollector {
                     volume: None,
                     volume_usd: None,
                 },
-                reserve0: "0".to_string(),
-                reserve1: "0".to_string(),
-                total_supply: "0".to_string(),
+                reserve0: Some("0".to_string()),
+                reserve1: Some("0".to_string()),
+                total_supply: Some("0".to_string()),
                 reserve_usd: None,
                 tracked_reserve_eth: None,
                 token0_price: None,
@@ -1025,8 +2160,10 @@ impl SwapEventCollector {
             amount0_out: "0".to_string(),
             amount1_out: "0.0005".to_string(),
             to: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
-            log_index: 0,
+            log_index: Some(0),
             amount_usd: None,
+            transaction: None,
+            block: None,
         };
 
         if let Ok(event) = self.create_event_from_v2_subgraph(
@@ -1044,7 +2181,7 @@ impl SwapEventCollector {
             pool: GraphQLV3Pool {
                 id: "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string(),
                 token0: GraphQLToken {
-                    id: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                    id: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                     symbol: "USDC".to_string(),
                     name: "USD Coin".to_string(),
                     decimals: 6,
@@ -1061,8 +2198,8 @@ impl SwapEventCollector {
                     volume: None,
                     volume_usd: None,
                 },
-                fee_tier: 3000,
-                liquidity: "0".to_string(),
+                fee_tier: Some(3000),
+                liquidity: Some("0".to_string()),
                 sqrt_price: None,
                 token0_price: None,
                 token1_price: None,
@@ -1070,7 +2207,7 @@ impl SwapEventCollector {
                 fees_usd: None,
                 total_value_locked_usd: None,
             },
-            token0: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            token0: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             token1: "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string(),
             sender: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
             recipient: "0x742d35Cc6634C0532925a3b8D4C9db96C4b4d8b6".to_string(),
@@ -1078,9 +2215,12 @@ impl SwapEventCollector {
             amount0: "1000000".to_string(),
             amount1: "0.0005".to_string(),
             amount_usd: None,
-            sqrt_price_x96: "0".to_string(),
-            liquidity: "0".to_string(),
-            tick: 0,
+            sqrt_price_x96: Some("0".to_string()),
+            liquidity: Some("0".to_string()),
+            tick: Some(0),
+            log_index: Some(0),
+            transaction: None,
+            block: None,
         };
 
         if let Ok(event) = self.create_event_from_v3_subgraph(
@@ -1106,8 +2246,8 @@ impl SwapEventCollector {
         // Test subgraph connectivity
         let subgraph_healthy = self.subgraph_client.test_connectivity().await.is_ok();
 
-        // Test Redis connectivity
-        let redis_healthy = self.redis_publisher.test_connection().await.is_ok();
+        // Test sink connectivity
+        let sink_healthy = self.sink.test_connection().await.is_ok();
 
         // Test SwapEventBuilder validation with sample data
         let validation_healthy = self
@@ -1116,7 +2256,7 @@ impl SwapEventCollector {
                 "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
                 "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8",
                 &TokenInfo {
-                    address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                    address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                     symbol: "USDC".to_string(),
                     name: "USD Coin".to_string(),
                     decimals: 6,
@@ -1139,7 +2279,7 @@ impl SwapEventCollector {
             )
             .is_ok();
 
-        Ok(subgraph_healthy && redis_healthy && validation_healthy)
+        Ok(subgraph_healthy && sink_healthy && validation_healthy)
     }
 
     /// Validate event data using SwapEventBuilder
@@ -1287,12 +2427,20 @@ impl SwapEventCollector {
     }
 
     /// Graceful shutdown
+    ///
+    /// Drains in-flight collection, then flushes buffered events. Dedup ids
+    /// and dead letters are persisted as each batch completes, so nothing
+    /// else is pending afterwards.
     pub async fn shutdown(&mut self) -> Result<()> {
         info!("Shutting down swap event collector...");
 
         if self.is_running {
             self.stop_collecting().await?;
         }
+        self.sink.flush().await?;
+        if let Some(dedup) = &self.dedup {
+            dedup.persist().await;
+        }
 
         info!("Swap event collector shutdown complete");
         Ok(())
@@ -1312,6 +2460,85 @@ impl SwapEventCollector {
     }
 }
 
+/// Builder for `SwapEventCollector` with injectable dependencies
+///
+/// Only the sink is required. The subgraph client defaults to one built from
+/// the config (optionally over a custom transport), the clock to the system
+/// clock and the metrics collector to a fresh one.
+pub struct SwapEventCollectorBuilder {
+    config: AppConfig,
+    subgraph_client: Option<SubgraphClient>,
+    transport: Option<Arc<dyn SubgraphTransport>>,
+    sink: Option<Arc<dyn EventSink>>,
+    clock: Option<Arc<dyn Clock>>,
+    metrics_collector: Option<MetricsCollector>,
+}
+
+#[allow(dead_code)]
+impl SwapEventCollectorBuilder {
+    fn new(config: AppConfig) -> Self {
+        Self {
+            config,
+            subgraph_client: None,
+            transport: None,
+            sink: None,
+            clock: None,
+            metrics_collector: None,
+        }
+    }
+
+    /// Use an existing subgraph client (takes precedence over `with_transport`)
+    pub fn with_subgraph_client(mut self, subgraph_client: SubgraphClient) -> Self {
+        self.subgraph_client = Some(subgraph_client);
+        self
+    }
+
+    /// Send subgraph requests through a custom transport
+    pub fn with_transport(mut self, transport: Arc<dyn SubgraphTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Publish collected events to `sink`
+    pub fn with_sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Timestamp events using `clock`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Record metrics into an existing collector
+    pub fn with_metrics_collector(mut self, metrics_collector: MetricsCollector) -> Self {
+        self.metrics_collector = Some(metrics_collector);
+        self
+    }
+
+    /// Build the collector, failing if no sink was provided
+    pub fn build(self) -> Result<SwapEventCollector> {
+        let sink = self.sink.ok_or_else(|| {
+            crate::error::DAppError::Config("SwapEventCollector requires an event sink".to_string())
+        })?;
+
+        let subgraph_client = match (self.subgraph_client, self.transport) {
+            (Some(subgraph_client), _) => subgraph_client,
+            (None, Some(transport)) => {
+                SubgraphClient::with_transport(self.config.clone(), transport)
+            }
+            (None, None) => SubgraphClient::new(self.config.clone())?,
+        };
+        let clock = self.clock.unwrap_or_else(|| Arc::new(SystemClock));
+        let metrics_collector = self
+            .metrics_collector
+            .unwrap_or_else(|| MetricsCollector::new(self.config.clone()));
+
+        SwapEventCollector::from_parts(self.config, subgraph_client, sink, clock, metrics_collector)
+    }
+}
+
 /// Status information for the swap event collector
 #[derive(Debug, Clone)]
 pub struct CollectorStatus {
@@ -1329,3 +2556,2174 @@ impl std::fmt::Display for CollectorStatus {
         )
     }
 }
+
+/// Map a subgraph query failure to the `DAppError` variant it represents
+fn classify_query_error(error: DAppError) -> DAppError {
+    match error {
+        DAppError::Subgraph(error) => error.into_dapp_error(),
+        other => other,
+    }
+}
+
+/// Fields every swap must carry for the parser to produce a meaningful event
+fn expected_swap_fields(version: &UniswapVersion) -> &'static [&'static str] {
+    match version {
+        UniswapVersion::V2 => &["id", "timestamp", "pair", "amount0_in", "amount1_out"],
+        UniswapVersion::V3 => &["id", "timestamp", "pool", "amount0", "amount1"],
+    }
+}
+
+/// Reject responses whose shape has drifted from what the parser expects
+///
+/// Missing fields otherwise parse as defaults, silently yielding empty or
+/// zero-amount events. Fails if `data.swaps` is not an array, or if more than
+/// `max_invalid_percent` of the swaps lack an expected field.
+fn validate_swaps_shape(
+    version: &UniswapVersion,
+    data: Option<&serde_json::Value>,
+    max_invalid_percent: f64,
+) -> Result<()> {
+    let invalid_response =
+        |message: String| DAppError::Subgraph(SubgraphError::InvalidResponse(message));
+
+    let swaps = data
+        .and_then(|data| data.get("swaps"))
+        .and_then(|swaps| swaps.as_array())
+        .ok_or_else(|| {
+            invalid_response(format!(
+                "{} response has no swaps array (data: {})",
+                version,
+                response_snippet(&data.map(|data| data.to_string()).unwrap_or_default())
+            ))
+        })?;
+
+    let expected = expected_swap_fields(version);
+    let invalid: Vec<&serde_json::Value> = swaps
+        .iter()
+        .filter(|swap| {
+            expected
+                .iter()
+                .any(|field| swap.get(field).is_none_or(|value| value.is_null()))
+        })
+        .collect();
+
+    let invalid_percent = invalid.len() as f64 * 100.0 / swaps.len().max(1) as f64;
+    if invalid_percent > max_invalid_percent {
+        return Err(invalid_response(format!(
+            "{} of {} {} swaps lack expected fields {:?} (sample: {})",
+            invalid.len(),
+            swaps.len(),
+            version,
+            expected,
+            response_snippet(&invalid[0].to_string())
+        )));
+    }
+
+    Ok(())
+}
+
+/// A swap's `amount_usd`, if the subgraph reported a usable value
+fn parse_amount_usd(amount_usd: &str) -> Option<f64> {
+    let value = amount_usd.trim().parse::<f64>().ok()?;
+    (value.is_finite() && value >= 0.0).then_some(value)
+}
+
+/// Pool price of the bought token in units of the sold token
+///
+/// The subgraph's `token0_price` is token0 per token1 and `token1_price`
+/// token1 per token0.
+fn pool_price_in_per_out(
+    token0_price: &Option<String>,
+    token1_price: &Option<String>,
+    token_in_is_token0: bool,
+) -> Option<f64> {
+    let price = if token_in_is_token0 {
+        token0_price
+    } else {
+        token1_price
+    };
+    let value = price.as_deref()?.trim().parse::<f64>().ok()?;
+    (value.is_finite() && value > 0.0).then_some(value)
+}
+
+/// Whether an address looks like a base58 Solana public key
+fn is_solana_style_key(address: &str) -> bool {
+    address.len() == 44 && address.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// An integer the subgraph reports as a string or a number
+fn parse_u64(value: &serde_json::Value) -> Option<u64> {
+    match value.as_str() {
+        Some(text) => text.parse().ok(),
+        None => value.as_u64(),
+    }
+}
+
+/// Block the swap's transaction was included in
+///
+/// V2 subgraphs report it on the transaction, V3 on the swap's block.
+fn swap_block_number(swap_data: &serde_json::Value) -> Option<u64> {
+    swap_data
+        .pointer("/transaction/block_number")
+        .or_else(|| swap_data.pointer("/block/number"))
+        .and_then(parse_u64)
+}
+
+/// Block through which a response delivered every swap its task covers
+///
+/// A full ascending page may continue in its last block on the next page, so
+/// only the blocks before it are complete. Otherwise every swap up to the
+/// indexed head, less those still awaiting `min_confirmations`, was delivered.
+fn delivered_through(
+    data: &serde_json::Value,
+    delivered: &[&serde_json::Value],
+    page_full: bool,
+    min_confirmations: u64,
+) -> Option<u64> {
+    let last_delivered = delivered
+        .iter()
+        .filter_map(|swap| swap_block_number(swap))
+        .max();
+    if page_full {
+        return last_delivered.map(|block| block.saturating_sub(1));
+    }
+
+    data.pointer("/_meta/block/number")
+        .and_then(parse_u64)
+        .map(|head| head.saturating_sub(min_confirmations))
+        .or(last_delivered)
+}
+
+/// Swaps at least `min_confirmations` blocks behind the indexed head (`_meta.block.number`)
+///
+/// Swaps closer to the head, or without a block number, are held back; they
+/// stay ahead of the cursor and are picked up by a later poll. Nothing is
+/// released if the response lacks the head.
+fn confirmed_swaps<'a>(
+    data: &serde_json::Value,
+    swaps: Vec<&'a serde_json::Value>,
+    min_confirmations: u64,
+) -> Vec<&'a serde_json::Value> {
+    if min_confirmations == 0 {
+        return swaps;
+    }
+
+    let Some(head) = data.pointer("/_meta/block/number").and_then(parse_u64) else {
+        warn!(
+            "Holding back {} swaps: response has no indexed head block",
+            swaps.len()
+        );
+        return Vec::new();
+    };
+
+    let total = swaps.len();
+    let confirmed: Vec<&serde_json::Value> = swaps
+        .into_iter()
+        .filter(|swap| {
+            swap_block_number(swap)
+                .is_some_and(|block| block.saturating_add(min_confirmations) <= head)
+        })
+        .collect();
+    if confirmed.len() < total {
+        debug!(
+            "Holding back {} swaps with fewer than {} confirmations (head {})",
+            total - confirmed.len(),
+            min_confirmations,
+            head
+        );
+    }
+    confirmed
+}
+
+/// Seconds the newest swap of a response trails `now`, if any swap has a timestamp
+fn publish_lag_seconds(
+    swaps: &[serde_json::Value],
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<u64> {
+    let newest = swaps
+        .iter()
+        .filter_map(|swap| {
+            let timestamp = swap.get("timestamp")?;
+            timestamp
+                .as_str()
+                .and_then(|timestamp| timestamp.parse::<i64>().ok())
+                .or_else(|| timestamp.as_i64())
+        })
+        .max()?;
+    Some(now.timestamp().saturating_sub(newest).max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{ControlMessage, PartitionKeyStrategy};
+    use crate::subgraph::transport::MockTransport;
+    use crate::utils::clock::MockClock;
+    use chrono::{TimeZone, Utc};
+    use std::sync::Mutex;
+
+    const HOT_POOL: &str = "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8";
+
+    /// Default config, but without seeding cursors from the clock, which
+    /// would skip the fixtures' 2023 swaps
+    fn fixture_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.subgraph.seed_cursor_from_now = false;
+        config
+    }
+
+    fn hot_pool_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        config.subgraph.polling_interval_seconds = 15;
+        config
+            .subgraph
+            .hot_pool_intervals
+            .insert(HOT_POOL.to_string(), 3);
+        config
+    }
+
+    #[test]
+    fn test_pool_filter_where_clause() {
+        let pools = vec![HOT_POOL.to_lowercase()];
+
+        assert_eq!(
+            PoolFilter::All.where_clause(&UniswapVersion::V3),
+            serde_json::json!({})
+        );
+        assert_eq!(
+            PoolFilter::Only(pools.clone()).where_clause(&UniswapVersion::V2),
+            serde_json::json!({ "pair_in": pools })
+        );
+        assert_eq!(
+            PoolFilter::Exclude(pools.clone()).where_clause(&UniswapVersion::V3),
+            serde_json::json!({ "pool_not_in": pools })
+        );
+    }
+
+    #[test]
+    fn test_default_filter_excludes_hot_pools() {
+        let config = hot_pool_config();
+
+        assert_eq!(
+            SwapEventCollector::default_pool_filter(&config),
+            PoolFilter::Exclude(vec![HOT_POOL.to_lowercase()])
+        );
+        assert_eq!(
+            SwapEventCollector::default_pool_filter(&AppConfig::default()),
+            PoolFilter::All
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hot_pool_polled_more_often_than_default() {
+        let config = hot_pool_config();
+        assert_eq!(
+            SwapEventCollector::hot_pool_groups(&config),
+            vec![(Duration::from_secs(3), vec![HOT_POOL.to_lowercase()])]
+        );
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let mut collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+
+        // Just under a simulated minute of polling
+        collector.start_collecting().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(59)).await;
+        collector.shutdown().await.unwrap();
+
+        // Hot pool queries select the pool; default ones exclude it
+        let polls = |url: &str, selector: &str| {
+            transport
+                .requests()
+                .iter()
+                .filter(|(request_url, body)| {
+                    request_url == url
+                        && body["variables"]["where"]
+                            .to_string()
+                            .contains(&format!("\"{}\"", selector))
+                })
+                .count()
+        };
+        for (url, field) in [
+            (&config.subgraph.uniswap_v2_url, "pair"),
+            (&config.subgraph.uniswap_v3_url, "pool"),
+        ] {
+            assert_eq!(polls(url, &format!("{}_not_in", field)), 4);
+            assert_eq!(polls(url, &format!("{}_in", field)), 20);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_trigger_causes_out_of_schedule_poll() {
+        let config = AppConfig::default();
+        let (trigger_tx, trigger_rx) = mpsc::channel(16);
+        let (poll_trigger, _) = watch::channel(());
+        let mut task_trigger = Some(poll_trigger.subscribe());
+        tokio::spawn(SwapEventCollector::forward_triggers(
+            trigger_rx,
+            poll_trigger,
+            SwapEventCollector::trigger_min_gap(&config),
+        ));
+
+        let mut interval_timer = interval(Duration::from_secs(15));
+        let start = tokio::time::Instant::now();
+
+        // The first tick fires immediately as the scheduled poll
+        assert!(!SwapEventCollector::next_poll(&mut interval_timer, &mut task_trigger).await);
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        trigger_tx.send(()).await.unwrap();
+
+        assert!(SwapEventCollector::next_poll(&mut interval_timer, &mut task_trigger).await);
+        assert!(start.elapsed() < Duration::from_secs(15));
+
+        // The interval still acts as a floor afterwards
+        assert!(!SwapEventCollector::next_poll(&mut interval_timer, &mut task_trigger).await);
+        assert_eq!(start.elapsed(), Duration::from_secs(15));
+    }
+
+    #[tokio::test]
+    async fn test_trigger_handle_unavailable_with_external_trigger() {
+        let collector = SwapEventCollector::builder(AppConfig::default())
+            .with_transport(Arc::new(MockTransport::new()))
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+        assert!(collector.trigger_handle().is_ok());
+
+        let (_trigger_tx, trigger_rx) = mpsc::channel(16);
+        let collector = collector.with_trigger(trigger_rx);
+        assert!(matches!(
+            collector.trigger_handle(),
+            Err(DAppError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_fails_without_transform_script() {
+        let mut config = AppConfig::default();
+        config.application.transform_script_path =
+            Some("/nonexistent/relay-transform.rhai".to_string());
+
+        let result = SwapEventCollector::builder(config)
+            .with_transport(Arc::new(MockTransport::new()))
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build();
+        assert!(matches!(result, Err(DAppError::Config(_))));
+    }
+
+    /// Sink that keeps published events in memory
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<SwapEvent>>,
+        /// Everything published on the event channel, in order, as JSON
+        messages: Mutex<Vec<serde_json::Value>>,
+        quarantined: Mutex<Vec<QuarantinedSwap>>,
+        block_summaries: Mutex<Vec<BlockSummary>>,
+        /// Reject block summaries, as a Redis outage after the events would
+        fail_block_summaries: bool,
+        /// Reject candles the same way
+        fail_candles: bool,
+        /// How long each event batch takes to publish
+        publish_delay: Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for RecordingSink {
+        async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+            tokio::time::sleep(self.publish_delay).await;
+            self.events.lock().unwrap().extend_from_slice(events);
+            self.messages.lock().unwrap().extend(
+                events
+                    .iter()
+                    .map(|event| serde_json::to_value(event).unwrap()),
+            );
+            Ok(())
+        }
+
+        async fn publish_control(&self, message: &ControlMessage) -> Result<()> {
+            self.messages
+                .lock()
+                .unwrap()
+                .push(serde_json::to_value(message).unwrap());
+            Ok(())
+        }
+
+        async fn publish_quarantined(&self, swaps: &[QuarantinedSwap]) -> Result<()> {
+            self.quarantined.lock().unwrap().extend_from_slice(swaps);
+            Ok(())
+        }
+
+        async fn publish_candles(&self, _candles: &[OhlcCandle]) -> Result<()> {
+            if self.fail_candles {
+                return Err(DAppError::Internal(
+                    "candle channel unavailable".to_string(),
+                ));
+            }
+            Ok(())
+        }
+
+        async fn publish_block_summaries(&self, summaries: &[BlockSummary]) -> Result<()> {
+            if self.fail_block_summaries {
+                return Err(DAppError::Internal(
+                    "summary channel unavailable".to_string(),
+                ));
+            }
+            self.block_summaries
+                .lock()
+                .unwrap()
+                .extend_from_slice(summaries);
+            Ok(())
+        }
+    }
+
+    /// V2 and V3 responses with swaps in `v2_blocks` / `v3_blocks`, both indexed through `head`
+    fn block_summary_transport(
+        config: &AppConfig,
+        v2_blocks: &[u64],
+        v3_blocks: &[u64],
+        head: u64,
+    ) -> Arc<MockTransport> {
+        let v2_swaps: Vec<serde_json::Value> = v2_blocks
+            .iter()
+            .map(|block| {
+                let mut swap = v2_swap_fixture();
+                swap["id"] = format!("0xv2-{}", block).into();
+                swap["transaction"] = serde_json::json!({ "block_number": block.to_string() });
+                swap
+            })
+            .collect();
+        let v3_swaps: Vec<serde_json::Value> = v3_blocks
+            .iter()
+            .map(|block| {
+                let mut swap = v3_swap_fixture("2000", "-1");
+                swap["id"] = format!("0xv3-{}", block).into();
+                swap["block"] = serde_json::json!({ "number": block.to_string() });
+                swap
+            })
+            .collect();
+        let meta = serde_json::json!({ "block": { "number": head } });
+        Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": v2_swaps, "_meta": meta } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": v3_swaps, "_meta": meta } }),
+                ),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_block_summary_combines_versions_and_polls() {
+        let mut config = fixture_config();
+        config.redis.emit_block_summaries = true;
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(block_summary_transport(&config, &[100], &[100, 101], 101))
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+        // The same swaps again: nothing new to summarize
+        collector.poll_once().await.unwrap();
+
+        let summaries = sink.block_summaries.lock().unwrap().clone();
+        assert_eq!(
+            summaries
+                .iter()
+                .map(|summary| (
+                    summary.block,
+                    summary.swap_count,
+                    summary.pools_touched.len()
+                ))
+                .collect::<Vec<_>>(),
+            vec![(100, 2, 2), (101, 1, 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_block_summary_does_not_republish_events() {
+        let mut config = fixture_config();
+        config.redis.emit_block_summaries = true;
+        let sink = Arc::new(RecordingSink {
+            fail_block_summaries: true,
+            ..RecordingSink::default()
+        });
+        let metrics_collector = MetricsCollector::new(config.clone());
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(block_summary_transport(&config, &[100], &[100], 100))
+            .with_sink(sink.clone())
+            .with_metrics_collector(metrics_collector.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        assert_eq!(sink.events.lock().unwrap().len(), 2);
+        assert_eq!(metrics_collector.get_metrics().errors_total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_candles_do_not_republish_events() {
+        let mut config = fixture_config();
+        config.redis.emit_candles = true;
+        let sink = Arc::new(RecordingSink {
+            fail_candles: true,
+            ..RecordingSink::default()
+        });
+        let metrics_collector = MetricsCollector::new(config.clone());
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(block_summary_transport(&config, &[100], &[100], 100))
+            .with_sink(sink.clone())
+            .with_metrics_collector(metrics_collector.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        assert_eq!(sink.events.lock().unwrap().len(), 2);
+        // One failure per task, each for the candle its swap completed
+        assert_eq!(metrics_collector.get_metrics().errors_total, 2);
+    }
+
+    fn v2_swap_fixture() -> serde_json::Value {
+        serde_json::json!({
+            "id": "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "timestamp": "1700000000",
+            "pair": {
+                "id": "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc",
+                "token0": {
+                    "id": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                    "symbol": "USDC",
+                    "name": "USD Coin",
+                    "decimals": 6
+                },
+                "token1": {
+                    "id": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "symbol": "WETH",
+                    "name": "Wrapped Ether",
+                    "decimals": 18
+                },
+                "reserve0": "50000000",
+                "reserve1": "25000"
+            },
+            "sender": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+            "amount0_in": "1000",
+            "amount1_out": "0.5",
+            "to": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d"
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_log_reported_by_both_versions_published_once() {
+        let mut config = fixture_config();
+        config.dedup.enabled = true;
+        config.dedup.cross_version = true;
+        let mut v2_swap = v2_swap_fixture();
+        v2_swap["id"] = "0xabc-7".into();
+        v2_swap["log_index"] = "7".into();
+        let mut v3_swap = v3_swap_fixture("2000", "-1");
+        v3_swap["id"] = "0xabc#7".into();
+        v3_swap["log_index"] = "7".into();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [v3_swap] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink {
+            publish_delay: Duration::from_secs(1),
+            ..RecordingSink::default()
+        });
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        // Both tasks poll while the other's publish is still in flight
+        let ctx = collector.collection_context();
+        let (v2, v3) = tokio::join!(
+            SwapEventCollector::collect_v2_events_with_retry(&ctx, &PoolFilter::All),
+            SwapEventCollector::collect_v3_events_with_retry(&ctx, &PoolFilter::All),
+        );
+        v2.unwrap();
+        v3.unwrap();
+
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_live_poll_during_backfill_waits_for_handoff() {
+        let mut config = fixture_config();
+        config.subgraph.ordered_catchup = true;
+        let mut early_swap = v2_swap_fixture();
+        early_swap["id"] = "back-1".into();
+        early_swap["timestamp"] = "1700000010".into();
+        let mut live_swap = v2_swap_fixture();
+        live_swap["id"] = "live".into();
+        live_swap["timestamp"] = "1700000120".into();
+        let mut late_swap = v3_swap_fixture("2000", "-1");
+        late_swap["id"] = "back-2".into();
+        late_swap["timestamp"] = "1700000020".into();
+        // The backfill takes the first V2 response, the live poll the second
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [early_swap] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [live_swap] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [late_swap] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink {
+            publish_delay: Duration::from_secs(1),
+            ..RecordingSink::default()
+        });
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .with_clock(Arc::new(MockClock::new(
+                Utc.timestamp_opt(1_700_000_100, 0).unwrap(),
+            )))
+            .build()
+            .unwrap();
+        let request = BackfillRequest {
+            from: 1_700_000_000,
+            to: 1_700_000_100,
+            versions: vec![UniswapVersion::V2, UniswapVersion::V3],
+            pool: None,
+        };
+
+        // The live poll lands between the two backfilled pages
+        let ctx = collector.collection_context();
+        let (summary, live) = tokio::join!(
+            collector.backfill(&request),
+            SwapEventCollector::collect_v2_events_with_retry(&ctx, &PoolFilter::All),
+        );
+        let summary = summary.unwrap();
+        live.unwrap();
+
+        // The live event was held back until the backfill handed off
+        let ids: Vec<String> = sink
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.id.clone())
+            .collect();
+        assert_eq!(ids, vec!["v2_back-1", "v3_back-2", "v2_live"]);
+        assert_eq!(summary.fetched, 2);
+        assert_eq!(summary.published, 3);
+
+        // After the handoff live events pass straight through
+        assert!(collector.catchup.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_last_delivered_blocks() {
+        let config = fixture_config();
+        let mut v2_swap = v2_swap_fixture();
+        v2_swap["transaction"] = serde_json::json!({ "block_number": "18500000" });
+        let mut v3_swap = v3_swap_fixture("2000", "-1");
+        v3_swap["block"] = serde_json::json!({ "number": "18500007" });
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [v3_swap] } }),
+                ),
+        );
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+        assert_eq!(collector.status().last_v2_block, 0);
+
+        collector.poll_once().await.unwrap();
+
+        let status = collector.status();
+        assert_eq!(status.last_v2_block, 18_500_000);
+        assert_eq!(status.last_v3_block, 18_500_007);
+    }
+
+    #[tokio::test]
+    async fn test_builder_wires_mock_transport_sink_and_clock() {
+        let config = fixture_config();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .with_clock(Arc::new(MockClock::new(now)))
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        let events = sink.events.lock().unwrap().clone();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].version, UniswapVersion::V2);
+        assert_eq!(events[0].token_in.symbol, "USDC");
+        assert_eq!(events[0].token_out.symbol, "WETH");
+        // Events carry the swap's own time rather than the collector's clock
+        assert_eq!(
+            events[0].timestamp,
+            Utc.timestamp_opt(1_700_000_000, 0).unwrap()
+        );
+        assert_eq!(
+            events[0].v2_reserves,
+            Some(V2Reserves {
+                reserve_in: "50000000".to_string(),
+                reserve_out: "25000".to_string(),
+            })
+        );
+
+        let requested: Vec<String> = transport
+            .requests()
+            .into_iter()
+            .map(|(url, _)| url)
+            .collect();
+        assert_eq!(
+            requested,
+            vec![
+                config.subgraph.uniswap_v2_url,
+                config.subgraph.uniswap_v3_url
+            ]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_one_shot_publishes_and_returns() {
+        let mut config = fixture_config();
+        config.application.one_shot = true;
+        config.subgraph.hot_pool_intervals = [("0xhot".to_string(), 1)].into();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+
+        let mut collector = SwapEventCollector::builder(config)
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), collector.start_collecting())
+            .await
+            .expect("one-shot collection should return")
+            .unwrap();
+        assert!(!collector.is_running);
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+
+        // Nothing keeps polling in the background
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_is_running_follows_start_and_stop() {
+        let config = AppConfig::default();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let mut collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+        assert!(!collector.is_running());
+
+        collector.start_collecting().await.unwrap();
+        assert!(collector.is_running());
+
+        collector.stop_collecting().await.unwrap();
+        assert!(!collector.is_running());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_stops_background_polling() {
+        let config = AppConfig::default();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let mut collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+
+        collector.start_collecting().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(
+            config.subgraph.polling_interval_seconds * 2,
+        ))
+        .await;
+        let polled = transport.requests().len();
+        assert!(polled >= 4);
+
+        collector.shutdown().await.unwrap();
+        assert!(!collector.is_running);
+        assert!(collector.tasks.is_empty());
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(transport.requests().len(), polled);
+    }
+
+    /// Sink whose publishes take `delay`, counting completed batches
+    struct SlowSink {
+        delay: Duration,
+        completed: Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for SlowSink {
+        async fn publish_batch(&self, _events: &[SwapEvent]) -> Result<()> {
+            tokio::time::sleep(self.delay).await;
+            *self.completed.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    async fn shutdown_during_slow_publish(shutdown_timeout_seconds: u64) -> (usize, Duration) {
+        let mut config = fixture_config();
+        config.application.shutdown_timeout_seconds = shutdown_timeout_seconds;
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(SlowSink {
+            delay: Duration::from_secs(5),
+            completed: Mutex::new(0),
+        });
+        let mut collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.start_collecting().await.unwrap();
+        // The first V2 poll is now blocked publishing its batch
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(*sink.completed.lock().unwrap(), 0);
+
+        let started = tokio::time::Instant::now();
+        collector.shutdown().await.unwrap();
+        let completed = *sink.completed.lock().unwrap();
+        (completed, started.elapsed())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_waits_for_in_flight_publish() {
+        let (completed, elapsed) = shutdown_during_slow_publish(30).await;
+
+        assert_eq!(completed, 1);
+        assert_eq!(elapsed, Duration::from_secs(4));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_timeout_bounds_drain() {
+        let (completed, elapsed) = shutdown_during_slow_publish(2).await;
+
+        assert_eq!(completed, 0);
+        assert_eq!(elapsed, Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reloaded_polling_interval_takes_effect() {
+        let mut config = AppConfig::default();
+        config.subgraph.polling_interval_seconds = 3600;
+        let path = std::env::temp_dir().join(format!("relay-reload-{}.toml", std::process::id()));
+        std::fs::write(&path, toml::to_string(&config).unwrap()).unwrap();
+
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let (tx, rx) = mpsc::channel(1);
+        let watcher = AppConfig::watch(&path, tx);
+        let mut collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap()
+            .with_config_updates(rx);
+        collector.start_collecting().await.unwrap();
+        let v2_polls = || {
+            transport
+                .requests()
+                .iter()
+                .filter(|(url, _)| url == &config.subgraph.uniswap_v2_url)
+                .count()
+        };
+
+        // Only the immediate first poll within the hour-long interval
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(v2_polls(), 1);
+
+        let mut reloaded = config.clone();
+        reloaded.subgraph.polling_interval_seconds = 2;
+        std::fs::write(&path, toml::to_string(&reloaded).unwrap()).unwrap();
+        // Noticed within a second, then polled every 2s
+        tokio::time::sleep(Duration::from_millis(7500)).await;
+        assert_eq!(v2_polls(), 1 + 3);
+
+        collector.stop_collecting().await.unwrap();
+        watcher.abort();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    async fn v2_query_error(collector: &SwapEventCollector) -> DAppError {
+        SwapEventCollector::collect_v2_events(&collector.collection_context(), &PoolFilter::All)
+            .await
+            .unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn test_subgraph_timeout_maps_to_timeout_error() {
+        let config = AppConfig::default();
+        let transport =
+            Arc::new(MockTransport::new().with_response(&config.subgraph.uniswap_v2_url, 408, ""));
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+
+        let error = v2_query_error(&collector).await;
+        assert!(
+            matches!(error, DAppError::Timeout(_)),
+            "Expected timeout, got {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_client_timeout_maps_to_timeout_error() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_delay(Duration::from_secs(3)))
+            .mount(&server)
+            .await;
+
+        let mut config = AppConfig::default();
+        config.subgraph.uniswap_v2_url = server.uri();
+        config.subgraph.timeout_seconds = 1;
+        let collector = SwapEventCollector::builder(config)
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+
+        let error = v2_query_error(&collector).await;
+        assert!(
+            matches!(error, DAppError::Timeout(_)),
+            "Expected timeout, got {:?}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_decimals_coalesces_lookups_across_batches() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": { "token": { "decimals": "18" } } }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&server)
+            .await;
+
+        let mut config = AppConfig::default();
+        config.subgraph.uniswap_v2_url = format!("{}/v2", server.uri());
+        config.subgraph.uniswap_v3_url = format!("{}/v3", server.uri());
+        let client = SubgraphClient::new(config).unwrap();
+
+        // Two batches of 50 swaps on the same pool, as the V2 and V3 tasks collect them
+        let event = SwapEventBuilder::test_builder().unwrap();
+        let mut first = vec![event.clone(); 50];
+        let mut second = vec![event.clone(); 50];
+        tokio::join!(
+            SwapEventCollector::verify_decimals(&client, &mut first),
+            SwapEventCollector::verify_decimals(&client, &mut second)
+        );
+
+        assert!(first
+            .iter()
+            .chain(&second)
+            .all(|event| event.token_in.decimals == 18 && event.token_out.decimals == 18));
+        // Both subgraphs queried once per token
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limited_retry_waits_for_retry_after() {
+        let mut config = fixture_config();
+        config.retry.initial_delay_ms = 100;
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_rate_limit(&config.subgraph.uniswap_v2_url, "5")
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        let started = tokio::time::Instant::now();
+        SwapEventCollector::collect_v2_events_with_retry(
+            &collector.collection_context(),
+            &PoolFilter::All,
+        )
+        .await
+        .unwrap();
+
+        // The 5s from Retry-After, not the 100ms backoff
+        assert_eq!(started.elapsed(), Duration::from_secs(5));
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_swap_is_quarantined_with_reason() {
+        let mut config = fixture_config();
+        config.redis.quarantine_channel = Some("uniswap_swaps.quarantine".to_string());
+        let mut malformed = v2_swap_fixture();
+        malformed.as_object_mut().unwrap().remove("pair");
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [malformed.clone(), v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .with_clock(Arc::new(MockClock::new(now)))
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        // The well-formed swap still goes out
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+        let quarantined = sink.quarantined.lock().unwrap().clone();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].source, "uniswap_v2");
+        assert_eq!(quarantined[0].payload, malformed);
+        assert!(
+            quarantined[0].reason.contains("pair"),
+            "Unexpected reason: {}",
+            quarantined[0].reason
+        );
+        assert_eq!(quarantined[0].quarantined_at, now);
+    }
+
+    /// Sink that records the partition keys it receives
+    #[derive(Default)]
+    struct KeyRecordingSink {
+        keys: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EventSink for KeyRecordingSink {
+        async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+            self.keys
+                .lock()
+                .unwrap()
+                .extend(events.iter().map(|_| None));
+            Ok(())
+        }
+
+        async fn publish_keyed_batch(&self, events: &[KeyedEvent<'_>]) -> Result<()> {
+            self.keys
+                .lock()
+                .unwrap()
+                .extend(events.iter().map(|keyed| keyed.key.map(str::to_string)));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_partition_key_reaches_sink() {
+        let mut config = fixture_config();
+        config.application.partition_key = Some(PartitionKeyStrategy::Pool);
+        let transport = Arc::new(MockTransport::new().with_json(
+            &config.subgraph.uniswap_v2_url,
+            serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+        ));
+        let sink = Arc::new(KeyRecordingSink::default());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        SwapEventCollector::collect_v2_events(&collector.collection_context(), &PoolFilter::All)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *sink.keys.lock().unwrap(),
+            vec![Some(
+                "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc".to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shape_validation_rejects_camel_case_fields() {
+        let mut camel_case = v2_swap_fixture();
+        let fields = camel_case.as_object_mut().unwrap();
+        let amount_in = fields.remove("amount0_in").unwrap();
+        let amount_out = fields.remove("amount1_out").unwrap();
+        fields.insert("amount0In".to_string(), amount_in);
+        fields.insert("amount1Out".to_string(), amount_out);
+
+        let mut config = AppConfig::default();
+        config.subgraph.validate_response_shape = true;
+        let transport = Arc::new(MockTransport::new().with_json(
+            &config.subgraph.uniswap_v2_url,
+            serde_json::json!({ "data": { "swaps": [camel_case.clone(), camel_case] } }),
+        ));
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        match v2_query_error(&collector).await {
+            DAppError::Subgraph(SubgraphError::InvalidResponse(message)) => {
+                assert!(message.starts_with("2 of 2 v2 swaps lack expected fields"));
+                assert!(message.contains("amount0In"));
+            }
+            other => panic!("Expected invalid response, got {:?}", other),
+        }
+        assert!(sink.events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_shape_validation_tolerates_few_malformed_swaps() {
+        let mut swaps: Vec<serde_json::Value> = (0..10).map(|_| v2_swap_fixture()).collect();
+        swaps[0].as_object_mut().unwrap().remove("timestamp");
+        let data = serde_json::json!({ "swaps": swaps });
+
+        assert!(validate_swaps_shape(&UniswapVersion::V2, Some(&data), 10.0).is_ok());
+        assert!(validate_swaps_shape(&UniswapVersion::V2, Some(&data), 5.0).is_err());
+        assert!(validate_swaps_shape(
+            &UniswapVersion::V2,
+            Some(&serde_json::json!({ "Swaps": [] })),
+            10.0
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_enrichment_shed_under_lag_then_restored() {
+        let mut config = fixture_config();
+        config.subgraph.verify_decimals = true;
+        config.enrichment.enrichment_shed_threshold = 60;
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let metrics_collector = MetricsCollector::new(config.clone());
+        // The fixture swap happened an hour before the clock
+        let clock = Arc::new(MockClock::new(
+            Utc.timestamp_opt(1_700_000_000 + 3600, 0).unwrap(),
+        ));
+
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .with_clock(clock.clone())
+            .with_metrics_collector(metrics_collector.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        // Core event published without the token decimal lookups
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+        assert_eq!(transport.requests().len(), 2);
+        assert!(collector.shedder.is_shedding());
+        assert!(metrics_collector.is_enrichment_shed());
+        assert!(metrics_collector.get_metrics().enrichment_shed);
+
+        // Caught up again: lag well under half the threshold
+        clock.set(Utc.timestamp_opt(1_700_000_000 + 10, 0).unwrap());
+        collector.poll_once().await.unwrap();
+
+        assert!(!collector.shedder.is_shedding());
+        assert!(!metrics_collector.is_enrichment_shed());
+    }
+
+    #[tokio::test]
+    async fn test_every_stage_timed_for_a_cycle() {
+        let config = fixture_config();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let metrics_collector = MetricsCollector::new(config.clone());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(Arc::new(RecordingSink::default()))
+            .with_metrics_collector(metrics_collector.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        // Both versions are fetched and parsed; only V2 had events to publish
+        let count = |stage| metrics_collector.stage_summary(stage).count;
+        assert_eq!(count(PipelineStage::Fetch), 2);
+        assert_eq!(count(PipelineStage::Parse), 2);
+        assert_eq!(count(PipelineStage::Enrich), 1);
+        assert_eq!(count(PipelineStage::Publish), 1);
+
+        let names: Vec<&str> = metrics_collector
+            .get_metrics()
+            .stage_latency
+            .iter()
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "stage_fetch_ms",
+                "stage_parse_ms",
+                "stage_enrich_ms",
+                "stage_publish_ms"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_publish_lag_uses_newest_swap() {
+        let now = Utc.timestamp_opt(1_000, 0).unwrap();
+        let swaps = vec![
+            serde_json::json!({ "timestamp": "900" }),
+            serde_json::json!({ "timestamp": "940" }),
+            serde_json::json!({ "id": "no timestamp" }),
+        ];
+        assert_eq!(publish_lag_seconds(&swaps, now), Some(60));
+        assert_eq!(
+            publish_lag_seconds(&[serde_json::json!({ "timestamp": "2000" })], now),
+            Some(0)
+        );
+        assert_eq!(publish_lag_seconds(&[], now), None);
+    }
+
+    #[test]
+    fn test_swap_usd_amount_parsed() {
+        let mut swap = v2_swap_fixture();
+        swap["amount_usd"] = serde_json::json!("1234.5");
+        let event = SwapEventCollector::parse_v2_swap_event(&swap).unwrap();
+        assert_eq!(event.volume_usd(), Some(1234.5));
+
+        swap["amount_usd"] = serde_json::json!("NaN");
+        let event = SwapEventCollector::parse_v2_swap_event(&swap).unwrap();
+        assert_eq!(event.volume_usd(), None);
+    }
+
+    #[test]
+    fn test_swap_usd_amounts_without_amount_usd() {
+        let mut swap = v2_swap_fixture();
+        swap.as_object_mut().unwrap().remove("amount_usd");
+        swap["pair"]["token0_price"] = "2000".into();
+        let event = SwapEventCollector::parse_v2_swap_event(&swap).unwrap();
+        assert_eq!(event.amount_in_usd, None);
+        assert_eq!(event.amount_out_usd, None);
+
+        swap["amount_usd"] = "1000".into();
+        let event = SwapEventCollector::parse_v2_swap_event(&swap).unwrap();
+        assert_eq!(event.amount_in_usd, Some(1000.0));
+        assert_eq!(event.amount_out_usd, Some(1000.0));
+    }
+
+    #[test]
+    fn test_missing_usd_leg_derived_from_pool_price() {
+        // 1000 USDC sold for 0.5 WETH at 2100 USDC per WETH
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.amount_in = "1000".to_string();
+        event.amount_out = "0.5".to_string();
+        event.amount_in_usd = Some(1000.0);
+        event.amount_out_usd = None;
+
+        event.derive_missing_usd_amount(2100.0);
+        assert_eq!(event.amount_out_usd, Some(1050.0));
+
+        event.amount_in_usd = None;
+        event.derive_missing_usd_amount(2100.0);
+        assert_eq!(event.amount_in_usd, Some(1000.0));
+
+        // Unparseable amounts leave the missing leg unset
+        event.amount_out = "not a number".to_string();
+        event.amount_in_usd = None;
+        event.derive_missing_usd_amount(2100.0);
+        assert_eq!(event.amount_in_usd, None);
+    }
+
+    #[tokio::test]
+    async fn test_captured_events_replay_identically() {
+        let path =
+            std::env::temp_dir().join(format!("relay-capture-{}.ndjson", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let mut config = fixture_config();
+        config.application.capture_to_file = Some(path.clone());
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let captured_sink = Arc::new(RecordingSink::default());
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+        let capturing = SwapEventCollector::builder(config.clone())
+            .with_transport(transport)
+            .with_sink(captured_sink.clone())
+            .with_clock(Arc::new(MockClock::new(now)))
+            .build()
+            .unwrap();
+        capturing.poll_once().await.unwrap();
+
+        // Replay into a fresh collector without capture, deduplicating
+        let mut replay_config = AppConfig::default();
+        replay_config.dedup.enabled = true;
+        let replayed_sink = Arc::new(RecordingSink::default());
+        let replaying = SwapEventCollector::builder(replay_config)
+            .with_transport(Arc::new(MockTransport::new()))
+            .with_sink(replayed_sink.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(replaying.replay_from_file(&path).await.unwrap(), 1);
+        // Already replayed ids are skipped
+        assert_eq!(replaying.replay_from_file(&path).await.unwrap(), 0);
+
+        let as_json = |sink: &RecordingSink| {
+            sink.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|event| serde_json::to_value(event).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(as_json(&captured_sink).len(), 1);
+        assert_eq!(as_json(&replayed_sink), as_json(&captured_sink));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_truncated_response_retried_until_complete() {
+        let config = fixture_config();
+        let complete = serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }).to_string();
+        let truncated = &complete[..complete.len() / 2];
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(&config.subgraph.uniswap_v2_url, 200, truncated)
+                .with_response(&config.subgraph.uniswap_v2_url, 200, complete.as_str())
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        assert_eq!(sink.events.lock().unwrap().len(), 1);
+        let v2_requests = transport
+            .requests()
+            .iter()
+            .filter(|(url, _)| *url == config.subgraph.uniswap_v2_url)
+            .count();
+        assert_eq!(v2_requests, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wrong_shaped_response_not_retried() {
+        let config = AppConfig::default();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_response(
+                    &config.subgraph.uniswap_v2_url,
+                    200,
+                    r#"{"errors": "not a list"}"#,
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+
+        let error = collector.poll_once().await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            DAppError::Subgraph(SubgraphError::Parsing(_))
+        ));
+        let v2_requests = transport
+            .requests()
+            .iter()
+            .filter(|(url, _)| *url == config.subgraph.uniswap_v2_url)
+            .count();
+        assert_eq!(v2_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_announce_is_first_message_after_startup() {
+        let mut config = fixture_config();
+        config.application.one_shot = true;
+        config.redis.announce_on_startup = true;
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_fixture()] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let mut collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.start_collecting().await.unwrap();
+
+        let messages = sink.messages.lock().unwrap().clone();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0],
+            serde_json::json!({
+                "type": "hello",
+                "schema_version": 1,
+                "sources": ["uniswap_v2", "uniswap_v3"],
+                "chain_ids": [1],
+                "serialization_format": "json"
+            })
+        );
+        assert!(messages[1].get("type").is_none());
+        assert_eq!(messages[1]["version"], "V2");
+    }
+
+    #[test]
+    fn test_builder_requires_sink() {
+        assert!(SwapEventCollector::builder(AppConfig::default())
+            .build()
+            .is_err());
+    }
+
+    fn v2_swap_at(id: &str, timestamp: u64) -> serde_json::Value {
+        let mut swap = v2_swap_fixture();
+        swap["id"] = id.into();
+        swap["timestamp"] = timestamp.to_string().into();
+        swap
+    }
+
+    #[tokio::test]
+    async fn test_composite_cursor_handles_shared_timestamps() {
+        let cursor_path = std::env::temp_dir().join(format!(
+            "uniswap_relay_cursor_test_{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&cursor_path);
+
+        let mut config = fixture_config();
+        config.subgraph.composite_cursor = true;
+        config.subgraph.cursor_path = Some(cursor_path.to_string_lossy().into_owned());
+
+        let (a, b, c, d) = (
+            "0xaaaa000001",
+            "0xbbbb000002",
+            "0xcccc000003",
+            "0xdddd000004",
+        );
+        // The page boundary falls between swaps sharing timestamp 100, and the
+        // second page overlaps the first
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_at(b, 100), v2_swap_at(a, 100)] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [
+                        v2_swap_at(b, 100),
+                        v2_swap_at(c, 100),
+                        v2_swap_at(d, 101)
+                    ] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+        collector.poll_once().await.unwrap();
+
+        let delivered: Vec<String> = sink
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.transaction_hash.clone())
+            .collect();
+        // The first poll keeps the subgraph's newest-first order; the resumed
+        // poll delivers only the swaps after the cursor
+        assert_eq!(delivered, vec![b, a, c, d]);
+
+        // The second V2 query resumes strictly after (100, b)
+        let v2_requests: Vec<serde_json::Value> = transport
+            .requests()
+            .into_iter()
+            .filter(|(url, _)| *url == config.subgraph.uniswap_v2_url)
+            .map(|(_, body)| body["variables"].clone())
+            .collect();
+        assert_eq!(v2_requests[0]["orderDirection"], "desc");
+        assert_eq!(v2_requests[1]["orderDirection"], "asc");
+        assert_eq!(
+            v2_requests[1]["where"],
+            serde_json::json!({ "or": [
+                { "timestamp_gt": "100" },
+                { "timestamp": "100", "id_gt": b }
+            ] })
+        );
+
+        // The cursor survives a restart
+        let restored = CursorStore::from_config(&config);
+        assert_eq!(
+            restored.get("v2"),
+            Some(SwapCursor {
+                timestamp: 101,
+                id: d.to_string()
+            })
+        );
+        let _ = std::fs::remove_file(&cursor_path);
+    }
+
+    #[tokio::test]
+    async fn test_overlapping_polls_publish_each_id_once() {
+        let mut config = fixture_config();
+        config.subgraph.dedup_cache_size = 100;
+        let v3_swap_at = |id: &str| {
+            let mut swap = v3_swap_fixture("2000.5", "-1.05");
+            swap["id"] = id.into();
+            swap
+        };
+        // Each poll window overlaps the previous one by a swap
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_at("0xa1", 100), v2_swap_at("0xa2", 101)] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_at("0xa2", 101), v2_swap_at("0xa3", 102)] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [v3_swap_at("0xb1")] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [v3_swap_at("0xb1"), v3_swap_at("0xb2")] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+        collector.poll_once().await.unwrap();
+        collector.poll_once().await.unwrap();
+
+        let mut ids: Vec<String> = sink
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.id.clone())
+            .collect();
+        assert_eq!(ids.len(), 5);
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_seeded_cursor_polls_only_new_swaps() {
+        let mut config = AppConfig::default();
+        config.subgraph.composite_cursor = true;
+        config.subgraph.seed_cursor_from_now = true;
+        config.subgraph.polling_interval_seconds = 15;
+
+        let (old, a, b) = ("0xaaaa000000", "0xaaaa000001", "0xbbbb000002");
+        // The endpoint ignores the filter, so overlap has to be dropped client-side
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_at(a, 995), v2_swap_at(old, 980)] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [v2_swap_at(b, 1010), v2_swap_at(a, 995)] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let clock = Arc::new(MockClock::new(Utc.timestamp_opt(1_000, 0).unwrap()));
+        let collector = SwapEventCollector::builder(config.clone())
+            .with_transport(transport.clone())
+            .with_sink(sink.clone())
+            .with_clock(clock.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+        clock.advance(chrono::Duration::seconds(15));
+        collector.poll_once().await.unwrap();
+
+        let delivered: Vec<String> = sink
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.transaction_hash.clone())
+            .collect();
+        assert_eq!(delivered, vec![a, b]);
+
+        // The first query starts one polling interval before startup
+        let first_v2 = transport
+            .requests()
+            .into_iter()
+            .find(|(url, _)| *url == config.subgraph.uniswap_v2_url)
+            .map(|(_, body)| body["variables"].clone())
+            .unwrap();
+        assert_eq!(first_v2["orderDirection"], "asc");
+        assert_eq!(first_v2["where"]["or"][0]["timestamp_gt"], "985");
+    }
+
+    #[tokio::test]
+    async fn test_only_confirmed_swaps_emitted() {
+        let mut config = fixture_config();
+        config.subgraph.min_confirmations = 5;
+        let swap_in_block = |id: &str, block: u64| {
+            let mut swap = v2_swap_fixture();
+            swap["id"] = id.into();
+            swap["timestamp"] = (1_700_000_000 + block * 12).to_string().into();
+            swap["transaction"] = serde_json::json!({ "block_number": block.to_string() });
+            swap
+        };
+        let swaps = vec![
+            swap_in_block("0xat-head", 200),
+            swap_in_block("0xfour-behind", 196),
+            swap_in_block("0xfive-behind", 195),
+            swap_in_block("0xfar-behind", 120),
+        ];
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": {
+                        "swaps": swaps,
+                        "_meta": { "block": { "number": 200 } }
+                    } }),
+                )
+                // Five blocks later the held-back swaps are confirmed
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": {
+                        "swaps": swaps,
+                        "_meta": { "block": { "number": 205 } }
+                    } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [], "_meta": { "block": { "number": 200 } } } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        let delivered = || -> Vec<(String, u64)> {
+            sink.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|event| (event.transaction_hash.clone(), event.block_number))
+                .collect()
+        };
+
+        collector.poll_once().await.unwrap();
+        assert_eq!(
+            delivered(),
+            vec![
+                ("0xfive-behind".to_string(), 195),
+                ("0xfar-behind".to_string(), 120)
+            ]
+        );
+
+        // The cursor stopped before the held-back swaps, so they are picked up
+        collector.poll_once().await.unwrap();
+        assert_eq!(
+            delivered()[2..],
+            [
+                ("0xfour-behind".to_string(), 196),
+                ("0xat-head".to_string(), 200)
+            ]
+        );
+    }
+
+    /// V2 swap `id` in `pair` trading `token0` for `token1`
+    fn v2_swap_between(id: &str, pair: &str, token0: &str, token1: &str) -> serde_json::Value {
+        let mut swap = v2_swap_fixture();
+        swap["id"] = id.into();
+        swap["pair"]["id"] = pair.into();
+        swap["pair"]["token0"]["id"] = token0.into();
+        swap["pair"]["token1"]["id"] = token1.into();
+        swap
+    }
+
+    /// Transaction hashes published after one poll of `swaps`, and how many were filtered
+    async fn publish_allowlisted(
+        config: AppConfig,
+        swaps: Vec<serde_json::Value>,
+    ) -> (Vec<String>, u64) {
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": swaps } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let metrics_collector = MetricsCollector::new(config.clone());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .with_metrics_collector(metrics_collector.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        let published = sink
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.transaction_hash.clone())
+            .collect();
+        (
+            published,
+            metrics_collector.get_metrics().events_filtered_total,
+        )
+    }
+
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    const WETH: &str = "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2";
+    const DAI: &str = "0x6b175474e89094c44da98b954eedeac495271d0f";
+    const USDC_WETH_PAIR: &str = "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc";
+    const DAI_WETH_PAIR: &str = "0xa478c2975ab1ea89e8196811f51a7b7ade33eb11";
+
+    #[tokio::test]
+    async fn test_pool_allowlist_publishes_only_listed_pools() {
+        let mut config = fixture_config();
+        config.subgraph.pool_allowlist = vec![USDC_WETH_PAIR.to_string()];
+        let swaps = vec![
+            v2_swap_between("0xusdc-weth", USDC_WETH_PAIR, USDC, WETH),
+            v2_swap_between("0xdai-weth", DAI_WETH_PAIR, DAI, WETH),
+        ];
+
+        let (published, filtered) = publish_allowlisted(config, swaps).await;
+
+        assert_eq!(published, vec!["0xusdc-weth"]);
+        assert_eq!(filtered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_allowlist_matches_either_side() {
+        let mut config = fixture_config();
+        config.subgraph.token_allowlist = vec![DAI.to_string()];
+        let swaps = vec![
+            v2_swap_between("0xusdc-weth", USDC_WETH_PAIR, USDC, WETH),
+            v2_swap_between("0xdai-weth", DAI_WETH_PAIR, DAI, WETH),
+            v2_swap_between("0xweth-dai", DAI_WETH_PAIR, WETH, DAI),
+        ];
+
+        let (published, filtered) = publish_allowlisted(config, swaps).await;
+
+        assert_eq!(published, vec!["0xdai-weth", "0xweth-dai"]);
+        assert_eq!(filtered, 1);
+    }
+
+    #[tokio::test]
+    async fn test_query_page_size_sent_to_both_subgraphs() {
+        let mut config = AppConfig::default();
+        config.subgraph.query_page_size = 250;
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport.clone())
+            .with_sink(Arc::new(RecordingSink::default()))
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        for (_, body) in requests {
+            assert_eq!(body["variables"]["first"], 250);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_metadata_sanitized_before_publishing() {
+        let config = fixture_config();
+        let mut swap = v2_swap_fixture();
+        swap["pair"]["token0"]["name"] = "Evil\n\u{1b}[2J\u{1b}[31mToken".into();
+        swap["pair"]["token0"]["symbol"] = "EV\rIL".into();
+        let transport = Arc::new(
+            MockTransport::new()
+                .with_json(
+                    &config.subgraph.uniswap_v2_url,
+                    serde_json::json!({ "data": { "swaps": [swap] } }),
+                )
+                .with_json(
+                    &config.subgraph.uniswap_v3_url,
+                    serde_json::json!({ "data": { "swaps": [] } }),
+                ),
+        );
+        let sink = Arc::new(RecordingSink::default());
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport)
+            .with_sink(sink.clone())
+            .build()
+            .unwrap();
+
+        collector.poll_once().await.unwrap();
+
+        let events = sink.events.lock().unwrap();
+        assert_eq!(events[0].token_in.name, "EvilToken");
+        assert_eq!(events[0].token_in.symbol, "EVIL");
+    }
+
+    #[test]
+    fn test_overlong_token_name_truncated() {
+        let mut config = AppConfig::default();
+        config.subgraph.max_token_name_len = 16;
+        let mut swap = v2_swap_fixture();
+        swap["pair"]["token1"]["name"] = "Visit https://example.com/claim for free tokens".into();
+        let mut event = SwapEventCollector::parse_v2_swap_event(&swap).unwrap();
+
+        SwapEventCollector::sanitize_token_metadata(&config, &mut event);
+        assert_eq!(event.token_out.name, "Visit https://e…");
+        assert_eq!(event.token_out.name.chars().count(), 16);
+    }
+
+    fn v3_swap_fixture(amount0: &str, amount1: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890",
+            "timestamp": "1700000000",
+            "pool": {
+                "id": "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640",
+                "token0": {
+                    "id": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                    "symbol": "USDC",
+                    "name": "USD Coin",
+                    "decimals": 6
+                },
+                "token1": {
+                    "id": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "symbol": "WETH",
+                    "name": "Wrapped Ether",
+                    "decimals": 18
+                }
+            },
+            "sender": "0xe592427a0aece92de3edee1f18e0157c05861564",
+            "recipient": "0x742d35cc6634c0532925a3b8d4c9db96c4b4d8b6",
+            "amount0": amount0,
+            "amount1": amount1,
+            "amountUSD": "2000"
+        })
+    }
+
+    #[test]
+    fn test_v3_swap_selling_token0() {
+        let swap = v3_swap_fixture("2000.5", "-1.05");
+        let event = SwapEventCollector::parse_v3_swap_event(&swap).unwrap();
+
+        assert_eq!(event.token_in.symbol, "USDC");
+        assert_eq!(event.token_out.symbol, "WETH");
+        assert_eq!(event.amount_in, "2000.5");
+        assert_eq!(event.amount_out, "1.05");
+        // Addresses are published in their EIP-55 checksummed form
+        assert_eq!(
+            event.user_address,
+            "0x742d35Cc6634C0532925A3B8D4C9dB96C4B4d8B6"
+        );
+    }
+
+    #[test]
+    fn test_v3_swap_selling_token1() {
+        let swap = v3_swap_fixture("-2000.5", "1.05");
+        let event = SwapEventCollector::parse_v3_swap_event(&swap).unwrap();
+
+        assert_eq!(event.token_in.symbol, "WETH");
+        assert_eq!(event.token_out.symbol, "USDC");
+        assert_eq!(event.amount_in, "1.05");
+        assert_eq!(event.amount_out, "2000.5");
+        assert!(event.validate().is_empty());
+    }
+
+    #[test]
+    fn test_typed_swap_accepts_string_and_number_scalars() {
+        let mut swap = v3_swap_fixture("2000.5", "-1.05");
+        swap["pool"]["token0"]["decimals"] = "6".into();
+        swap["pool"]["fee_tier"] = "3000".into();
+        swap["pool"]["liquidity"] = "123456".into();
+        swap["amount_usd"] = 2000.5.into();
+        swap["log_index"] = "7".into();
+        swap["transaction"] = serde_json::json!({ "block_number": 18000000 });
+
+        let event = SwapEventCollector::parse_v3_swap_event(&swap).unwrap();
+
+        assert_eq!(event.token_in.decimals, 6);
+        assert_eq!(event.volume_usd(), Some(2000.5));
+        assert_eq!(event.log_index, Some(7));
+        assert_eq!(event.block_number, 18000000);
+        let pool = event.pool_info.unwrap();
+        assert_eq!(pool.fee_tier, Some(3000));
+        assert_eq!(pool.liquidity.as_deref(), Some("123456"));
+    }
+
+    #[test]
+    fn test_block_info_from_either_schema() {
+        let swap_time = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        let mut v2_swap = v2_swap_fixture();
+        v2_swap["transaction"] =
+            serde_json::json!({ "block_number": "18500000", "timestamp": "1700000000" });
+        let event = SwapEventCollector::parse_v2_swap_event(&v2_swap).unwrap();
+        assert_eq!(event.block_number, 18_500_000);
+        assert_eq!(event.timestamp, swap_time);
+
+        let mut v3_swap = v3_swap_fixture("2000.5", "-1.05");
+        v3_swap["block"] = serde_json::json!({ "number": "18500001", "timestamp": "1700000012" });
+        let event = SwapEventCollector::parse_v3_swap_event(&v3_swap).unwrap();
+        assert_eq!(event.block_number, 18_500_001);
+        assert_eq!(event.timestamp, swap_time);
+
+        // Without a usable swap timestamp the block's time is used
+        v3_swap["timestamp"] = "".into();
+        let event = SwapEventCollector::parse_v3_swap_event(&v3_swap).unwrap();
+        assert_eq!(event.timestamp, swap_time + chrono::Duration::seconds(12));
+    }
+
+    #[test]
+    fn test_typed_swap_rejects_missing_required_field() {
+        let mut swap = v3_swap_fixture("2000.5", "-1.05");
+        swap.as_object_mut().unwrap().remove("amount1");
+
+        let error = SwapEventCollector::parse_v3_swap_event(&swap).unwrap_err();
+        assert!(
+            matches!(&error, DAppError::Subgraph(SubgraphError::InvalidResponse(message))
+                if message.contains("amount1")),
+            "unexpected error: {}",
+            error
+        );
+
+        let mut swap = v2_swap_fixture();
+        swap["pair"]["token1"]["decimals"] = "eighteen".into();
+        assert!(SwapEventCollector::parse_v2_swap_event(&swap).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failing_sources_share_global_retry_budget() {
+        let mut config = AppConfig::default();
+        config.retry.max_attempts = 10;
+        config.retry.global_retry_budget_per_second = 3.0;
+        // No responses registered, so every request to either subgraph fails
+        let transport = Arc::new(MockTransport::new());
+
+        let collector = SwapEventCollector::builder(config)
+            .with_transport(transport.clone())
+            .with_sink(Arc::new(RecordingSink::default()))
+            .with_clock(Arc::new(MockClock::new(Utc::now())))
+            .build()
+            .unwrap();
+
+        assert!(collector.poll_once().await.is_err());
+        // One attempt per source plus the three retries the budget allows
+        assert_eq!(transport.requests().len(), 2 + 3);
+    }
+}
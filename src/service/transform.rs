@@ -0,0 +1,181 @@
+use crate::config::AppConfig;
+use crate::error::{DAppError, Result};
+use crate::model::SwapEvent;
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Dynamic, Engine, Scope, AST};
+use tracing::{debug, error, warn};
+
+/// User-supplied Rhai script applied to every event before publishing
+///
+/// The script sees the event as an object map named `event`, with the same
+/// field names as the published JSON (optional fields that are `None` are
+/// absent and read as `()`). It may:
+///
+/// - set fields, e.g. `event.amount_in_usd = 1.0;`
+/// - add tags, e.g. `event.tags.push("whale");`
+/// - drop the event by evaluating to `false`
+///
+/// Scripts run in a sandboxed engine: no module imports, and bounded
+/// operations, call depth and data sizes. Script errors are logged and the
+/// original event is published unchanged, or dropped when
+/// `application.transform_strict` is set.
+pub struct EventTransformer {
+    engine: Engine,
+    ast: AST,
+    strict: bool,
+}
+
+#[allow(dead_code)]
+impl EventTransformer {
+    /// Compile a transform script
+    pub fn from_script(script: &str, strict: bool) -> Result<Self> {
+        let engine = Self::sandboxed_engine();
+        let ast = engine
+            .compile(script)
+            .map_err(|e| DAppError::Config(format!("Invalid transform script: {}", e)))?;
+
+        Ok(Self {
+            engine,
+            ast,
+            strict,
+        })
+    }
+
+    /// Load the script configured in `application.transform_script_path`, if any
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>> {
+        let Some(path) = &config.application.transform_script_path else {
+            return Ok(None);
+        };
+
+        let script = std::fs::read_to_string(path).map_err(|e| {
+            DAppError::Config(format!("Failed to read transform script {}: {}", path, e))
+        })?;
+        Self::from_script(&script, config.application.transform_strict).map(Some)
+    }
+
+    /// Transform a batch, dropping events the script filters out
+    pub fn transform_batch(&self, events: Vec<SwapEvent>) -> Vec<SwapEvent> {
+        events
+            .into_iter()
+            .filter_map(|event| self.transform(event))
+            .collect()
+    }
+
+    /// Transform one event; `None` means the event is dropped
+    pub fn transform(&self, event: SwapEvent) -> Option<SwapEvent> {
+        match self.apply(&event) {
+            Ok(transformed) => transformed,
+            Err(e) if self.strict => {
+                error!(
+                    "Transform script failed for {}, dropping event: {}",
+                    event.id, e
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    "Transform script failed for {}, publishing unchanged: {}",
+                    event.id, e
+                );
+                Some(event)
+            }
+        }
+    }
+
+    fn apply(&self, event: &SwapEvent) -> std::result::Result<Option<SwapEvent>, String> {
+        let mut input = rhai::serde::to_dynamic(event).map_err(|e| e.to_string())?;
+        // Empty tags are skipped when serializing; expose them so scripts can push
+        if let Some(mut fields) = input.write_lock::<rhai::Map>() {
+            fields
+                .entry("tags".into())
+                .or_insert_with(|| rhai::Array::new().into());
+        }
+        let mut scope = Scope::new();
+        scope.push("event", input);
+
+        let outcome: Dynamic = self
+            .engine
+            .eval_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|e| e.to_string())?;
+        if outcome.as_bool() == Ok(false) {
+            debug!("Transform script dropped event {}", event.id);
+            return Ok(None);
+        }
+
+        let output = scope
+            .get_value::<Dynamic>("event")
+            .ok_or_else(|| "script removed the `event` variable".to_string())?;
+        rhai::serde::from_dynamic(&output)
+            .map(Some)
+            .map_err(|e| format!("script produced an invalid event: {}", e))
+    }
+
+    fn sandboxed_engine() -> Engine {
+        let mut engine = Engine::new();
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.set_max_operations(100_000);
+        engine.set_max_call_levels(32);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_string_size(16 * 1024);
+        engine.set_max_array_size(1024);
+        engine.set_max_map_size(1024);
+        engine.on_print(|text| debug!("transform script: {}", text));
+        engine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+
+    const WHALE_SCRIPT: &str = r#"
+        if event.amount_in_usd != () && event.amount_in_usd > 100000.0 {
+            event.tags.push("whale");
+        }
+        if event.amount_in_usd == () {
+            false
+        }
+    "#;
+
+    fn event_with_usd(amount_in_usd: Option<f64>) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.amount_in_usd = amount_in_usd;
+        event
+    }
+
+    #[test]
+    fn test_script_tags_events_above_usd_threshold() {
+        let transformer = EventTransformer::from_script(WHALE_SCRIPT, false).unwrap();
+
+        let whale = transformer
+            .transform(event_with_usd(Some(250_000.0)))
+            .unwrap();
+        assert_eq!(whale.tags, vec!["whale".to_string()]);
+        assert_eq!(whale.amount_in_usd, Some(250_000.0));
+
+        let minnow = transformer.transform(event_with_usd(Some(50.0))).unwrap();
+        assert!(minnow.tags.is_empty());
+
+        // Events without a USD value are filtered out by the script
+        assert!(transformer.transform(event_with_usd(None)).is_none());
+    }
+
+    #[test]
+    fn test_script_errors_respect_strictness() {
+        let script = r#"event.amount_in_usd = "not a number";"#;
+        let lenient = EventTransformer::from_script(script, false).unwrap();
+        let strict = EventTransformer::from_script(script, true).unwrap();
+
+        let unchanged = lenient.transform(event_with_usd(Some(1.0))).unwrap();
+        assert_eq!(unchanged.amount_in_usd, Some(1.0));
+        assert!(strict.transform(event_with_usd(Some(1.0))).is_none());
+    }
+
+    #[test]
+    fn test_script_cannot_import_modules() {
+        let transformer =
+            EventTransformer::from_script(r#"import "std" as s; event"#, true).unwrap();
+        assert!(transformer.transform(event_with_usd(Some(1.0))).is_none());
+    }
+}
@@ -0,0 +1,384 @@
+use crate::config::AppConfig;
+use crate::error::Result;
+use crate::model::{
+    BlockSummary, ControlMessage, OhlcCandle, QuarantinedSwap, SwapEvent, UniswapVersion,
+};
+use crate::sink::{EventSink, KeyedEvent};
+use crate::utils::clock::Clock;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+#[derive(Default)]
+struct Buffer {
+    events: Vec<(Option<String>, SwapEvent)>,
+    volume_usd: f64,
+    oldest: Option<DateTime<Utc>>,
+}
+
+impl Buffer {
+    fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Drop events buffered after the first `len`
+    fn truncate(&mut self, len: usize) {
+        self.events.truncate(len);
+        self.volume_usd = self
+            .events
+            .iter()
+            .filter_map(|(_, event)| event.volume_usd())
+            .sum();
+        if self.events.is_empty() {
+            self.oldest = None;
+        }
+    }
+}
+
+/// Sink wrapper that holds events and delivers them in larger batches
+///
+/// Buffered events are flushed to the wrapped sink once any trigger fires:
+/// `redis.buffer_max_events` are waiting, their summed `amount_usd` reaches
+/// `redis.flush_volume_usd`, or the oldest has waited
+/// `redis.buffer_max_age_ms`. Block summaries, candles and control messages
+/// are not buffered.
+///
+/// With `redis.order_window_ms` set, events from all sources are held for
+/// that window instead and delivered sorted by block, log index, then
+/// source (V2 before V3), with the event id as the final tiebreak. This
+/// gives consumers one deterministic order at the cost of up to the window
+/// in added latency; an event arriving after a window has been delivered
+/// can still sort before it, so the window should cover the spread between
+/// the sources' polling.
+pub struct BufferedSink {
+    inner: Arc<dyn EventSink>,
+    clock: Arc<dyn Clock>,
+    max_events: usize,
+    max_age: Duration,
+    flush_volume_usd: f64,
+    ordered: bool,
+    buffer: Mutex<Buffer>,
+}
+
+impl BufferedSink {
+    /// Wrap `inner` if a size or volume trigger or an ordering window is configured
+    pub fn from_config(
+        config: &AppConfig,
+        inner: Arc<dyn EventSink>,
+        clock: Arc<dyn Clock>,
+    ) -> Option<Self> {
+        let redis = &config.redis;
+        let ordered = redis.order_window_ms > 0;
+        let max_age_ms = if ordered {
+            redis.order_window_ms
+        } else {
+            redis.buffer_max_age_ms
+        };
+        (redis.buffer_max_events > 0 || redis.flush_volume_usd > 0.0 || ordered).then(|| Self {
+            inner,
+            clock,
+            max_events: redis.buffer_max_events,
+            max_age: Duration::from_millis(max_age_ms),
+            flush_volume_usd: redis.flush_volume_usd,
+            ordered,
+            buffer: Mutex::new(Buffer::default()),
+        })
+    }
+
+    /// Longest an event waits in the buffer
+    pub fn max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Deliver the buffer if its oldest event has waited `max_age`
+    pub async fn flush_expired(&self) -> Result<()> {
+        let mut buffer = self.buffer.lock().await;
+        if self.is_expired(&buffer) {
+            debug!("Flushing {} buffered events (age)", buffer.len());
+            self.deliver(&mut buffer).await?;
+        }
+        Ok(())
+    }
+
+    async fn push(&self, events: Vec<(Option<String>, SwapEvent)>) -> Result<()> {
+        let mut buffer = self.buffer.lock().await;
+        let previous_len = buffer.len();
+
+        for (key, event) in events {
+            buffer.volume_usd += event.volume_usd().unwrap_or(0.0);
+            buffer.events.push((key, event));
+        }
+        if buffer.oldest.is_none() && !buffer.events.is_empty() {
+            buffer.oldest = Some(self.clock.now());
+        }
+
+        let trigger = if self.max_events > 0 && buffer.len() >= self.max_events {
+            Some("size")
+        } else if self.flush_volume_usd > 0.0 && buffer.volume_usd >= self.flush_volume_usd {
+            Some("volume")
+        } else if self.is_expired(&buffer) {
+            Some("age")
+        } else {
+            None
+        };
+
+        if let Some(trigger) = trigger {
+            debug!("Flushing {} buffered events ({})", buffer.len(), trigger);
+            if let Err(e) = self.deliver(&mut buffer).await {
+                // The caller retries this batch, so only keep what was already buffered
+                buffer.truncate(previous_len);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn is_expired(&self, buffer: &Buffer) -> bool {
+        buffer.oldest.is_some_and(|oldest| {
+            (self.clock.now() - oldest)
+                .to_std()
+                .is_ok_and(|waited| waited >= self.max_age)
+        })
+    }
+
+    /// Publish everything buffered, keeping the buffer if the inner sink fails
+    async fn deliver(&self, buffer: &mut Buffer) -> Result<()> {
+        if buffer.events.is_empty() {
+            return Ok(());
+        }
+
+        if self.ordered {
+            buffer
+                .events
+                .sort_by(|(_, a), (_, b)| emission_order(a).cmp(&emission_order(b)));
+        }
+
+        if buffer.events.iter().any(|(key, _)| key.is_some()) {
+            let keyed: Vec<KeyedEvent<'_>> = buffer
+                .events
+                .iter()
+                .map(|(key, event)| KeyedEvent {
+                    key: key.as_deref(),
+                    event,
+                })
+                .collect();
+            self.inner.publish_keyed_batch(&keyed).await?;
+        } else {
+            let events: Vec<SwapEvent> = buffer
+                .events
+                .iter()
+                .map(|(_, event)| event.clone())
+                .collect();
+            self.inner.publish_batch(&events).await?;
+        }
+
+        *buffer = Buffer::default();
+        Ok(())
+    }
+}
+
+/// Sort key for `redis.order_window_ms`: block, log index, source, then id
+fn emission_order(event: &SwapEvent) -> (u64, Option<u32>, &UniswapVersion, &str) {
+    (
+        event.block_number,
+        event.log_index,
+        &event.version,
+        &event.id,
+    )
+}
+
+#[async_trait]
+impl EventSink for BufferedSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        self.push(events.iter().map(|event| (None, event.clone())).collect())
+            .await
+    }
+
+    async fn publish_keyed_batch(&self, events: &[KeyedEvent<'_>]) -> Result<()> {
+        self.push(
+            events
+                .iter()
+                .map(|keyed| (keyed.key.map(str::to_string), keyed.event.clone()))
+                .collect(),
+        )
+        .await
+    }
+
+    async fn publish_block_summaries(&self, summaries: &[BlockSummary]) -> Result<()> {
+        self.inner.publish_block_summaries(summaries).await
+    }
+
+    async fn publish_candles(&self, candles: &[OhlcCandle]) -> Result<()> {
+        self.inner.publish_candles(candles).await
+    }
+
+    async fn publish_quarantined(&self, swaps: &[QuarantinedSwap]) -> Result<()> {
+        self.inner.publish_quarantined(swaps).await
+    }
+
+    async fn publish_control(&self, message: &ControlMessage) -> Result<()> {
+        self.inner.publish_control(message).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        let mut buffer = self.buffer.lock().await;
+        self.deliver(&mut buffer).await?;
+        self.inner.flush().await
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        self.inner.test_connection().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+    use crate::utils::clock::MockClock;
+    use std::sync::Mutex as StdMutex;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        batches: StdMutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl EventSink for RecordingSink {
+        async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+            self.batches.lock().unwrap().push(events.len());
+            Ok(())
+        }
+    }
+
+    fn swap_worth(usd: f64) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.set_usd_amounts(usd, usd);
+        event
+    }
+
+    fn buffered(
+        config: &AppConfig,
+        inner: Arc<RecordingSink>,
+        clock: Arc<MockClock>,
+    ) -> BufferedSink {
+        BufferedSink::from_config(config, inner, clock).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_flushes_once_volume_threshold_crossed() {
+        let mut config = AppConfig::default();
+        config.redis.flush_volume_usd = 1_000_000.0;
+        config.redis.buffer_max_age_ms = 60_000;
+        let inner = Arc::new(RecordingSink::default());
+        let sink = buffered(&config, inner.clone(), Arc::new(MockClock::new(Utc::now())));
+
+        for usd in [250_000.0, 300_000.0, 400_000.0] {
+            sink.publish_batch(&[swap_worth(usd)]).await.unwrap();
+        }
+        assert!(inner.batches.lock().unwrap().is_empty());
+
+        sink.publish_batch(&[swap_worth(100_000.0)]).await.unwrap();
+        assert_eq!(*inner.batches.lock().unwrap(), vec![4]);
+
+        // The volume count restarts after a flush
+        sink.publish_batch(&[swap_worth(500_000.0)]).await.unwrap();
+        assert_eq!(*inner.batches.lock().unwrap(), vec![4]);
+    }
+
+    #[tokio::test]
+    async fn test_size_and_age_triggers() {
+        let mut config = AppConfig::default();
+        config.redis.buffer_max_events = 3;
+        config.redis.buffer_max_age_ms = 1_000;
+        let inner = Arc::new(RecordingSink::default());
+        let clock = Arc::new(MockClock::new(Utc::now()));
+        let sink = buffered(&config, inner.clone(), clock.clone());
+
+        sink.publish_batch(&[swap_worth(1.0), swap_worth(1.0)])
+            .await
+            .unwrap();
+        sink.publish_batch(&[swap_worth(1.0)]).await.unwrap();
+        assert_eq!(*inner.batches.lock().unwrap(), vec![3]);
+
+        sink.publish_batch(&[swap_worth(1.0)]).await.unwrap();
+        sink.flush_expired().await.unwrap();
+        assert_eq!(*inner.batches.lock().unwrap(), vec![3]);
+
+        clock.advance(chrono::Duration::milliseconds(1_000));
+        sink.flush_expired().await.unwrap();
+        assert_eq!(*inner.batches.lock().unwrap(), vec![3, 1]);
+    }
+
+    /// Sink that records event ids in the order they arrive
+    #[derive(Default)]
+    struct OrderRecordingSink {
+        ids: StdMutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl EventSink for OrderRecordingSink {
+        async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+            self.ids
+                .lock()
+                .unwrap()
+                .extend(events.iter().map(|event| event.id.clone()));
+            Ok(())
+        }
+    }
+
+    fn swap_at(id: &str, version: UniswapVersion, block_number: u64, log_index: u32) -> SwapEvent {
+        let mut event = SwapEventBuilder::test_builder().unwrap();
+        event.id = id.to_string();
+        event.version = version;
+        event.block_number = block_number;
+        event.log_index = Some(log_index);
+        event
+    }
+
+    #[tokio::test]
+    async fn test_order_window_emits_sources_in_deterministic_order() {
+        let mut config = AppConfig::default();
+        config.redis.order_window_ms = 500;
+        let clock = Arc::new(MockClock::new(Utc::now()));
+
+        // The same swaps, with the two sources' batches arriving in either order
+        let v2 = vec![
+            swap_at("v2-b11-l3", UniswapVersion::V2, 11, 3),
+            swap_at("v2-b10-l7", UniswapVersion::V2, 10, 7),
+            swap_at("v2-b12-l0", UniswapVersion::V2, 12, 0),
+        ];
+        let v3 = vec![
+            swap_at("v3-b10-l2", UniswapVersion::V3, 10, 2),
+            swap_at("v3-b11-l3", UniswapVersion::V3, 11, 3),
+        ];
+        let mut outputs = Vec::new();
+        for batches in [[&v2, &v3], [&v3, &v2]] {
+            let inner = Arc::new(OrderRecordingSink::default());
+            let sink = BufferedSink::from_config(&config, inner.clone(), clock.clone()).unwrap();
+            for batch in batches {
+                sink.publish_batch(batch).await.unwrap();
+            }
+            assert!(inner.ids.lock().unwrap().is_empty());
+
+            clock.advance(chrono::Duration::milliseconds(500));
+            sink.flush_expired().await.unwrap();
+            outputs.push(inner.ids.lock().unwrap().clone());
+        }
+
+        assert_eq!(
+            outputs[0],
+            vec![
+                "v3-b10-l2",
+                "v2-b10-l7",
+                "v2-b11-l3",
+                "v3-b11-l3",
+                "v2-b12-l0"
+            ]
+        );
+        assert_eq!(outputs[0], outputs[1]);
+    }
+}
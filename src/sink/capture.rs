@@ -0,0 +1,89 @@
+use crate::error::{DAppError, Result, SerializationError};
+use crate::model::{BlockSummary, ControlMessage, OhlcCandle, QuarantinedSwap, SwapEvent};
+use crate::sink::{EventSink, KeyedEvent};
+use async_trait::async_trait;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+/// Sink wrapper that appends every delivered event to a file
+///
+/// Events are written as newline-delimited JSON, in the same shape they are
+/// published, after the wrapped sink accepted them. The file can be fed back
+/// through `SwapEventCollector::replay_from_file`.
+pub struct CaptureSink {
+    inner: Arc<dyn EventSink>,
+    path: String,
+    file: Mutex<File>,
+}
+
+impl CaptureSink {
+    /// Wrap `inner`, appending delivered events to `path`
+    pub fn open(path: &str, inner: Arc<dyn EventSink>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                DAppError::Config(format!("Failed to open capture file {}: {}", path, e))
+            })?;
+        info!("Capturing published events to {}", path);
+
+        Ok(Self {
+            inner,
+            path: path.to_string(),
+            file: Mutex::new(file),
+        })
+    }
+
+    fn capture<'a>(&self, events: impl Iterator<Item = &'a SwapEvent>) -> Result<()> {
+        let mut lines = Vec::new();
+        SwapEvent::serialize_batch_ndjson(events, &mut lines)
+            .map_err(|e| SerializationError::Json(e.to_string()))?;
+
+        self.file.lock().unwrap().write_all(&lines).map_err(|e| {
+            DAppError::Internal(format!("Failed to write capture file {}: {}", self.path, e))
+        })
+    }
+}
+
+#[async_trait]
+impl EventSink for CaptureSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        self.inner.publish_batch(events).await?;
+        self.capture(events.iter())
+    }
+
+    async fn publish_keyed_batch(&self, events: &[KeyedEvent<'_>]) -> Result<()> {
+        self.inner.publish_keyed_batch(events).await?;
+        self.capture(events.iter().map(|keyed| keyed.event))
+    }
+
+    async fn publish_block_summaries(&self, summaries: &[BlockSummary]) -> Result<()> {
+        self.inner.publish_block_summaries(summaries).await
+    }
+
+    async fn publish_candles(&self, candles: &[OhlcCandle]) -> Result<()> {
+        self.inner.publish_candles(candles).await
+    }
+
+    async fn publish_quarantined(&self, swaps: &[QuarantinedSwap]) -> Result<()> {
+        self.inner.publish_quarantined(swaps).await
+    }
+
+    async fn publish_control(&self, message: &ControlMessage) -> Result<()> {
+        self.inner.publish_control(message).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.inner.flush().await?;
+        self.file.lock().unwrap().flush().map_err(|e| {
+            DAppError::Internal(format!("Failed to flush capture file {}: {}", self.path, e))
+        })
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        self.inner.test_connection().await
+    }
+}
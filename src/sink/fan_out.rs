@@ -0,0 +1,209 @@
+use crate::config::AppConfig;
+use crate::error::Result;
+use crate::model::{BlockSummary, ControlMessage, OhlcCandle, QuarantinedSwap, SwapEvent};
+use crate::sink::{EventSink, KeyedEvent};
+use async_trait::async_trait;
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Sink that delivers everything to several target sinks concurrently
+///
+/// At most `redis.publish_concurrency` targets are published to at once
+/// (0 = all of them), so a slow target delays only its own delivery rather
+/// than every target queued behind it. Each call waits for all targets and
+/// fails with the first error if any target failed.
+#[allow(dead_code)]
+pub struct FanOutSink {
+    targets: Vec<Arc<dyn EventSink>>,
+    concurrency: usize,
+}
+
+#[allow(dead_code)]
+impl FanOutSink {
+    pub fn new(config: &AppConfig, targets: Vec<Arc<dyn EventSink>>) -> Self {
+        let concurrency = match config.redis.publish_concurrency {
+            0 => targets.len().max(1),
+            limit => limit,
+        };
+        Self {
+            targets,
+            concurrency,
+        }
+    }
+
+    /// Run one publish per target, then report any failures
+    async fn fan_out<'a>(
+        &'a self,
+        operation: &str,
+        publish: impl Fn(&'a dyn EventSink) -> BoxFuture<'a, Result<()>>,
+    ) -> Result<()> {
+        let publishes: Vec<_> = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| publish(target.as_ref()).map(move |result| (index, result)))
+            .collect();
+        let results: Vec<(usize, Result<()>)> = stream::iter(publishes)
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        let mut first_error = None;
+        for (index, result) in results {
+            if let Err(e) = result {
+                warn!("{} failed on target {}: {}", operation, index, e);
+                first_error.get_or_insert(e);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+#[async_trait]
+impl EventSink for FanOutSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        self.fan_out("Publishing batch", |target| target.publish_batch(events))
+            .await
+    }
+
+    async fn publish_keyed_batch(&self, events: &[KeyedEvent<'_>]) -> Result<()> {
+        self.fan_out("Publishing keyed batch", |target| {
+            target.publish_keyed_batch(events)
+        })
+        .await
+    }
+
+    async fn publish_block_summaries(&self, summaries: &[BlockSummary]) -> Result<()> {
+        self.fan_out("Publishing block summaries", |target| {
+            target.publish_block_summaries(summaries)
+        })
+        .await
+    }
+
+    async fn publish_candles(&self, candles: &[OhlcCandle]) -> Result<()> {
+        self.fan_out("Publishing candles", |target| {
+            target.publish_candles(candles)
+        })
+        .await
+    }
+
+    async fn publish_quarantined(&self, swaps: &[QuarantinedSwap]) -> Result<()> {
+        self.fan_out("Publishing quarantined swaps", |target| {
+            target.publish_quarantined(swaps)
+        })
+        .await
+    }
+
+    async fn publish_control(&self, message: &ControlMessage) -> Result<()> {
+        self.fan_out("Publishing control message", |target| {
+            target.publish_control(message)
+        })
+        .await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.fan_out("Flushing", |target| target.flush()).await
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        self.fan_out("Connection test", |target| target.test_connection())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{DAppError, RedisError};
+    use crate::model::SwapEventBuilder;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Target that takes `delay` to publish, optionally failing
+    struct SlowSink {
+        delay: Duration,
+        fail: bool,
+        published: Mutex<usize>,
+    }
+
+    impl SlowSink {
+        fn new(delay_ms: u64) -> Arc<Self> {
+            Arc::new(Self {
+                delay: Duration::from_millis(delay_ms),
+                fail: false,
+                published: Mutex::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl EventSink for SlowSink {
+        async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(RedisError::Publish("target down".to_string()).into());
+            }
+            *self.published.lock().unwrap() += events.len();
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_latency_bounded_by_slowest_target() {
+        let targets = [SlowSink::new(50), SlowSink::new(50), SlowSink::new(200)];
+        let sink = FanOutSink::new(
+            &AppConfig::default(),
+            targets
+                .iter()
+                .map(|target| target.clone() as Arc<dyn EventSink>)
+                .collect(),
+        );
+        let event = SwapEventBuilder::test_builder().unwrap();
+
+        let started = tokio::time::Instant::now();
+        sink.publish_batch(&[event]).await.unwrap();
+
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+        assert!(targets
+            .iter()
+            .all(|target| *target.published.lock().unwrap() == 1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrency_limit_queues_targets() {
+        let mut config = AppConfig::default();
+        config.redis.publish_concurrency = 1;
+        let sink = FanOutSink::new(
+            &config,
+            vec![SlowSink::new(50), SlowSink::new(50), SlowSink::new(200)],
+        );
+        let event = SwapEventBuilder::test_builder().unwrap();
+
+        let started = tokio::time::Instant::now();
+        sink.publish_batch(&[event]).await.unwrap();
+
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_failed_target_does_not_stop_others() {
+        let healthy = SlowSink::new(0);
+        let failing = Arc::new(SlowSink {
+            delay: Duration::ZERO,
+            fail: true,
+            published: Mutex::new(0),
+        });
+        let sink = FanOutSink::new(&AppConfig::default(), vec![failing, healthy.clone()]);
+        let event = SwapEventBuilder::test_builder().unwrap();
+
+        let result = sink.publish_batch(&[event]).await;
+
+        assert!(matches!(
+            result,
+            Err(DAppError::Redis(RedisError::Publish(_)))
+        ));
+        assert_eq!(*healthy.published.lock().unwrap(), 1);
+    }
+}
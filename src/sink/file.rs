@@ -0,0 +1,308 @@
+use crate::config::AppConfig;
+use crate::error::{DAppError, Result, SerializationError};
+use crate::model::SwapEvent;
+use crate::sink::EventSink;
+use async_trait::async_trait;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+struct OpenFile {
+    writer: BufWriter<File>,
+    written: u64,
+    opened_at: Instant,
+}
+
+/// Sink appending events to a rotating newline-delimited JSON file
+///
+/// The active file is rotated to `<path>.<UTC timestamp>.<n>` before a batch
+/// that would take it past `file_max_bytes`, or once it has been open for
+/// `file_rotate_interval_seconds`; a batch is never split across files.
+/// Rotated files are gzipped to `<rotated>.gz` when `gzip_on_rotate` is set.
+///
+/// Renames run on the blocking pool and compression in the background, so
+/// neither stalls the task publishing events; `flush` waits for pending
+/// compressions.
+pub struct FileSink {
+    path: String,
+    max_bytes: u64,
+    rotate_interval: Option<Duration>,
+    gzip_on_rotate: bool,
+    rotations: Mutex<u64>,
+    active: tokio::sync::Mutex<OpenFile>,
+    compressions: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl FileSink {
+    /// Create a sink from `[file_sink]`, if a path is configured
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>> {
+        let file_sink = &config.file_sink;
+        let Some(path) = &file_sink.path else {
+            return Ok(None);
+        };
+
+        let rotate_interval = match file_sink.file_rotate_interval_seconds {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        };
+        Self::open(
+            path,
+            file_sink.file_max_bytes,
+            rotate_interval,
+            file_sink.gzip_on_rotate,
+        )
+        .map(Some)
+    }
+
+    /// Append to `path`, rotating at `max_bytes` (0 = no size limit) or `rotate_interval`
+    pub fn open(
+        path: &str,
+        max_bytes: u64,
+        rotate_interval: Option<Duration>,
+        gzip_on_rotate: bool,
+    ) -> Result<Self> {
+        let active = Self::open_file(path)?;
+        info!("Writing events to {}", path);
+
+        Ok(Self {
+            path: path.to_string(),
+            max_bytes,
+            rotate_interval,
+            gzip_on_rotate,
+            rotations: Mutex::new(0),
+            active: tokio::sync::Mutex::new(active),
+            compressions: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn open_file(path: &str) -> Result<OpenFile> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| DAppError::Config(format!("Failed to open event file {}: {}", path, e)))?;
+        let written = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        Ok(OpenFile {
+            writer: BufWriter::new(file),
+            written,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn needs_rotation(&self, active: &OpenFile, incoming: u64) -> bool {
+        if active.written == 0 {
+            return false;
+        }
+        let over_size = self.max_bytes > 0 && active.written + incoming > self.max_bytes;
+        let expired = self
+            .rotate_interval
+            .is_some_and(|interval| active.opened_at.elapsed() >= interval);
+        over_size || expired
+    }
+
+    /// Move the active file aside and start a new one
+    async fn rotate(&self, active: &mut OpenFile) -> Result<()> {
+        active
+            .writer
+            .flush()
+            .map_err(|e| io_error(&self.path, "flush", e))?;
+
+        let rotation = {
+            let mut rotations = self.rotations.lock().unwrap();
+            *rotations += 1;
+            *rotations
+        };
+        let rotated = format!(
+            "{}.{}.{}",
+            self.path,
+            chrono::Utc::now().format("%Y%m%dT%H%M%S"),
+            rotation
+        );
+
+        let path = self.path.clone();
+        let rotated_path = rotated.clone();
+        *active = tokio::task::spawn_blocking(move || {
+            std::fs::rename(&path, &rotated_path).map_err(|e| io_error(&path, "rotate", e))?;
+            Self::open_file(&path)
+        })
+        .await
+        .map_err(|e| DAppError::Internal(format!("Event file rotation panicked: {}", e)))??;
+        info!("Rotated event file to {}", rotated);
+
+        if self.gzip_on_rotate {
+            let compression = tokio::task::spawn_blocking(move || {
+                if let Err(e) = Self::gzip(&rotated) {
+                    warn!("Failed to gzip rotated event file {}: {}", rotated, e);
+                }
+            });
+            let mut compressions = self.compressions.lock().unwrap();
+            compressions.retain(|compression| !compression.is_finished());
+            compressions.push(compression);
+        }
+        Ok(())
+    }
+
+    /// Compress `path` to `<path>.gz` and remove the original
+    fn gzip(path: &str) -> std::io::Result<()> {
+        let mut source = BufReader::new(File::open(path)?);
+        let mut encoder = GzEncoder::new(
+            File::create(format!("{}.gz", path))?,
+            Compression::default(),
+        );
+        std::io::copy(&mut source, &mut encoder)?;
+        encoder.finish()?;
+        std::fs::remove_file(path)
+    }
+}
+
+fn io_error(path: &str, action: &str, e: std::io::Error) -> DAppError {
+    DAppError::Internal(format!("Failed to {} event file {}: {}", action, path, e))
+}
+
+#[async_trait]
+impl EventSink for FileSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let mut lines = Vec::new();
+        SwapEvent::serialize_batch_ndjson(events, &mut lines)
+            .map_err(|e| SerializationError::Json(e.to_string()))?;
+
+        let mut active = self.active.lock().await;
+        if self.needs_rotation(&active, lines.len() as u64) {
+            self.rotate(&mut active).await?;
+        }
+        active
+            .writer
+            .write_all(&lines)
+            .map_err(|e| io_error(&self.path, "write", e))?;
+        active.written += lines.len() as u64;
+        Ok(())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.active
+            .lock()
+            .await
+            .writer
+            .flush()
+            .map_err(|e| io_error(&self.path, "flush", e))?;
+
+        let compressions = std::mem::take(&mut *self.compressions.lock().unwrap());
+        for compression in compressions {
+            if let Err(e) = compression.await {
+                warn!("Event file compression panicked: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn test_connection(&self) -> Result<()> {
+        std::fs::metadata(&self.path)
+            .map(|_| ())
+            .map_err(|e| io_error(&self.path, "stat", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("relay-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn files_in(dir: &std::path::Path) -> Vec<String> {
+        let mut files: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn batch(size: usize) -> Vec<SwapEvent> {
+        (0..size)
+            .map(|_| SwapEventBuilder::test_builder().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_batch_written_as_json_lines() {
+        let dir = temp_dir("file-sink");
+        let path = dir.join("events.ndjson");
+        let sink = FileSink::open(path.to_str().unwrap(), 0, None, false).unwrap();
+
+        sink.publish_batch(&batch(3)).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        for line in lines {
+            serde_json::from_str::<SwapEvent>(line).unwrap();
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_configured_sink_dispatches_as_trait_object() {
+        let dir = temp_dir("file-sink-dyn");
+        let path = dir.join("events.ndjson");
+        let mut config = AppConfig::default();
+        assert!(FileSink::from_config(&config).unwrap().is_none());
+        config.file_sink.path = Some(path.to_string_lossy().into_owned());
+
+        let sink: std::sync::Arc<dyn EventSink> =
+            std::sync::Arc::new(FileSink::from_config(&config).unwrap().unwrap());
+        sink.test_connection().await.unwrap();
+        sink.publish_batch(&batch(2)).await.unwrap();
+        sink.publish_batch(&batch(1)).await.unwrap();
+        sink.flush().await.unwrap();
+
+        // Appended across batches rather than overwritten
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_rotates_and_gzips_at_size_limit() {
+        let dir = temp_dir("file-sink-rotate");
+        let path = dir.join("events.ndjson");
+        let line_len = serde_json::to_string(&batch(1)[0]).unwrap().len() as u64 + 1;
+        // Room for two events per file
+        let sink = FileSink::open(path.to_str().unwrap(), line_len * 2, None, true).unwrap();
+
+        sink.publish_batch(&batch(2)).await.unwrap();
+        assert_eq!(files_in(&dir), vec!["events.ndjson"]);
+
+        sink.publish_batch(&batch(1)).await.unwrap();
+        // Compression runs in the background until flushed
+        sink.flush().await.unwrap();
+        let files = files_in(&dir);
+        assert_eq!(files.len(), 2);
+        let rotated = files.iter().find(|name| name.ends_with(".gz")).unwrap();
+
+        let mut archived = String::new();
+        GzDecoder::new(File::open(dir.join(rotated)).unwrap())
+            .read_to_string(&mut archived)
+            .unwrap();
+        assert_eq!(archived.lines().count(), 2);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,63 @@
+pub mod buffered;
+pub mod capture;
+pub mod fan_out;
+pub mod file;
+pub mod webhook;
+
+use crate::error::Result;
+use crate::model::{BlockSummary, ControlMessage, OhlcCandle, QuarantinedSwap, SwapEvent};
+use async_trait::async_trait;
+
+/// Event paired with the partition key a partitioned sink should route it by
+#[derive(Debug, Clone, Copy)]
+pub struct KeyedEvent<'a> {
+    #[allow(dead_code)]
+    pub key: Option<&'a str>,
+    pub event: &'a SwapEvent,
+}
+
+/// Destination for collected swap events
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Publish a batch of swap events
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()>;
+
+    /// Publish a batch where each event carries an optional partition key
+    ///
+    /// Partitioned sinks (Kafka, streams) route on the key so events sharing
+    /// it stay in order; the default ignores keys.
+    async fn publish_keyed_batch(&self, events: &[KeyedEvent<'_>]) -> Result<()> {
+        let events: Vec<SwapEvent> = events.iter().map(|keyed| keyed.event.clone()).collect();
+        self.publish_batch(&events).await
+    }
+
+    /// Publish per-block aggregates; sinks without a use for them ignore them
+    async fn publish_block_summaries(&self, _summaries: &[BlockSummary]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Publish completed OHLC candles; sinks without a use for them ignore them
+    async fn publish_candles(&self, _candles: &[OhlcCandle]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Publish swaps that failed to parse; sinks without a quarantine ignore them
+    async fn publish_quarantined(&self, _swaps: &[QuarantinedSwap]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Publish a control message alongside the events; ignored by default
+    async fn publish_control(&self, _message: &ControlMessage) -> Result<()> {
+        Ok(())
+    }
+
+    /// Flush anything the sink has buffered
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Check that the sink is reachable
+    async fn test_connection(&self) -> Result<()> {
+        Ok(())
+    }
+}
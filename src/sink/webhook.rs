@@ -0,0 +1,223 @@
+use crate::config::AppConfig;
+use crate::error::{DAppError, NetworkError, Result, SerializationError};
+use crate::model::SwapEvent;
+use crate::sink::EventSink;
+use crate::utils::backoff::ExponentialBackoff;
+use crate::utils::retry::retry_async_if;
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, StatusCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::warn;
+
+/// Sink POSTing each batch as a JSON array to `webhook_sink.url`
+///
+/// Failed requests are retried with the `[retry]` backoff. Client errors
+/// other than 408 and 429 mean the endpoint rejected the payload, so they
+/// fail immediately; everything that isn't 2xx maps to `DAppError::Network`.
+pub struct WebhookSink {
+    client: Client,
+    url: String,
+    config: AppConfig,
+}
+
+impl WebhookSink {
+    /// Create a sink from `[webhook_sink]`, if a URL is configured
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>> {
+        let Some(url) = &config.webhook_sink.url else {
+            return Ok(None);
+        };
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.webhook_sink.headers {
+            let name = HeaderName::try_from(name.as_str()).map_err(|e| {
+                DAppError::Config(format!("Invalid webhook header name {}: {}", name, e))
+            })?;
+            let value = HeaderValue::try_from(value.as_str()).map_err(|e| {
+                DAppError::Config(format!("Invalid webhook header value for {}: {}", name, e))
+            })?;
+            headers.insert(name, value);
+        }
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let client = Client::builder()
+            .timeout(Duration::from_millis(config.webhook_sink.timeout_ms))
+            .default_headers(headers)
+            .build()
+            .map_err(|e| {
+                NetworkError::Http(format!("Failed to create webhook HTTP client: {}", e))
+            })?;
+
+        Ok(Some(Self {
+            client,
+            url: url.clone(),
+            config: config.clone(),
+        }))
+    }
+
+    fn backoff(&self) -> ExponentialBackoff {
+        let retry = &self.config.retry;
+        ExponentialBackoff::new(
+            Duration::from_millis(retry.initial_delay_ms),
+            Duration::from_millis(retry.max_delay_ms),
+            retry.backoff_multiplier,
+            retry.max_attempts.saturating_sub(1),
+        )
+    }
+
+    /// POST `body` once, flagging `rejected` when retrying cannot help
+    async fn post(&self, body: &[u8], rejected: &AtomicBool) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|e| NetworkError::Http(format!("Webhook request failed: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        if status.is_client_error()
+            && status != StatusCode::REQUEST_TIMEOUT
+            && status != StatusCode::TOO_MANY_REQUESTS
+        {
+            rejected.store(true, Ordering::Relaxed);
+        }
+        Err(NetworkError::Http(format!("Webhook responded with {}", status)).into())
+    }
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let body =
+            serde_json::to_vec(events).map_err(|e| SerializationError::Json(e.to_string()))?;
+        let rejected = AtomicBool::new(false);
+        retry_async_if(
+            self.backoff(),
+            || self.post(&body, &rejected),
+            |e, delay| {
+                let retry = !rejected.load(Ordering::Relaxed);
+                if retry {
+                    warn!("Webhook delivery failed, retrying in {:?}: {}", delay, e);
+                }
+                retry
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SwapEventBuilder;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn webhook_config(server: &MockServer) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.webhook_sink.url = Some(format!("{}/hooks/swaps", server.uri()));
+        config.webhook_sink.headers = [("X-Relay-Token".to_string(), "secret".to_string())].into();
+        config.retry.max_attempts = 3;
+        config.retry.initial_delay_ms = 1;
+        config.retry.max_delay_ms = 10;
+        config
+    }
+
+    fn batch(size: usize) -> Vec<SwapEvent> {
+        (0..size)
+            .map(|i| {
+                let mut event = SwapEventBuilder::test_builder().unwrap();
+                event.id = format!("0xswap-{}", i);
+                event
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_batch_posted_as_json_array_with_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hooks/swaps"))
+            .and(header("x-relay-token", "secret"))
+            .and(header("content-type", "application/json"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::from_config(&webhook_config(&server))
+            .unwrap()
+            .unwrap();
+
+        sink.publish_batch(&batch(2)).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let posted: Vec<SwapEvent> = serde_json::from_slice(&requests[0].body).unwrap();
+        let ids: Vec<&str> = posted.iter().map(|event| event.id.as_str()).collect();
+        assert_eq!(ids, ["0xswap-0", "0xswap-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_endpoint_retried_until_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::from_config(&webhook_config(&server))
+            .unwrap()
+            .unwrap();
+
+        sink.publish_batch(&batch(1)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_persistent_failure_maps_to_network_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::from_config(&webhook_config(&server))
+            .unwrap()
+            .unwrap();
+
+        let result = sink.publish_batch(&batch(1)).await;
+
+        assert!(matches!(
+            result,
+            Err(DAppError::Network(NetworkError::Http(message))) if message.contains("503")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_payload_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::from_config(&webhook_config(&server))
+            .unwrap()
+            .unwrap();
+
+        assert!(sink.publish_batch(&batch(1)).await.is_err());
+    }
+}
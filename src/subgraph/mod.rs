@@ -1,3 +1,4 @@
 pub mod client;
+pub mod transport;
 
 pub use client::SubgraphClient;
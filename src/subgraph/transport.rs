@@ -0,0 +1,326 @@
+use crate::config::AppConfig;
+use crate::error::{DAppError, NetworkError, Result, SubgraphError};
+use async_trait::async_trait;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Certificate, Client, Request};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Raw HTTP response returned by a transport
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportResponse {
+    pub status: u16,
+    pub body: String,
+    /// Raw `Retry-After` header, if the server sent one
+    pub retry_after: Option<String>,
+}
+
+/// Sends JSON requests to a subgraph (or JSON-RPC) endpoint
+#[async_trait]
+pub trait SubgraphTransport: Send + Sync {
+    /// POST a JSON body to `url` and return the raw response
+    async fn post(&self, url: &str, body: &Value) -> Result<TransportResponse>;
+
+    /// POST with an `Authorization: Bearer <bearer_token>` header
+    ///
+    /// Transports that don't send real HTTP requests ignore the token.
+    async fn post_authorized(
+        &self,
+        url: &str,
+        body: &Value,
+        _bearer_token: &str,
+    ) -> Result<TransportResponse> {
+        self.post(url, body).await
+    }
+}
+
+/// Transport backed by a reqwest HTTP client
+pub struct HttpTransport {
+    client: Client,
+    /// Gzip bodies larger than this many bytes, if set
+    compress_threshold: Option<usize>,
+}
+
+impl HttpTransport {
+    /// Create an HTTP transport using the subgraph timeout
+    ///
+    /// Fails if `subgraph.ca_cert_path` can't be read or the client can't be
+    /// built from the configured settings.
+    pub fn new(config: &AppConfig) -> Result<Self> {
+        let client_error = |e: &dyn std::fmt::Display| {
+            DAppError::Network(NetworkError::Http(format!(
+                "Failed to create HTTP client: {}",
+                e
+            )))
+        };
+
+        let mut builder = Client::builder().timeout(config.subgraph.request_timeout());
+        if let Some(path) = &config.subgraph.ca_cert_path {
+            let pem = std::fs::read(path)
+                .map_err(|e| client_error(&format!("cannot read {}: {}", path, e)))?;
+            let certificate = Certificate::from_pem(&pem).map_err(|e| client_error(&e))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        let client = builder.build().map_err(|e| client_error(&e))?;
+
+        Ok(Self {
+            client,
+            compress_threshold: config
+                .subgraph
+                .compress_requests
+                .then_some(config.subgraph.compress_threshold_bytes),
+        })
+    }
+
+    /// Build the POST request, gzipping the body when it exceeds the threshold
+    fn build_request(
+        &self,
+        url: &str,
+        body: &Value,
+        bearer_token: Option<&str>,
+    ) -> Result<Request> {
+        let json = serde_json::to_vec(body)
+            .map_err(|e| DAppError::Subgraph(SubgraphError::Parsing(e.to_string())))?;
+        let mut request = self
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, "application/json");
+        if let Some(token) = bearer_token {
+            request = request.bearer_auth(token);
+        }
+
+        let request = match self.compress_threshold {
+            Some(threshold) if json.len() > threshold => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                let compressed = encoder
+                    .write_all(&json)
+                    .and_then(|_| encoder.finish())
+                    .map_err(|e| {
+                        DAppError::Subgraph(SubgraphError::Http(format!(
+                            "Failed to gzip request body: {}",
+                            e
+                        )))
+                    })?;
+                request.header(CONTENT_ENCODING, "gzip").body(compressed)
+            }
+            _ => request.body(json),
+        };
+
+        request
+            .build()
+            .map_err(|e| DAppError::Subgraph(SubgraphError::Http(e.to_string())))
+    }
+}
+
+#[async_trait]
+impl SubgraphTransport for HttpTransport {
+    async fn post(&self, url: &str, body: &Value) -> Result<TransportResponse> {
+        self.send(self.build_request(url, body, None)?).await
+    }
+
+    async fn post_authorized(
+        &self,
+        url: &str,
+        body: &Value,
+        bearer_token: &str,
+    ) -> Result<TransportResponse> {
+        self.send(self.build_request(url, body, Some(bearer_token))?)
+            .await
+    }
+}
+
+impl HttpTransport {
+    /// Execute `request` and read the whole response
+    async fn send(&self, request: Request) -> Result<TransportResponse> {
+        let response = self.client.execute(request).await.map_err(|e| {
+            if e.is_timeout() {
+                DAppError::Subgraph(SubgraphError::Timeout(format!(
+                    "Subgraph request timed out: {}",
+                    e
+                )))
+            }
+            // Check if this looks like a WebSocket error
+            else if e.to_string().contains("websocket")
+                || e.to_string().contains("ws://")
+                || e.to_string().contains("wss://")
+            {
+                DAppError::Network(crate::error::NetworkError::websocket_error(format!(
+                    "WebSocket error in subgraph request: {}",
+                    e
+                )))
+            }
+            // Check if this looks like a DNS resolution error
+            else if e.to_string().contains("dns")
+                || e.to_string().contains("resolve")
+                || e.to_string().contains("lookup")
+            {
+                DAppError::Network(crate::error::NetworkError::dns_resolution_error(format!(
+                    "DNS resolution error in subgraph request: {}",
+                    e
+                )))
+            }
+            // Check if this looks like a TLS error
+            else if e.to_string().contains("tls")
+                || e.to_string().contains("ssl")
+                || e.to_string().contains("certificate")
+            {
+                DAppError::Network(crate::error::NetworkError::tls_error(format!(
+                    "TLS error in subgraph request: {}",
+                    e
+                )))
+            } else if e.is_connect() {
+                DAppError::Network(crate::error::NetworkError::Http(format!(
+                    "Failed to connect to subgraph: {}",
+                    e
+                )))
+            } else {
+                DAppError::Subgraph(SubgraphError::Http(e.to_string()))
+            }
+        })?;
+
+        let status = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| DAppError::Subgraph(SubgraphError::Http(e.to_string())))?;
+
+        Ok(TransportResponse {
+            status,
+            body,
+            retry_after,
+        })
+    }
+}
+
+/// In-memory transport returning canned responses per URL
+///
+/// Intended for offline tests: every request is recorded and answered with the
+/// responses registered for its URL, in order. The last response is repeated
+/// once the others have been used.
+#[derive(Default)]
+pub struct MockTransport {
+    responses: Mutex<HashMap<String, VecDeque<TransportResponse>>>,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockTransport {
+    /// Create a mock transport with no registered responses
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response with the given status and body for `url`
+    #[allow(dead_code)]
+    pub fn with_response(self, url: &str, status: u16, body: impl Into<String>) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(url.to_string())
+            .or_default()
+            .push_back(TransportResponse {
+                status,
+                body: body.into(),
+                retry_after: None,
+            });
+        self
+    }
+
+    /// Queue a 429 response carrying `Retry-After: <retry_after>` for `url`
+    #[allow(dead_code)]
+    pub fn with_rate_limit(self, url: &str, retry_after: &str) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(url.to_string())
+            .or_default()
+            .push_back(TransportResponse {
+                status: 429,
+                body: String::new(),
+                retry_after: Some(retry_after.to_string()),
+            });
+        self
+    }
+
+    /// Queue a JSON response with status 200 for `url`
+    #[allow(dead_code)]
+    pub fn with_json(self, url: &str, body: Value) -> Self {
+        self.with_response(url, 200, body.to_string())
+    }
+
+    /// Requests received so far as `(url, body)` pairs
+    #[allow(dead_code)]
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl SubgraphTransport for MockTransport {
+    async fn post(&self, url: &str, body: &Value) -> Result<TransportResponse> {
+        self.requests
+            .lock()
+            .unwrap()
+            .push((url.to_string(), body.clone()));
+
+        let mut responses = self.responses.lock().unwrap();
+        let queue = responses.get_mut(url).filter(|queue| !queue.is_empty());
+        queue
+            .map(|queue| {
+                if queue.len() > 1 {
+                    queue.pop_front().unwrap()
+                } else {
+                    queue[0].clone()
+                }
+            })
+            .ok_or_else(|| {
+                DAppError::Subgraph(SubgraphError::Http(format!(
+                    "No mock response registered for {}",
+                    url
+                )))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_large_bodies_are_gzipped() {
+        let mut config = AppConfig::default();
+        config.subgraph.compress_requests = true;
+        config.subgraph.compress_threshold_bytes = 1024;
+        let transport = HttpTransport::new(&config).unwrap();
+        let url = "https://example.com/subgraph";
+
+        let small = serde_json::json!({ "query": "{ swaps { id } }" });
+        let request = transport.build_request(url, &small, None).unwrap();
+        assert!(request.headers().get(CONTENT_ENCODING).is_none());
+
+        let pools: Vec<String> = (0..500).map(|i| format!("0x{:040x}", i)).collect();
+        let large =
+            serde_json::json!({ "query": "{ swaps { id } }", "variables": { "pools": pools } });
+        let request = transport.build_request(url, &large, None).unwrap();
+        assert_eq!(request.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/json");
+
+        let compressed = request.body().and_then(|body| body.as_bytes()).unwrap();
+        let mut decompressed = String::new();
+        GzDecoder::new(compressed)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&decompressed).unwrap(), large);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::config::AppConfig;
+use crate::error::{DAppError, NetworkError, Result};
+use crate::telemetry::metrics::Metrics;
+use async_trait::async_trait;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Pushes metric snapshots to an external monitoring system
+#[async_trait]
+pub trait MetricsExporter: Send + Sync {
+    /// Push one metrics snapshot
+    async fn push(&self, metrics: &Metrics) -> Result<()>;
+}
+
+/// Build the exporter selected by `monitoring.metrics_exporter`
+pub fn exporter_from_config(config: &AppConfig) -> Result<Arc<dyn MetricsExporter>> {
+    let endpoint = || {
+        config
+            .monitoring
+            .metrics_exporter_endpoint
+            .clone()
+            .ok_or_else(|| {
+                DAppError::Config(format!(
+                    "monitoring.metrics_exporter_endpoint is required for the {} exporter",
+                    config.monitoring.metrics_exporter
+                ))
+            })
+    };
+
+    match config.monitoring.metrics_exporter.as_str() {
+        "none" => Ok(Arc::new(NoopExporter)),
+        "statsd" => Ok(Arc::new(StatsdExporter::new(
+            endpoint()?,
+            &config.monitoring.metrics_prefix,
+        )?)),
+        "prometheus_push" => Ok(Arc::new(PrometheusPushExporter::new(
+            endpoint()?,
+            &config.monitoring.metrics_prefix,
+        ))),
+        other => Err(DAppError::Config(format!(
+            "Unknown metrics exporter: {}",
+            other
+        ))),
+    }
+}
+
+/// Exporter that discards every snapshot
+pub struct NoopExporter;
+
+#[async_trait]
+impl MetricsExporter for NoopExporter {
+    async fn push(&self, _metrics: &Metrics) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Named gauge values of a snapshot, shared by the wire formats
+fn gauges(metrics: &Metrics) -> Vec<(String, f64)> {
+    let mut gauges: Vec<(String, f64)> = [
+        (
+            "events_processed_total",
+            metrics.events_processed_total as f64,
+        ),
+        ("events_processed_rate", metrics.events_processed_rate),
+        ("errors_total", metrics.errors_total as f64),
+        ("errors_rate", metrics.errors_rate),
+        ("latency_p50_ms", metrics.latency_p50_ms),
+        ("latency_p95_ms", metrics.latency_p95_ms),
+        ("latency_p99_ms", metrics.latency_p99_ms),
+        ("memory_usage_mb", metrics.memory_usage_mb),
+        ("cpu_usage_percent", metrics.cpu_usage_percent),
+        ("enrichment_shed", metrics.enrichment_shed as u8 as f64),
+        ("tracked_pools", metrics.tracked_pools as f64),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value))
+    .collect();
+
+    for (name, summary) in &metrics.stage_latency {
+        gauges.push((format!("{}_count", name), summary.count as f64));
+        gauges.push((format!("{}_p50", name), summary.p50_ms));
+        gauges.push((format!("{}_p95", name), summary.p95_ms));
+        gauges.push((format!("{}_p99", name), summary.p99_ms));
+    }
+    gauges
+}
+
+/// StatsD exporter sending gauges over UDP
+pub struct StatsdExporter {
+    socket: UdpSocket,
+    endpoint: String,
+    prefix: String,
+}
+
+impl StatsdExporter {
+    /// Create an exporter sending to `endpoint` (`host:port`)
+    pub fn new(endpoint: String, prefix: &str) -> Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+            .map_err(|e| {
+                DAppError::Network(NetworkError::Http(format!(
+                    "Failed to open StatsD socket: {}",
+                    e
+                )))
+            })?;
+
+        Ok(Self {
+            socket,
+            endpoint,
+            prefix: prefix.to_string(),
+        })
+    }
+
+    fn payload(&self, metrics: &Metrics) -> String {
+        gauges(metrics)
+            .iter()
+            .map(|(name, value)| format!("{}.{}:{}|g", self.prefix, name, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[async_trait]
+impl MetricsExporter for StatsdExporter {
+    async fn push(&self, metrics: &Metrics) -> Result<()> {
+        self.socket
+            .send_to(self.payload(metrics).as_bytes(), &self.endpoint)
+            .map_err(|e| {
+                DAppError::Network(NetworkError::Http(format!(
+                    "Failed to send metrics to StatsD at {}: {}",
+                    self.endpoint, e
+                )))
+            })?;
+        Ok(())
+    }
+}
+
+/// Exporter pushing the text exposition format to a Prometheus Pushgateway
+pub struct PrometheusPushExporter {
+    client: reqwest::Client,
+    url: String,
+    prefix: String,
+}
+
+impl PrometheusPushExporter {
+    /// Create an exporter pushing to the Pushgateway at `endpoint`
+    pub fn new(endpoint: String, prefix: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            url: format!("{}/metrics/job/{}", endpoint.trim_end_matches('/'), prefix),
+            prefix: prefix.to_string(),
+        }
+    }
+
+    fn payload(&self, metrics: &Metrics) -> String {
+        gauges(metrics)
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "# TYPE {prefix}_{name} gauge\n{prefix}_{name} {value}\n",
+                    prefix = self.prefix
+                )
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl MetricsExporter for PrometheusPushExporter {
+    async fn push(&self, metrics: &Metrics) -> Result<()> {
+        let response = self
+            .client
+            .put(&self.url)
+            .body(self.payload(metrics))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(DAppError::Network(NetworkError::Http(format!(
+                "Pushgateway returned {}",
+                response.status()
+            ))));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bound of the first bucket
+const MIN_MS: f64 = 0.1;
+/// Buckets per doubling of latency; percentiles are accurate to about ±9%
+const BUCKETS_PER_DOUBLING: f64 = 4.0;
+/// Covers 0.1ms to roughly 90s; slower samples land in the last bucket
+const BUCKETS: usize = 80;
+
+/// Request latencies in fixed, exponentially sized buckets
+///
+/// Recording is a single atomic increment, so subgraph queries and Redis
+/// publishes can share one histogram without locking. Percentiles are the
+/// geometric middle of the bucket holding the requested rank, over every
+/// sample since startup.
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    buckets: [AtomicU64; BUCKETS],
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Record one round trip
+    pub fn record(&self, elapsed: Duration) {
+        self.buckets[bucket_index(elapsed.as_secs_f64() * 1000.0)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Samples recorded
+    #[allow(dead_code)]
+    pub fn count(&self) -> u64 {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    /// Latency in ms below which `p` percent of samples fall (0 without samples)
+    pub fn percentile(&self, p: f64) -> f64 {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let rank = ((p / 100.0) * total as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return bucket_midpoint(index);
+            }
+        }
+        bucket_midpoint(BUCKETS - 1)
+    }
+}
+
+fn bucket_upper_bound(index: usize) -> f64 {
+    MIN_MS * 2f64.powf(index as f64 / BUCKETS_PER_DOUBLING)
+}
+
+fn bucket_midpoint(index: usize) -> f64 {
+    if index == 0 {
+        return MIN_MS / 2.0;
+    }
+    (bucket_upper_bound(index - 1) * bucket_upper_bound(index)).sqrt()
+}
+
+fn bucket_index(ms: f64) -> usize {
+    if ms <= MIN_MS {
+        return 0;
+    }
+    let index = ((ms / MIN_MS).log2() * BUCKETS_PER_DOUBLING).ceil() as usize;
+    index.min(BUCKETS - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f64, expected: f64) {
+        let error = (actual - expected).abs() / expected;
+        assert!(error < 0.1, "{} is not within 10% of {}", actual, expected);
+    }
+
+    #[test]
+    fn test_percentiles_of_known_distribution() {
+        let histogram = LatencyHistogram::default();
+        // 1..=1000ms once each: p50 = 500, p95 = 950, p99 = 990
+        for ms in 1..=1000 {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(histogram.count(), 1000);
+        assert_near(histogram.percentile(50.0), 500.0);
+        assert_near(histogram.percentile(95.0), 950.0);
+        assert_near(histogram.percentile(99.0), 990.0);
+    }
+
+    #[test]
+    fn test_outliers_only_move_the_tail() {
+        let histogram = LatencyHistogram::default();
+        for _ in 0..97 {
+            histogram.record(Duration::from_millis(20));
+        }
+        for _ in 0..3 {
+            histogram.record(Duration::from_secs(5));
+        }
+
+        assert_near(histogram.percentile(50.0), 20.0);
+        assert_near(histogram.percentile(95.0), 20.0);
+        assert_near(histogram.percentile(99.0), 5000.0);
+    }
+
+    #[test]
+    fn test_empty_and_out_of_range_samples() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentile(99.0), 0.0);
+
+        histogram.record(Duration::ZERO);
+        histogram.record(Duration::from_secs(3600));
+        assert_eq!(histogram.percentile(50.0), MIN_MS / 2.0);
+        assert_eq!(histogram.percentile(100.0), bucket_midpoint(BUCKETS - 1));
+    }
+}
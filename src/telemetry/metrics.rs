@@ -1,29 +1,116 @@
 use crate::config::AppConfig;
-use std::sync::atomic::{AtomicU64, Ordering};
+use crate::error::{DAppError, NetworkError};
+use crate::telemetry::exporter::{exporter_from_config, MetricsExporter, NoopExporter};
+use crate::telemetry::http::{
+    config_route, route_handler, serve, Handler, HttpRequest, HttpResponse,
+};
+use crate::telemetry::latency::LatencyHistogram;
+use crate::telemetry::pool_metrics::PoolCounters;
+use crate::telemetry::process_stats::ProcessStats;
+use crate::telemetry::source_health::SourceErrorRates;
+use crate::telemetry::stages::{PipelineStage, StageSummary, StageTimings};
+use futures::future::FutureExt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+/// Handler invoked with the process exit code once the error budget is exhausted
+pub type ExitHandler = Arc<dyn Fn(i32) + Send + Sync>;
+
+/// Returns the current resident memory of the process in MB, if available
+pub type MemoryProbe = Arc<dyn Fn() -> Option<f64> + Send + Sync>;
+
 /// Metrics collector for the application
+///
+/// Counters are shared between clones so that errors recorded by spawned
+/// collection tasks count towards the same totals.
 pub struct MetricsCollector {
     config: AppConfig,
-    events_processed: AtomicU64,
-    events_dropped: AtomicU64,
-    errors_total: AtomicU64,
+    events_processed: Arc<AtomicU64>,
+    events_dropped: Arc<AtomicU64>,
+    events_filtered: Arc<AtomicU64>,
+    errors_total: Arc<AtomicU64>,
+    /// Last `PUBSUB NUMSUB` count for the Redis channel
+    redis_subscribers: Arc<AtomicU64>,
+    error_budget_exhausted: Arc<AtomicBool>,
+    enrichment_shed: Arc<AtomicBool>,
+    pool_counters: Arc<PoolCounters>,
+    token_counters: Arc<PoolCounters>,
+    stage_timings: Arc<StageTimings>,
+    /// Round trips of subgraph queries and Redis publishes
+    request_latency: Arc<LatencyHistogram>,
+    source_errors: Arc<SourceErrorRates>,
+    exit_handler: ExitHandler,
+    exporter: Arc<dyn MetricsExporter>,
+    memory_probe: MemoryProbe,
+    /// Set when `monitoring.collect_system_metrics` is enabled
+    process_stats: Option<Arc<ProcessStats>>,
     start_time: Instant,
 }
 
 impl MetricsCollector {
     /// Create a new metrics collector
     pub fn new(config: AppConfig) -> Self {
+        let exporter = exporter_from_config(&config).unwrap_or_else(|e| {
+            error!("Metrics export disabled: {}", e);
+            Arc::new(NoopExporter)
+        });
+        let pool_counters = Arc::new(PoolCounters::new(config.monitoring.max_tracked_pools));
+        let token_counters = Arc::new(PoolCounters::new(config.monitoring.max_tracked_tokens));
+        let process_stats = config
+            .monitoring
+            .collect_system_metrics
+            .then(|| Arc::new(ProcessStats::new()));
+
         Self {
             config,
-            events_processed: AtomicU64::new(0),
-            events_dropped: AtomicU64::new(0),
-            errors_total: AtomicU64::new(0),
+            events_processed: Arc::new(AtomicU64::new(0)),
+            events_dropped: Arc::new(AtomicU64::new(0)),
+            events_filtered: Arc::new(AtomicU64::new(0)),
+            errors_total: Arc::new(AtomicU64::new(0)),
+            redis_subscribers: Arc::new(AtomicU64::new(0)),
+            error_budget_exhausted: Arc::new(AtomicBool::new(false)),
+            enrichment_shed: Arc::new(AtomicBool::new(false)),
+            pool_counters,
+            token_counters,
+            stage_timings: Arc::new(StageTimings::default()),
+            request_latency: Arc::new(LatencyHistogram::default()),
+            source_errors: Arc::new(SourceErrorRates::default()),
+            exit_handler: Arc::new(|code| std::process::exit(code)),
+            exporter,
+            memory_probe: Arc::new(process_rss_mb),
+            process_stats,
             start_time: Instant::now(),
         }
     }
 
+    /// Replace the handler called when `max_total_errors` is exceeded
+    ///
+    /// Defaults to `std::process::exit`; mainly useful for tests.
+    #[allow(dead_code)]
+    pub fn with_exit_handler(mut self, handler: ExitHandler) -> Self {
+        self.exit_handler = handler;
+        self
+    }
+
+    /// Replace how the process' resident memory is measured
+    #[allow(dead_code)]
+    pub fn with_memory_probe(mut self, probe: MemoryProbe) -> Self {
+        self.memory_probe = probe;
+        self
+    }
+
+    /// Replace the exporter metric snapshots are pushed to
+    #[allow(dead_code)]
+    pub fn with_exporter(mut self, exporter: Arc<dyn MetricsExporter>) -> Self {
+        self.exporter = exporter;
+        self
+    }
+
     /// Record processed events
     pub fn record_events_processed(&self, count: u64) {
         match count.cmp(&0) {
@@ -74,12 +161,92 @@ impl MetricsCollector {
         }
     }
 
+    /// Record how many clients are subscribed to the Redis channel
+    pub fn record_redis_subscribers(&self, count: u64) {
+        self.redis_subscribers.store(count, Ordering::Relaxed);
+    }
+
+    /// Record events left out by the pool/token allowlists
+    pub fn record_events_filtered(&self, count: u64) {
+        if count > 0 {
+            self.events_filtered.fetch_add(count, Ordering::Relaxed);
+            debug!("Filtered out {} events not on the allowlists", count);
+        }
+    }
+
+    /// Record events published for `pool`
+    pub fn record_pool_events(&self, pool: &str, count: u64) {
+        self.pool_counters.record(pool, count);
+    }
+
+    /// Per-pool event counters
+    #[allow(dead_code)]
+    pub fn pool_counters(&self) -> &PoolCounters {
+        &self.pool_counters
+    }
+
+    /// Record events published involving the token at `address`
+    ///
+    /// Symbols are neither unique nor trusted, so counts are keyed by address
+    /// and `symbol` is only reported alongside it.
+    pub fn record_token_events(&self, address: &str, symbol: &str, count: u64) {
+        self.token_counters
+            .record_labeled(&address.to_lowercase(), symbol, count);
+    }
+
+    /// Per-token event counters, keyed by lowercase address
+    #[allow(dead_code)]
+    pub fn token_counters(&self) -> &PoolCounters {
+        &self.token_counters
+    }
+
+    /// Record how long a pipeline stage took in one collection cycle
+    pub fn record_stage(&self, stage: PipelineStage, elapsed: Duration) {
+        self.stage_timings.record(stage, elapsed);
+    }
+
+    /// Record the round trip of one subgraph query or Redis publish
+    pub fn record_request_latency(&self, elapsed: Duration) {
+        self.request_latency.record(elapsed);
+    }
+
+    /// Count and percentiles of a pipeline stage's timings
+    #[allow(dead_code)]
+    pub fn stage_summary(&self, stage: PipelineStage) -> StageSummary {
+        self.stage_timings.summary(stage)
+    }
+
+    /// Record whether enrichment is currently shed under load
+    pub fn set_enrichment_shed(&self, shed: bool) {
+        self.enrichment_shed.store(shed, Ordering::Relaxed);
+    }
+
+    /// Whether enrichment is currently shed under load
+    #[allow(dead_code)]
+    pub fn is_enrichment_shed(&self) -> bool {
+        self.enrichment_shed.load(Ordering::Relaxed)
+    }
+
+    /// Record the outcome of one request against a source (e.g. `uniswap_v2`)
+    pub fn record_source_result(&self, source: &str, failed: bool) {
+        self.source_errors.record(source, failed);
+    }
+
+    /// Sources above `monitoring.source_error_rate_threshold`, with their error rates
+    pub fn degraded_sources(&self) -> Vec<(String, f64)> {
+        let threshold = self.config.monitoring.source_error_rate_threshold;
+        if threshold <= 0.0 {
+            return Vec::new();
+        }
+        self.source_errors.degraded(threshold)
+    }
+
     /// Record errors
     pub fn record_error(&self) {
         let error_count = self.errors_total.fetch_add(1, Ordering::Relaxed) + 1;
 
         // Log error milestones
-        if error_count % 10 == 0 {
+        if error_count.is_multiple_of(10) {
             warn!("Error count milestone: {} errors recorded", error_count);
         }
 
@@ -92,12 +259,45 @@ impl MetricsCollector {
 
         // Log individual errors in debug mode
         debug!("Error recorded, total count: {}", error_count);
+
+        // Exit once the configured error budget is exceeded (0 = unlimited)
+        let max_total_errors = self.config.monitoring.max_total_errors;
+        if max_total_errors > 0 && error_count > max_total_errors {
+            self.exit_on_error_budget_exhausted(error_count, max_total_errors);
+        }
+    }
+
+    /// Log a fatal summary and exit the process with a non-zero code
+    fn exit_on_error_budget_exhausted(&self, error_count: u64, max_total_errors: u64) {
+        // Only the first caller past the threshold triggers the exit
+        if self.error_budget_exhausted.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        error!(
+            "Fatal: error budget exhausted ({} errors > max_total_errors {}), uptime: {}s, events processed: {}, events dropped: {}; exiting",
+            error_count,
+            max_total_errors,
+            self.start_time.elapsed().as_secs(),
+            self.events_processed.load(Ordering::Relaxed),
+            self.events_dropped.load(Ordering::Relaxed)
+        );
+
+        (self.exit_handler)(1);
     }
 
     /// Get current metrics
+    ///
+    /// Also samples the process' memory and CPU usage when
+    /// `monitoring.collect_system_metrics` is enabled; both are 0 otherwise.
     #[allow(dead_code)]
     pub fn get_metrics(&self) -> Metrics {
         let uptime = self.start_time.elapsed();
+        let usage = self
+            .process_stats
+            .as_ref()
+            .and_then(|stats| stats.sample())
+            .unwrap_or_default();
         let events_processed = self.events_processed.load(Ordering::Relaxed);
         let events_dropped = self.events_dropped.load(Ordering::Relaxed);
         let errors_total = self.errors_total.load(Ordering::Relaxed);
@@ -140,14 +340,22 @@ impl MetricsCollector {
 
         Metrics {
             events_processed_total: events_processed,
+            events_filtered_total: self.events_filtered.load(Ordering::Relaxed),
             events_processed_rate,
             errors_total,
             errors_rate,
-            latency_p50_ms: 0.0, // Would be calculated from actual measurements
-            latency_p95_ms: 0.0,
-            latency_p99_ms: 0.0,
-            memory_usage_mb: 0.0, // Would be calculated from system metrics
-            cpu_usage_percent: 0.0,
+            redis_subscribers: self.redis_subscribers.load(Ordering::Relaxed),
+            latency_p50_ms: self.request_latency.percentile(50.0),
+            latency_p95_ms: self.request_latency.percentile(95.0),
+            latency_p99_ms: self.request_latency.percentile(99.0),
+            memory_usage_mb: usage.memory_mb,
+            cpu_usage_percent: usage.cpu_percent,
+            enrichment_shed: self.enrichment_shed.load(Ordering::Relaxed),
+            tracked_pools: self.pool_counters.tracked_pools() as u64,
+            stage_latency: PipelineStage::ALL
+                .iter()
+                .map(|stage| (stage.metric_name(), self.stage_timings.summary(*stage)))
+                .collect(),
             timestamp: chrono::Utc::now(),
         }
     }
@@ -159,14 +367,8 @@ impl MetricsCollector {
             return Err("Monitoring not enabled".to_string());
         }
 
-        // Check if metrics collection has been running too long
-        let uptime = self.start_time.elapsed();
-        if uptime.as_secs() > 86400 {
-            // 24 hours
-            return Err("Metrics collection running too long, potential memory leak".to_string());
-        }
-
         // Check error rate threshold
+        let uptime = self.start_time.elapsed();
         let error_rate =
             self.errors_total.load(Ordering::Relaxed) as f64 / uptime.as_secs_f64().max(1.0);
         if error_rate > 0.5 {
@@ -179,7 +381,6 @@ impl MetricsCollector {
     }
 
     /// Start metrics collection loop
-    #[allow(dead_code)]
     pub async fn start_collection(&self) {
         // Only start if metrics are enabled
         if !self.config.monitoring.enable_metrics {
@@ -217,6 +418,10 @@ impl MetricsCollector {
                             "Monitoring config: {}",
                             metrics_collector.get_monitoring_config()
                         );
+
+                        if let Err(e) = metrics_collector.exporter.push(&metrics).await {
+                            warn!("Failed to push metrics: {}", e);
+                        }
                     }
                     Err(e) => {
                         consecutive_failures += 1;
@@ -235,9 +440,6 @@ impl MetricsCollector {
                         }
                     }
                 }
-
-                // Here you would typically send metrics to a monitoring system
-                // like Prometheus, InfluxDB, or CloudWatch
             }
         });
     }
@@ -265,23 +467,12 @@ impl MetricsCollector {
                     Ok(health_status) => {
                         consecutive_health_failures = 0; // Reset failure counter on success
 
+                        health_status.log();
                         if !health_status.is_healthy {
-                            warn!(
-                                "Health check failed at {}: {}",
-                                health_status.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
-                                health_status.message
-                            );
-
                             // Handle unhealthy state
                             metrics_collector
                                 .handle_unhealthy_state(&health_status)
                                 .await;
-                        } else {
-                            debug!(
-                                "Health check passed at {}: {}",
-                                health_status.timestamp.format("%Y:%M:%S UTC"),
-                                health_status.message
-                            );
                         }
                     }
                     Err(e) => {
@@ -310,32 +501,55 @@ impl MetricsCollector {
         // Simple health check - could be expanded with actual system checks
         let timestamp = chrono::Utc::now();
 
-        // Check if system has been running for too long (potential memory leak indicator)
         let uptime = self.start_time.elapsed();
-        let is_healthy = uptime.as_secs() < 86400; // 24 hours
 
-        // Check memory usage (simplified)
-        let memory_ok = uptime.as_secs() < 3600 || uptime.as_secs() > 86400; // Memory check after 1 hour or after 24 hours
+        // Check resident memory against the configured limit
+        let max_memory_mb = self.config.monitoring.max_memory_mb as f64;
+        let memory_mb = (self.memory_probe)();
+        let memory_ok = memory_mb.is_none_or(|memory_mb| memory_mb < max_memory_mb);
 
         // Check error rate
         let error_rate =
             self.errors_total.load(Ordering::Relaxed) as f64 / uptime.as_secs_f64().max(1.0);
         let error_rate_ok = error_rate < 0.1; // Less than 10% error rate
 
-        let final_health = is_healthy && memory_ok && error_rate_ok;
+        let final_health = memory_ok && error_rate_ok;
+
+        // Shedding degrades output but keeps core delivery healthy
+        let enrichment_note = if self.enrichment_shed.load(Ordering::Relaxed) {
+            ", enrichment shed"
+        } else {
+            ""
+        };
+
+        // So does a source that fails often but not always
+        let degraded_sources = self.degraded_sources();
+        let degraded_note = if degraded_sources.is_empty() {
+            String::new()
+        } else {
+            let sources: Vec<String> = degraded_sources
+                .iter()
+                .map(|(source, rate)| format!("{} ({:.1}% errors)", source, rate * 100.0))
+                .collect();
+            format!(", degraded sources: {}", sources.join(", "))
+        };
 
         let message = if final_health {
             format!(
-                "System healthy, uptime: {}s, error rate: {:.2}%",
+                "System healthy, uptime: {}s, error rate: {:.2}%{}{}",
                 uptime.as_secs(),
-                error_rate * 100.0
+                error_rate * 100.0,
+                enrichment_note,
+                degraded_note
             )
         } else {
             format!(
-                "System unhealthy, uptime: {}s, error rate: {:.2}%, memory: {}",
+                "System unhealthy, uptime: {}s, error rate: {:.2}%, memory: {} ({:.1}MB, limit {}MB)",
                 uptime.as_secs(),
                 error_rate * 100.0,
-                if memory_ok { "OK" } else { "WARNING" }
+                if memory_ok { "OK" } else { "WARNING" },
+                memory_mb.unwrap_or(0.0),
+                self.config.monitoring.max_memory_mb
             )
         };
 
@@ -343,24 +557,23 @@ impl MetricsCollector {
             is_healthy: final_health,
             message,
             timestamp,
+            version: self.config.application.version.clone(),
+            degraded_sources: degraded_sources
+                .into_iter()
+                .map(|(source, _)| source)
+                .collect(),
         }
     }
 
     /// Check system health with error handling
     async fn check_health_safely(&self) -> Result<HealthStatus, String> {
-        // Validate health check prerequisites
-        if !self.is_monitoring_enabled() {
-            return Err("Health monitoring not enabled".to_string());
-        }
-
-        // Check if health checks have been running too long
-        let uptime = self.start_time.elapsed();
-        if uptime.as_secs() > 86400 {
-            // 24 hours
-            return Err("Health monitoring running too long, potential memory leak".to_string());
+        // Metrics and structured logging are independent of health checks
+        if !self.config.monitoring.enable_health_checks {
+            return Err("Health checks not enabled".to_string());
         }
 
         // Check if error rate is too high for health checks
+        let uptime = self.start_time.elapsed();
         let error_rate =
             self.errors_total.load(Ordering::Relaxed) as f64 / uptime.as_secs_f64().max(1.0);
         if error_rate > 0.8 {
@@ -381,11 +594,7 @@ impl MetricsCollector {
         self.record_error();
 
         // Log detailed health information
-        error!(
-            "System health degraded at {}: {}",
-            health_status.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
-            health_status.message
-        );
+        error!("System health degraded: {}", health_status.log_line());
 
         // Here you could add additional actions like:
         // - Sending alerts
@@ -399,15 +608,158 @@ impl Clone for MetricsCollector {
     fn clone(&self) -> Self {
         Self {
             config: self.config.clone(),
-            events_processed: AtomicU64::new(self.events_processed.load(Ordering::Relaxed)),
-            events_dropped: AtomicU64::new(self.events_dropped.load(Ordering::Relaxed)),
-            errors_total: AtomicU64::new(self.errors_total.load(Ordering::Relaxed)),
+            events_processed: Arc::clone(&self.events_processed),
+            events_dropped: Arc::clone(&self.events_dropped),
+            events_filtered: Arc::clone(&self.events_filtered),
+            errors_total: Arc::clone(&self.errors_total),
+            redis_subscribers: Arc::clone(&self.redis_subscribers),
+            error_budget_exhausted: Arc::clone(&self.error_budget_exhausted),
+            enrichment_shed: Arc::clone(&self.enrichment_shed),
+            pool_counters: Arc::clone(&self.pool_counters),
+            token_counters: Arc::clone(&self.token_counters),
+            stage_timings: Arc::clone(&self.stage_timings),
+            request_latency: Arc::clone(&self.request_latency),
+            source_errors: Arc::clone(&self.source_errors),
+            exit_handler: Arc::clone(&self.exit_handler),
+            exporter: Arc::clone(&self.exporter),
+            memory_probe: Arc::clone(&self.memory_probe),
+            process_stats: self.process_stats.clone(),
             start_time: self.start_time,
         }
     }
 }
 
 impl MetricsCollector {
+    /// Serve `/metrics` on `application.metrics_port` until `shutdown` changes
+    pub async fn serve_metrics(&self, shutdown: watch::Receiver<bool>) -> crate::error::Result<()> {
+        let address = format!("0.0.0.0:{}", self.config.application.metrics_port);
+        let listener = TcpListener::bind(&address).await.map_err(|e| {
+            DAppError::Network(NetworkError::Http(format!(
+                "Failed to bind metrics server to {}: {}",
+                address, e
+            )))
+        })?;
+        info!("Serving Prometheus metrics on {}", address);
+
+        self.spawn_metrics_server(listener, shutdown);
+        Ok(())
+    }
+
+    fn spawn_metrics_server(&self, listener: TcpListener, mut shutdown: watch::Receiver<bool>) {
+        let collector = self.clone();
+        let handler = route_handler(vec![Arc::new(move |request: &HttpRequest| {
+            (request.path == "/metrics").then(|| HttpResponse {
+                status: 200,
+                content_type: "text/plain; version=0.0.4",
+                body: collector.render_prometheus(),
+            })
+        })]);
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = serve(listener, handler) => {}
+                _ = shutdown.changed() => info!("Metrics server stopped"),
+            }
+        });
+    }
+
+    /// Serve `/health` (and `/config` if exposed) on `application.health_check_port`
+    ///
+    /// `/health` answers 200 with the `HealthStatus` as JSON while healthy and
+    /// 503 otherwise, including when the health check itself fails.
+    pub async fn serve_health(&self) -> crate::error::Result<Option<JoinHandle<()>>> {
+        let monitoring = &self.config.monitoring;
+        if !monitoring.enable_health_checks && !monitoring.expose_config {
+            return Ok(None);
+        }
+
+        let address = format!("0.0.0.0:{}", self.config.application.health_check_port);
+        let listener = TcpListener::bind(&address).await.map_err(|e| {
+            DAppError::Network(NetworkError::Http(format!(
+                "Failed to bind health server to {}: {}",
+                address, e
+            )))
+        })?;
+        info!("Health server listening on {}", address);
+
+        Ok(Some(self.spawn_health_server(listener)))
+    }
+
+    fn spawn_health_server(&self, listener: TcpListener) -> JoinHandle<()> {
+        let collector = self.clone();
+        let routes = route_handler(vec![config_route(&self.config)]);
+        let handler: Handler = Arc::new(move |request: HttpRequest| {
+            let collector = collector.clone();
+            let routes = routes.clone();
+            async move {
+                if request.path == "/health" && collector.config.monitoring.enable_health_checks {
+                    collector.health_response().await
+                } else {
+                    routes(request).await
+                }
+            }
+            .boxed()
+        });
+
+        tokio::spawn(serve(listener, handler))
+    }
+
+    async fn health_response(&self) -> HttpResponse {
+        match self.check_health_safely().await {
+            Ok(status) => {
+                let body = serde_json::to_value(&status).unwrap_or_default();
+                HttpResponse {
+                    status: if status.is_healthy { 200 } else { 503 },
+                    ..HttpResponse::json(&body)
+                }
+            }
+            Err(e) => HttpResponse {
+                status: 503,
+                ..HttpResponse::json(&serde_json::json!({ "is_healthy": false, "message": e }))
+            },
+        }
+    }
+
+    /// Counters and gauges in the Prometheus text exposition format
+    ///
+    /// Per-pool and per-token event counts follow as `pool_events_total` and
+    /// `token_events_total`, labelled `pool=` and `token=` (the address) plus
+    /// `symbol=`.
+    pub fn render_prometheus(&self) -> String {
+        let prefix = &self.config.monitoring.metrics_prefix;
+        let mut rendered: String = [
+            ("events_processed_total", "counter", &self.events_processed),
+            ("events_dropped", "counter", &self.events_dropped),
+            ("events_filtered_total", "counter", &self.events_filtered),
+            ("errors_total", "counter", &self.errors_total),
+            ("redis_subscribers", "gauge", &self.redis_subscribers),
+        ]
+        .iter()
+        .map(|(name, kind, value)| {
+            format!(
+                "# TYPE {prefix}_{name} {kind}\n{prefix}_{name} {value}\n",
+                value = value.load(Ordering::Relaxed)
+            )
+        })
+        .collect();
+
+        rendered += &labeled_counter(
+            prefix,
+            "pool_events_total",
+            "pool",
+            None,
+            &self.pool_counters,
+        );
+        rendered += &labeled_counter(
+            prefix,
+            "token_events_total",
+            "token",
+            Some("symbol"),
+            &self.token_counters,
+        );
+        rendered
+    }
+
     /// Check if monitoring features are enabled
     pub fn is_monitoring_enabled(&self) -> bool {
         self.config.monitoring.enable_metrics
@@ -427,12 +779,81 @@ impl MetricsCollector {
     }
 }
 
+/// One Prometheus counter with a sample per key, in key order
+///
+/// Keys are reported as `key_label`, and their display labels, where they
+/// have one, as `display_label` when given.
+fn labeled_counter(
+    prefix: &str,
+    name: &str,
+    key_label: &str,
+    display_label: Option<&str>,
+    counters: &PoolCounters,
+) -> String {
+    let mut counts: Vec<(String, u64)> = counters.snapshot().into_iter().collect();
+    if counts.is_empty() {
+        return String::new();
+    }
+    counts.sort();
+    let display_values = counters.labels();
+
+    let mut rendered = format!("# TYPE {prefix}_{name} counter\n");
+    for (key, count) in counts {
+        let mut labels = format!("{key_label}=\"{}\"", escape_label_value(&key));
+        if let (Some(display_label), Some(value)) = (display_label, display_values.get(&key)) {
+            labels += &format!(",{display_label}=\"{}\"", escape_label_value(value));
+        }
+        rendered += &format!("{prefix}_{name}{{{labels}}} {count}\n");
+    }
+    rendered
+}
+
+/// Escape a Prometheus label value (backslash, double quote and newline)
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Resident memory of the current process in MB
+fn process_rss_mb() -> Option<f64> {
+    ProcessStats::new().sample().map(|usage| usage.memory_mb)
+}
+
 /// Health status structure
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct HealthStatus {
     pub is_healthy: bool,
     pub message: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Version of the relay reporting this status
+    pub version: String,
+    /// Sources whose recent error rate exceeds `monitoring.source_error_rate_threshold`
+    #[allow(dead_code)]
+    pub degraded_sources: Vec<String>,
+}
+
+impl HealthStatus {
+    /// Log line for this status, with an RFC3339 timestamp
+    pub fn log_line(&self) -> String {
+        format!(
+            "Health check {} at {}: {} (v{})",
+            if self.is_healthy { "passed" } else { "failed" },
+            self.timestamp.to_rfc3339(),
+            self.message,
+            self.version
+        )
+    }
+
+    /// Log this status: passes at debug level, failures as warnings
+    fn log(&self) {
+        if self.is_healthy {
+            debug!("{}", self.log_line());
+        } else {
+            warn!("{}", self.log_line());
+        }
+    }
 }
 
 /// Metrics data structure
@@ -441,12 +862,404 @@ pub struct HealthStatus {
 pub struct Metrics {
     pub events_processed_total: u64,
     pub events_processed_rate: f64,
+    /// Events left out by `subgraph.pool_allowlist`/`token_allowlist`
+    pub events_filtered_total: u64,
     pub errors_total: u64,
     pub errors_rate: f64,
+    /// Clients subscribed to the Redis channel at the last check
+    pub redis_subscribers: u64,
+    /// Percentiles of subgraph query and Redis publish round trips
     pub latency_p50_ms: f64,
     pub latency_p95_ms: f64,
     pub latency_p99_ms: f64,
     pub memory_usage_mb: f64,
     pub cpu_usage_percent: f64,
+    /// Enrichment is being skipped because publishing fell behind
+    pub enrichment_shed: bool,
+    /// Pools with individual event counters
+    pub tracked_pools: u64,
+    /// Per-stage latency histograms, keyed by metric name (e.g. `stage_fetch_ms`)
+    pub stage_latency: Vec<(&'static str, StageSummary)>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
+
+impl Default for Metrics {
+    /// All-zero snapshot with an empty histogram for every stage, as reported before any events
+    fn default() -> Self {
+        Self {
+            events_processed_total: 0,
+            events_processed_rate: 0.0,
+            events_filtered_total: 0,
+            errors_total: 0,
+            errors_rate: 0.0,
+            redis_subscribers: 0,
+            latency_p50_ms: 0.0,
+            latency_p95_ms: 0.0,
+            latency_p99_ms: 0.0,
+            memory_usage_mb: 0.0,
+            cpu_usage_percent: 0.0,
+            enrichment_shed: false,
+            tracked_pools: 0,
+            stage_latency: PipelineStage::ALL
+                .iter()
+                .map(|stage| (stage.metric_name(), StageSummary::default()))
+                .collect(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn collector_with_exit_recorder(
+        max_total_errors: u64,
+    ) -> (MetricsCollector, Arc<Mutex<Vec<i32>>>) {
+        let mut config = AppConfig::default();
+        config.monitoring.max_total_errors = max_total_errors;
+
+        let exit_codes = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&exit_codes);
+        let collector = MetricsCollector::new(config).with_exit_handler(Arc::new(move |code| {
+            recorder.lock().unwrap().push(code);
+        }));
+
+        (collector, exit_codes)
+    }
+
+    #[tokio::test]
+    async fn test_exit_when_error_budget_exceeded() {
+        let (metrics_collector, exit_codes) = collector_with_exit_recorder(3);
+
+        // Mock operation that always fails, recorded from a clone like the collection tasks do
+        let always_errors = || async { Err::<(), String>("subgraph unavailable".to_string()) };
+        let task_collector = metrics_collector.clone();
+        for _ in 0..5 {
+            if always_errors().await.is_err() {
+                task_collector.record_error();
+            }
+        }
+
+        assert_eq!(metrics_collector.get_metrics().errors_total, 5);
+        assert_eq!(*exit_codes.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_no_exit_at_threshold() {
+        let (metrics_collector, exit_codes) = collector_with_exit_recorder(3);
+
+        for _ in 0..3 {
+            metrics_collector.record_error();
+        }
+
+        assert!(exit_codes.lock().unwrap().is_empty());
+    }
+
+    /// Exporter recording when each snapshot was pushed
+    #[derive(Default)]
+    struct RecordingExporter {
+        pushes: Mutex<Vec<tokio::time::Instant>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MetricsExporter for RecordingExporter {
+        async fn push(&self, _metrics: &Metrics) -> crate::error::Result<()> {
+            self.pushes
+                .lock()
+                .unwrap()
+                .push(tokio::time::Instant::now());
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_metrics_pushed_at_configured_interval() {
+        let mut config = AppConfig::default();
+        config.monitoring.metrics_interval_seconds = 10;
+        let exporter = Arc::new(RecordingExporter::default());
+        let metrics_collector = MetricsCollector::new(config).with_exporter(exporter.clone());
+
+        let started = tokio::time::Instant::now();
+        metrics_collector.start_collection().await;
+        tokio::time::sleep(Duration::from_secs(35)).await;
+
+        let offsets: Vec<u64> = exporter
+            .pushes
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|pushed| pushed.duration_since(started).as_secs())
+            .collect();
+        assert_eq!(offsets, vec![0, 10, 20, 30]);
+    }
+
+    fn collector_with_memory(max_memory_mb: u64, memory_mb: f64) -> MetricsCollector {
+        let mut config = AppConfig::default();
+        config.monitoring.max_memory_mb = max_memory_mb;
+        MetricsCollector::new(config).with_memory_probe(Arc::new(move || Some(memory_mb)))
+    }
+
+    #[tokio::test]
+    async fn test_healthy_under_memory_limit() {
+        let health = collector_with_memory(512, 200.0).check_health().await;
+        assert!(health.is_healthy, "{}", health.message);
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_configured_version() {
+        let mut collector = collector_with_memory(512, 200.0);
+        assert_eq!(
+            collector.check_health().await.version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        collector.config.application.version = "9.9.9".to_string();
+        assert_eq!(collector.check_health().await.version, "9.9.9");
+    }
+
+    #[tokio::test]
+    async fn test_source_degraded_above_error_rate_threshold() {
+        let mut collector = collector_with_memory(512, 200.0);
+        collector.config.monitoring.source_error_rate_threshold = 0.25;
+
+        // 25% errors is at the threshold, not above it
+        for attempt in 0..8 {
+            collector.record_source_result("uniswap_v2", attempt < 2);
+            collector.record_source_result("uniswap_v3", false);
+        }
+        let health = collector.check_health().await;
+        assert!(health.degraded_sources.is_empty(), "{}", health.message);
+
+        collector.record_source_result("uniswap_v2", true);
+        collector.record_source_result("uniswap_v2", false);
+        let health = collector.check_health().await;
+        assert!(health.is_healthy);
+        assert_eq!(health.degraded_sources, vec!["uniswap_v2"]);
+        assert!(health
+            .message
+            .contains("degraded sources: uniswap_v2 (30.0% errors)"));
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_over_memory_limit() {
+        let health = collector_with_memory(512, 600.0).check_health().await;
+        assert!(!health.is_healthy);
+        assert!(health.message.contains("memory: WARNING"));
+    }
+
+    #[test]
+    fn test_health_log_timestamp_round_trips() {
+        let timestamp = chrono::Utc::now();
+        for is_healthy in [true, false] {
+            let status = HealthStatus {
+                is_healthy,
+                message: "System healthy, uptime: 5s".to_string(),
+                timestamp,
+                version: "0.1.0".to_string(),
+                degraded_sources: Vec::new(),
+            };
+
+            let line = status.log_line();
+            let logged = line
+                .split_once(" at ")
+                .and_then(|(_, rest)| rest.split_once(": "))
+                .map(|(logged, _)| logged)
+                .unwrap();
+            let parsed = chrono::DateTime::parse_from_rfc3339(logged).unwrap();
+            assert_eq!(parsed, timestamp);
+        }
+    }
+
+    #[test]
+    fn test_process_rss_is_measured() {
+        assert!(process_rss_mb().is_some_and(|memory_mb| memory_mb > 0.0));
+    }
+
+    #[test]
+    fn test_system_metrics_follow_config_flag() {
+        let metrics = MetricsCollector::new(AppConfig::default()).get_metrics();
+        assert!(metrics.memory_usage_mb > 0.0);
+
+        let mut config = AppConfig::default();
+        config.monitoring.collect_system_metrics = false;
+        let metrics = MetricsCollector::new(config).get_metrics();
+        assert_eq!(metrics.memory_usage_mb, 0.0);
+        assert_eq!(metrics.cpu_usage_percent, 0.0);
+    }
+
+    #[test]
+    fn test_default_metrics_match_fresh_collector_shape() {
+        let fresh = MetricsCollector::new(AppConfig::default()).get_metrics();
+        let default = Metrics::default();
+
+        assert_eq!(default.events_processed_total, 0);
+        assert_eq!(default.errors_total, 0);
+        assert_eq!(default.latency_p50_ms, 0.0);
+        assert_eq!(default.stage_latency, fresh.stage_latency);
+    }
+
+    #[test]
+    fn test_latency_percentiles_from_recorded_round_trips() {
+        let metrics_collector = MetricsCollector::new(AppConfig::default());
+        for ms in 1..=100 {
+            metrics_collector.record_request_latency(Duration::from_millis(ms));
+        }
+
+        let metrics = metrics_collector.get_metrics();
+        assert!((45.0..=55.0).contains(&metrics.latency_p50_ms));
+        assert!((86.0..=104.0).contains(&metrics.latency_p95_ms));
+        assert!((90.0..=108.0).contains(&metrics.latency_p99_ms));
+        assert!(metrics.latency_p50_ms < metrics.latency_p95_ms);
+    }
+
+    #[test]
+    fn test_unlimited_errors_by_default() {
+        let (metrics_collector, exit_codes) = collector_with_exit_recorder(0);
+
+        for _ in 0..1500 {
+            metrics_collector.record_error();
+        }
+
+        assert!(exit_codes.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_renders_counters() {
+        let metrics_collector = MetricsCollector::new(AppConfig::default());
+        metrics_collector.record_events_processed(7);
+        metrics_collector.record_events_dropped(2);
+        metrics_collector.record_error();
+        metrics_collector.record_redis_subscribers(3);
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        metrics_collector.spawn_metrics_server(listener, shutdown_rx);
+
+        let body = reqwest::get(format!("http://{}/metrics", address))
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert!(body.contains("uniswap_relay_events_processed_total 7\n"));
+        assert!(body.contains("uniswap_relay_events_dropped 2\n"));
+        assert!(body.contains("uniswap_relay_errors_total 1\n"));
+        assert!(body.contains("# TYPE uniswap_relay_errors_total counter"));
+        assert!(body.contains("uniswap_relay_redis_subscribers 3\n"));
+        assert!(body.contains("# TYPE uniswap_relay_redis_subscribers gauge"));
+
+        shutdown_tx.send(true).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(reqwest::get(format!("http://{}/metrics", address))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_pool_and_token_counters_rendered_with_labels() {
+        let metrics_collector = MetricsCollector::new(AppConfig::default());
+        metrics_collector.record_pool_events("0xpool", 2);
+        metrics_collector.record_pool_events("0xpool", 1);
+        metrics_collector.record_token_events("0xC02a", "WETH", 3);
+        metrics_collector.record_token_events("0xa0b8", "USDC", 1);
+        metrics_collector.record_token_events("0xbad", "a\"b", 1);
+
+        assert_eq!(metrics_collector.pool_counters().events_for("0xpool"), 3);
+        assert_eq!(metrics_collector.token_counters().events_for("0xc02a"), 3);
+
+        let body = metrics_collector.render_prometheus();
+        assert!(body.contains("# TYPE uniswap_relay_pool_events_total counter\n"));
+        assert!(body.contains("uniswap_relay_pool_events_total{pool=\"0xpool\"} 3\n"));
+        assert!(body.contains(
+            "uniswap_relay_token_events_total{token=\"0xa0b8\",symbol=\"USDC\"} 1\n\
+             uniswap_relay_token_events_total{token=\"0xbad\",symbol=\"a\\\"b\"} 1\n\
+             uniswap_relay_token_events_total{token=\"0xc02a\",symbol=\"WETH\"} 3\n"
+        ));
+    }
+
+    #[test]
+    fn test_tokens_sharing_a_symbol_counted_separately() {
+        let metrics_collector = MetricsCollector::new(AppConfig::default());
+        metrics_collector.record_token_events("0xa0b8", "USDC", 2);
+        metrics_collector.record_token_events("0xfake", "USDC", 1);
+
+        let counters = metrics_collector.token_counters();
+        assert_eq!(counters.events_for("0xa0b8"), 2);
+        assert_eq!(counters.events_for("0xfake"), 1);
+        assert!(metrics_collector
+            .render_prometheus()
+            .contains("uniswap_relay_token_events_total{token=\"0xfake\",symbol=\"USDC\"} 1\n"));
+    }
+
+    #[test]
+    fn test_tokens_beyond_cap_counted_as_other() {
+        let mut config = AppConfig::default();
+        config.monitoring.max_tracked_tokens = 2;
+        let metrics_collector = MetricsCollector::new(config);
+
+        for (address, symbol) in [
+            ("0xweth2", "WETH"),
+            ("0xusdc", "USDC"),
+            ("0xdai", "DAI"),
+            ("0xwbtc", "WBTC"),
+        ] {
+            metrics_collector.record_token_events(address, symbol, 1);
+            metrics_collector.record_token_events("0xweth", "WETH", 1);
+        }
+
+        let counters = metrics_collector.token_counters();
+        assert_eq!(counters.tracked_pools(), 2);
+        assert_eq!(counters.events_for("0xweth"), 4);
+        assert_eq!(counters.events_for("0xwbtc"), 1);
+        assert_eq!(counters.events_for("other"), 3);
+        assert!(metrics_collector
+            .render_prometheus()
+            .contains("uniswap_relay_token_events_total{token=\"other\"} 3\n"));
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_turns_unavailable_after_error_burst() {
+        let metrics_collector =
+            MetricsCollector::new(AppConfig::default()).with_memory_probe(Arc::new(|| Some(1.0)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        let server = metrics_collector.spawn_health_server(listener);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["is_healthy"], true);
+
+        for _ in 0..5 {
+            metrics_collector.record_error();
+        }
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), 503);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["is_healthy"], false);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint_healthy_with_metrics_disabled() {
+        let mut config = AppConfig::default();
+        config.monitoring.enable_metrics = false;
+        config.monitoring.enable_structured_logging = false;
+        let metrics_collector =
+            MetricsCollector::new(config).with_memory_probe(Arc::new(|| Some(1.0)));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        let server = metrics_collector.spawn_health_server(listener);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), 200);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["is_healthy"], true);
+
+        server.abort();
+    }
+}
@@ -1,3 +1,10 @@
+pub mod exporter;
+pub mod http;
+pub mod latency;
 pub mod metrics;
+pub mod pool_metrics;
+pub mod process_stats;
+pub mod source_health;
+pub mod stages;
 
 pub use metrics::MetricsCollector;
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Bucket that evicted pools' counts roll into
+pub const OTHER_POOLS: &str = "other";
+
+#[derive(Debug, Clone)]
+struct PoolCount {
+    events: u64,
+    last_active: u64,
+    /// Display label from the latest `record_labeled`
+    label: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct PoolCountersInner {
+    pools: HashMap<String, PoolCount>,
+    other: u64,
+    tick: u64,
+}
+
+/// Per-pool event counters bounded to `max_tracked_pools` entries
+///
+/// When a new pool would exceed the bound, the least recently active pool is
+/// evicted and its count added to the [`OTHER_POOLS`] bucket, so totals stay
+/// exact while memory is capped. A bound of 0 tracks every pool. Token
+/// counters use the same structure, keyed by address and labelled with the
+/// token's symbol.
+#[derive(Debug)]
+pub struct PoolCounters {
+    max_tracked_pools: usize,
+    inner: Mutex<PoolCountersInner>,
+}
+
+#[allow(dead_code)]
+impl PoolCounters {
+    /// Create counters tracking at most `max_tracked_pools` pools (0 = unbounded)
+    pub fn new(max_tracked_pools: usize) -> Self {
+        Self {
+            max_tracked_pools,
+            inner: Mutex::new(PoolCountersInner::default()),
+        }
+    }
+
+    /// Add `count` events for `pool`, evicting the least active pool if full
+    pub fn record(&self, pool: &str, count: u64) {
+        self.add(pool, None, count);
+    }
+
+    /// `record`, also setting the display label reported for `key`
+    pub fn record_labeled(&self, key: &str, label: &str, count: u64) {
+        self.add(key, Some(label), count);
+    }
+
+    fn add(&self, pool: &str, label: Option<&str>, count: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if let Some(entry) = inner.pools.get_mut(pool) {
+            entry.events += count;
+            entry.last_active = tick;
+            if let Some(label) = label {
+                if entry.label.as_deref() != Some(label) {
+                    entry.label = Some(label.to_string());
+                }
+            }
+            return;
+        }
+
+        if self.max_tracked_pools > 0 && inner.pools.len() >= self.max_tracked_pools {
+            let least_active = inner
+                .pools
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_active)
+                .map(|(pool, _)| pool.clone());
+            if let Some(evicted) = least_active.and_then(|pool| inner.pools.remove(&pool)) {
+                inner.other += evicted.events;
+            }
+        }
+
+        inner.pools.insert(
+            pool.to_string(),
+            PoolCount {
+                events: count,
+                last_active: tick,
+                label: label.map(str::to_string),
+            },
+        );
+    }
+
+    /// Number of pools currently tracked individually
+    pub fn tracked_pools(&self) -> usize {
+        self.inner.lock().unwrap().pools.len()
+    }
+
+    /// Events recorded for `pool`, or for evicted pools with [`OTHER_POOLS`]
+    pub fn events_for(&self, pool: &str) -> u64 {
+        let inner = self.inner.lock().unwrap();
+        if pool == OTHER_POOLS {
+            return inner.other;
+        }
+        inner.pools.get(pool).map_or(0, |entry| entry.events)
+    }
+
+    /// Snapshot of all counts, including the [`OTHER_POOLS`] bucket once non-empty
+    pub fn snapshot(&self) -> HashMap<String, u64> {
+        let inner = self.inner.lock().unwrap();
+        let mut counts: HashMap<String, u64> = inner
+            .pools
+            .iter()
+            .map(|(pool, entry)| (pool.clone(), entry.events))
+            .collect();
+        if inner.other > 0 {
+            counts.insert(OTHER_POOLS.to_string(), inner.other);
+        }
+        counts
+    }
+
+    /// Display labels of the tracked keys that have one
+    pub fn labels(&self) -> HashMap<String, String> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .pools
+            .iter()
+            .filter_map(|(key, entry)| Some((key.clone(), entry.label.clone()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_map_rolls_evicted_pools_into_other() {
+        let counters = PoolCounters::new(3);
+
+        for i in 0..10 {
+            counters.record(&format!("0xpool{}", i), 2);
+            // Keep one pool active so it is never the eviction candidate
+            counters.record("0xbusy", 1);
+            assert!(counters.tracked_pools() <= 3);
+        }
+
+        assert_eq!(counters.tracked_pools(), 3);
+        assert_eq!(counters.events_for("0xbusy"), 10);
+        assert_eq!(counters.events_for("0xpool9"), 2);
+        // Eight of the ten single-use pools were evicted
+        assert_eq!(counters.events_for(OTHER_POOLS), 16);
+
+        let total: u64 = counters.snapshot().values().sum();
+        assert_eq!(total, 10 * 2 + 10);
+    }
+
+    #[test]
+    fn test_zero_bound_tracks_every_pool() {
+        let counters = PoolCounters::new(0);
+        for i in 0..100 {
+            counters.record(&format!("0xpool{}", i), 1);
+        }
+        assert_eq!(counters.tracked_pools(), 100);
+        assert_eq!(counters.events_for(OTHER_POOLS), 0);
+    }
+}
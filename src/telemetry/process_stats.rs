@@ -0,0 +1,79 @@
+use std::sync::Mutex;
+use sysinfo::{get_current_pid, Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// Resource usage of the relay process at one sample
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProcessUsage {
+    /// Resident memory in MB
+    pub memory_mb: f64,
+    /// CPU used since the previous sample, in percent of one core
+    pub cpu_percent: f64,
+}
+
+/// Samples the current process' memory and CPU usage
+///
+/// Keeps one `System` across samples, since CPU usage is measured between
+/// consecutive refreshes; the first sample reports 0% CPU.
+pub struct ProcessStats {
+    pid: Option<Pid>,
+    system: Mutex<System>,
+}
+
+impl ProcessStats {
+    pub fn new() -> Self {
+        Self {
+            pid: get_current_pid().ok(),
+            system: Mutex::new(System::new()),
+        }
+    }
+
+    /// Refresh and return the process' usage, if it can be measured
+    pub fn sample(&self) -> Option<ProcessUsage> {
+        let pid = self.pid?;
+        let mut system = self.system.lock().unwrap();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            false,
+            ProcessRefreshKind::nothing().with_memory().with_cpu(),
+        );
+        system.process(pid).map(|process| ProcessUsage {
+            memory_mb: process.memory() as f64 / (1024.0 * 1024.0),
+            cpu_percent: f64::from(process.cpu_usage()),
+        })
+    }
+}
+
+impl Default for ProcessStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_usage_non_zero_after_allocating_and_working() {
+        let stats = ProcessStats::new();
+        stats.sample();
+
+        // Touch every page so the allocation is resident
+        let chunk = vec![1u8; 64 * 1024 * 1024];
+        let started = Instant::now();
+        let mut checksum = 0u64;
+        while started.elapsed() < Duration::from_millis(300) {
+            checksum = chunk
+                .iter()
+                .step_by(4096)
+                .fold(checksum, |sum, byte| sum.wrapping_add(u64::from(*byte)));
+        }
+        assert!(checksum > 0);
+
+        let usage = stats.sample().unwrap();
+        assert!(usage.memory_mb >= 64.0, "memory: {}MB", usage.memory_mb);
+        assert!(usage.cpu_percent > 0.0, "cpu: {}%", usage.cpu_percent);
+        drop(chunk);
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+/// Attempts per source that the error rate is computed over
+const WINDOW: usize = 100;
+
+/// Per-source error rates over a window of recent attempts
+///
+/// A source whose error rate exceeds the threshold is degraded: still
+/// polled and still counted healthy, but reported so operators can see a
+/// struggling subgraph before it fails outright.
+#[derive(Debug, Default)]
+pub struct SourceErrorRates {
+    outcomes: Mutex<BTreeMap<String, VecDeque<bool>>>,
+}
+
+impl SourceErrorRates {
+    /// Record whether one attempt against `source` failed
+    pub fn record(&self, source: &str, failed: bool) {
+        let mut outcomes = self.outcomes.lock().unwrap();
+        let window = outcomes.entry(source.to_string()).or_default();
+        if window.len() == WINDOW {
+            window.pop_front();
+        }
+        window.push_back(failed);
+    }
+
+    /// Fraction of recent attempts against `source` that failed
+    #[allow(dead_code)]
+    pub fn error_rate(&self, source: &str) -> Option<f64> {
+        let outcomes = self.outcomes.lock().unwrap();
+        outcomes.get(source).and_then(Self::rate)
+    }
+
+    /// Sources whose error rate exceeds `threshold`, with their rates, by name
+    pub fn degraded(&self, threshold: f64) -> Vec<(String, f64)> {
+        let outcomes = self.outcomes.lock().unwrap();
+        outcomes
+            .iter()
+            .filter_map(|(source, window)| {
+                Self::rate(window)
+                    .filter(|rate| *rate > threshold)
+                    .map(|rate| (source.clone(), rate))
+            })
+            .collect()
+    }
+
+    fn rate(window: &VecDeque<bool>) -> Option<f64> {
+        if window.is_empty() {
+            return None;
+        }
+        let failed = window.iter().filter(|failed| **failed).count();
+        Some(failed as f64 / window.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_keeps_recent_attempts() {
+        let rates = SourceErrorRates::default();
+        for _ in 0..WINDOW {
+            rates.record("uniswap_v2", true);
+        }
+        for _ in 0..WINDOW / 2 {
+            rates.record("uniswap_v2", false);
+        }
+
+        assert_eq!(rates.error_rate("uniswap_v2"), Some(0.5));
+        assert_eq!(rates.error_rate("uniswap_v3"), None);
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Samples kept per stage; percentiles cover this recent window
+const WINDOW: usize = 1024;
+
+/// Pipeline stage timed once per collection cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    /// Subgraph query round trip
+    Fetch,
+    /// Turning the response into `SwapEvent`s
+    Parse,
+    /// Decimal verification, direction stamping and transform scripts
+    Enrich,
+    /// Delivering events and block summaries to the sink
+    Publish,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 4] = [
+        PipelineStage::Fetch,
+        PipelineStage::Parse,
+        PipelineStage::Enrich,
+        PipelineStage::Publish,
+    ];
+
+    /// Metric name of the stage's histogram
+    pub fn metric_name(self) -> &'static str {
+        match self {
+            PipelineStage::Fetch => "stage_fetch_ms",
+            PipelineStage::Parse => "stage_parse_ms",
+            PipelineStage::Enrich => "stage_enrich_ms",
+            PipelineStage::Publish => "stage_publish_ms",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Summary of one stage histogram
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StageSummary {
+    /// Cycles recorded since startup
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+#[derive(Debug, Default)]
+struct StageSamples {
+    count: u64,
+    recent_ms: VecDeque<f64>,
+}
+
+/// Per-stage latency histograms over a window of recent cycles
+#[derive(Debug, Default)]
+pub struct StageTimings {
+    stages: [Mutex<StageSamples>; 4],
+}
+
+impl StageTimings {
+    /// Record how long `stage` took in one cycle
+    pub fn record(&self, stage: PipelineStage, elapsed: Duration) {
+        let mut samples = self.stages[stage.index()].lock().unwrap();
+        samples.count += 1;
+        if samples.recent_ms.len() == WINDOW {
+            samples.recent_ms.pop_front();
+        }
+        samples.recent_ms.push_back(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    /// Count and percentiles of `stage`
+    pub fn summary(&self, stage: PipelineStage) -> StageSummary {
+        let samples = self.stages[stage.index()].lock().unwrap();
+        let mut sorted: Vec<f64> = samples.recent_ms.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+
+        let percentile = |p: f64| {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            let rank = ((p / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+            sorted[rank]
+        };
+
+        StageSummary {
+            count: samples.count,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_over_recorded_samples() {
+        let timings = StageTimings::default();
+        for ms in 1..=100 {
+            timings.record(PipelineStage::Fetch, Duration::from_millis(ms));
+        }
+
+        let fetch = timings.summary(PipelineStage::Fetch);
+        assert_eq!(fetch.count, 100);
+        assert!((fetch.p50_ms - 51.0).abs() < 1e-9);
+        assert!((fetch.p99_ms - 99.0).abs() < 1e-9);
+        assert_eq!(
+            timings.summary(PipelineStage::Publish),
+            StageSummary::default()
+        );
+    }
+}
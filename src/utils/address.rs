@@ -0,0 +1,63 @@
+use sha3::{Digest, Keccak256};
+
+/// Validate an Ethereum address and return its EIP-55 checksummed form
+///
+/// Accepts any casing; the address must be `0x` followed by 40 hex digits.
+pub fn normalize_address(addr: &str) -> Result<String, String> {
+    if addr.len() != 42 {
+        return Err(format!(
+            "address {} must be 42 characters, got {}",
+            addr,
+            addr.len()
+        ));
+    }
+    let Some(hex_digits) = addr.strip_prefix("0x") else {
+        return Err(format!("address {} doesn't start with 0x", addr));
+    };
+    if !hex_digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("address {} is not hexadecimal", addr));
+    }
+
+    let lower = hex_digits.to_ascii_lowercase();
+    let hash = Keccak256::digest(lower.as_bytes());
+    let mut checksummed = String::with_capacity(42);
+    checksummed.push_str("0x");
+    for (i, c) in lower.chars().enumerate() {
+        // Uppercase a letter when the matching nibble of the hash is >= 8
+        let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+        if nibble >= 8 {
+            checksummed.push(c.to_ascii_uppercase());
+        } else {
+            checksummed.push(c);
+        }
+    }
+    Ok(checksummed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksums_lowercase_address() {
+        assert_eq!(
+            normalize_address("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").unwrap(),
+            "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+        );
+        assert_eq!(
+            normalize_address("0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED").unwrap(),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+    }
+
+    #[test]
+    fn test_rejects_malformed_addresses() {
+        assert!(
+            normalize_address("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc")
+                .unwrap_err()
+                .contains("42 characters")
+        );
+        assert!(normalize_address("0xg02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").is_err());
+        assert!(normalize_address("00c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::time::Duration;
 
 /// Exponential backoff strategy for retries
@@ -7,6 +9,9 @@ pub struct ExponentialBackoff {
     _multiplier: f64,
     _max_attempts: u32,
     _current_attempt: u32,
+    /// Delays are randomized by up to this fraction either way (0 = exact)
+    _jitter: f64,
+    _rng: Option<StdRng>,
 }
 
 impl ExponentialBackoff {
@@ -23,9 +28,30 @@ impl ExponentialBackoff {
             _multiplier: multiplier,
             _max_attempts: max_attempts,
             _current_attempt: 0,
+            _jitter: 0.0,
+            _rng: None,
         }
     }
 
+    /// Randomize each delay by up to ±`fraction` of itself
+    ///
+    /// Spreads out retries from many callers that failed at the same moment,
+    /// so they don't all hit the recovering endpoint together. Jittered delays
+    /// never exceed the max delay.
+    #[allow(dead_code)]
+    pub fn with_jitter(mut self, fraction: f64) -> Self {
+        self._jitter = fraction.clamp(0.0, 1.0);
+        self._rng.get_or_insert_with(StdRng::from_entropy);
+        self
+    }
+
+    /// Draw jitter from an RNG seeded with `seed`, for reproducible delays
+    #[allow(dead_code)]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self._rng = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
     /// Get the next delay duration
     #[allow(dead_code)]
     pub fn next_delay(&mut self) -> Option<Duration> {
@@ -48,7 +74,18 @@ impl ExponentialBackoff {
         };
 
         self._current_attempt += 1;
-        Some(delay)
+        Some(self.jittered(delay))
+    }
+
+    /// `delay` scaled by a random factor in `1 ± jitter`, capped at the max delay
+    fn jittered(&mut self, delay: Duration) -> Duration {
+        let jitter = self._jitter;
+        match &mut self._rng {
+            Some(rng) if jitter > 0.0 => delay
+                .mul_f64(1.0 + rng.gen_range(-jitter..=jitter))
+                .min(self._max_delay),
+            _ => delay,
+        }
     }
 
     /// Reset the backoff counter
@@ -147,4 +184,43 @@ mod tests {
         // Third: 9000ms, but capped at 2000ms
         assert_eq!(backoff.next_delay(), Some(Duration::from_millis(2000)));
     }
+
+    #[test]
+    fn test_jitter_stays_within_band() {
+        let exact: Vec<Duration> = std::iter::from_fn({
+            let mut backoff =
+                ExponentialBackoff::new(Duration::from_millis(100), Duration::from_secs(1), 2.0, 5);
+            move || backoff.next_delay()
+        })
+        .collect();
+        let jittered = |seed| -> Vec<Duration> {
+            let mut backoff =
+                ExponentialBackoff::new(Duration::from_millis(100), Duration::from_secs(1), 2.0, 5)
+                    .with_jitter(0.2)
+                    .with_seed(seed);
+            std::iter::from_fn(move || backoff.next_delay()).collect()
+        };
+
+        let delays = jittered(42);
+        assert_eq!(delays.len(), exact.len());
+        for (delay, exact) in delays.iter().zip(&exact) {
+            assert!(
+                *delay >= exact.mul_f64(0.8),
+                "{:?} below band of {:?}",
+                delay,
+                exact
+            );
+            assert!(
+                *delay <= exact.mul_f64(1.2),
+                "{:?} above band of {:?}",
+                delay,
+                exact
+            );
+            assert!(*delay <= Duration::from_secs(1));
+        }
+        assert_ne!(delays, exact);
+
+        // The same seed gives the same delays
+        assert_eq!(jittered(42), delays);
+    }
 }
@@ -1 +1,7 @@
+pub mod address;
 pub mod backoff;
+pub mod clock;
+pub mod rate_limit;
+pub mod retry;
+pub mod sanitize;
+pub mod singleflight;
@@ -0,0 +1,169 @@
+use crate::config::RateLimitingConfig;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Token bucket limiting how fast requests are sent
+///
+/// Up to `burst_size` requests go out back to back; after that tokens refill
+/// at `max_subgraph_requests_per_second`. On top of that, no more than
+/// `max_subgraph_requests_per_second * window_size_seconds` requests are
+/// admitted per window (0 = no window limit). Callers over the limit wait
+/// for their turn rather than failing, in the order they arrived.
+pub struct TokenBucket {
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    rate: f64,
+    capacity: f64,
+    window: Duration,
+    window_limit: u64,
+    tokens: f64,
+    updated: Instant,
+    window_start: Instant,
+    window_count: u64,
+}
+
+impl BucketState {
+    fn configure(&mut self, config: &RateLimitingConfig) {
+        let rate = config.max_subgraph_requests_per_second.max(1);
+        self.rate = f64::from(rate);
+        self.capacity = f64::from(config.burst_size.max(1));
+        self.tokens = self.tokens.min(self.capacity);
+        self.window = Duration::from_secs(config.window_size_seconds);
+        self.window_limit = u64::from(rate) * config.window_size_seconds;
+    }
+}
+
+impl TokenBucket {
+    /// Create a full bucket from `[rate_limiting]`
+    pub fn new(config: &RateLimitingConfig) -> Self {
+        let now = Instant::now();
+        let mut state = BucketState {
+            rate: 0.0,
+            capacity: 0.0,
+            window: Duration::ZERO,
+            window_limit: 0,
+            tokens: f64::MAX,
+            updated: now,
+            window_start: now,
+            window_count: 0,
+        };
+        state.configure(config);
+        Self {
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Apply new limits, keeping the tokens already accrued up to the new burst size
+    pub fn reconfigure(&self, config: &RateLimitingConfig) {
+        self.state.lock().unwrap().configure(config);
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        let ready_at = self.reserve(Instant::now());
+        tokio::time::sleep_until(ready_at).await;
+    }
+
+    /// Take a token, returning when it becomes usable
+    fn reserve(&self, now: Instant) -> Instant {
+        let state = &mut *self.state.lock().unwrap();
+
+        let refilled = now.saturating_duration_since(state.updated).as_secs_f64() * state.rate;
+        state.tokens = (state.tokens + refilled).min(state.capacity);
+        state.updated = now;
+
+        // Tokens go negative while callers queue for ones not refilled yet
+        state.tokens -= 1.0;
+        let mut ready_at = now;
+        if state.tokens < 0.0 {
+            ready_at += Duration::from_secs_f64(-state.tokens / state.rate);
+        }
+
+        if state.window_limit > 0 {
+            while ready_at >= state.window_start + state.window {
+                state.window_start += state.window;
+                state.window_count = 0;
+            }
+            if state.window_count >= state.window_limit {
+                state.window_start += state.window;
+                state.window_count = 0;
+                ready_at = state.window_start;
+            }
+            state.window_count += 1;
+        }
+
+        ready_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(per_second: u32, burst_size: u32, window_size_seconds: u64) -> RateLimitingConfig {
+        RateLimitingConfig {
+            max_subgraph_requests_per_second: per_second,
+            burst_size,
+            window_size_seconds,
+        }
+    }
+
+    /// Time at which each of `count` back-to-back acquisitions completed
+    async fn acquire_times(bucket: &TokenBucket, count: usize) -> Vec<Duration> {
+        let started = Instant::now();
+        let mut times = Vec::new();
+        for _ in 0..count {
+            bucket.acquire().await;
+            times.push(started.elapsed());
+        }
+        times
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_then_spaced_at_rate() {
+        let bucket = TokenBucket::new(&limits(5, 2, 0));
+
+        let times = acquire_times(&bucket, 5).await;
+
+        let millis: Vec<u128> = times.iter().map(Duration::as_millis).collect();
+        assert_eq!(millis, vec![0, 0, 200, 400, 600]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_time_refills_up_to_burst() {
+        let bucket = TokenBucket::new(&limits(10, 3, 0));
+        acquire_times(&bucket, 3).await;
+
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let times = acquire_times(&bucket, 4).await;
+
+        let millis: Vec<u128> = times.iter().map(Duration::as_millis).collect();
+        assert_eq!(millis, vec![0, 0, 0, 100]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_window_limit_defers_to_next_window() {
+        // 2 req/s over a 2s window admits 4 per window despite a larger burst
+        let bucket = TokenBucket::new(&limits(2, 10, 2));
+
+        let times = acquire_times(&bucket, 6).await;
+
+        let secs: Vec<u64> = times.iter().map(Duration::as_secs).collect();
+        assert_eq!(secs, vec![0, 0, 0, 0, 2, 2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reconfigure_changes_spacing() {
+        let bucket = TokenBucket::new(&limits(1, 1, 0));
+        bucket.acquire().await;
+
+        bucket.reconfigure(&limits(4, 1, 0));
+        let times = acquire_times(&bucket, 2).await;
+
+        let millis: Vec<u128> = times.iter().map(Duration::as_millis).collect();
+        assert_eq!(millis, vec![250, 500]);
+    }
+}
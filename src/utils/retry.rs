@@ -0,0 +1,146 @@
+use crate::error::{DAppError, Result};
+use crate::utils::backoff::ExponentialBackoff;
+use std::future::Future;
+use std::time::Duration;
+
+/// Run `op`, retrying each failure after the backoff's next delay
+///
+/// Returns the last error once the backoff is exhausted.
+#[allow(dead_code)]
+pub async fn retry_async<F, Fut, T>(backoff: ExponentialBackoff, op: F) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    retry_async_if(backoff, op, |_, _| true).await
+}
+
+/// Like `retry_async`, but gives up early when `should_retry` rejects a failure
+///
+/// `should_retry` is called with the error and the delay before the next
+/// attempt, only while the backoff still allows a retry.
+pub async fn retry_async_if<F, Fut, T, P>(
+    backoff: ExponentialBackoff,
+    op: F,
+    mut should_retry: P,
+) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    P: FnMut(&DAppError, Duration) -> bool,
+{
+    retry_async_with(backoff, op, |e, delay| {
+        should_retry(e, delay).then_some(delay)
+    })
+    .await
+}
+
+/// Like `retry_async_if`, but `next_delay` also chooses how long to wait
+///
+/// `next_delay` gets the error and the backoff's delay, and returns the
+/// delay to actually wait, or `None` to give up. The backoff still bounds
+/// the number of attempts.
+pub async fn retry_async_with<F, Fut, T, P>(
+    mut backoff: ExponentialBackoff,
+    op: F,
+    mut next_delay: P,
+) -> Result<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    P: FnMut(&DAppError, Duration) -> Option<Duration>,
+{
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) => match backoff.next_delay().and_then(|delay| next_delay(&e, delay)) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn backoff(max_retries: u32) -> ExponentialBackoff {
+        ExponentialBackoff::new(
+            Duration::from_millis(100),
+            Duration::from_secs(1),
+            2.0,
+            max_retries,
+        )
+    }
+
+    /// Operation failing its first `failures` calls
+    async fn flaky(calls: &AtomicU32, failures: u32) -> Result<u32> {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if call <= failures {
+            Err(DAppError::Internal(format!("failure {}", call)))
+        } else {
+            Ok(call)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_success_on_first_try() {
+        let calls = AtomicU32::new(0);
+        let started = tokio::time::Instant::now();
+
+        let result = retry_async(backoff(3), || flaky(&calls, 0)).await;
+
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_success_after_failures() {
+        let calls = AtomicU32::new(0);
+        let started = tokio::time::Instant::now();
+
+        let result = retry_async(backoff(3), || flaky(&calls, 2)).await;
+
+        assert_eq!(result.unwrap(), 3);
+        // Waited 100ms, then 200ms
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_exhaustion_returns_last_error() {
+        let calls = AtomicU32::new(0);
+
+        let result = retry_async(backoff(2), || flaky(&calls, u32::MAX)).await;
+
+        assert!(matches!(result, Err(DAppError::Internal(message)) if message == "failure 3"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rejected_failure_is_not_retried() {
+        let calls = AtomicU32::new(0);
+
+        let result = retry_async_if(backoff(3), || flaky(&calls, u32::MAX), |_, _| false).await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_chosen_delay_replaces_backoff() {
+        let calls = AtomicU32::new(0);
+        let started = tokio::time::Instant::now();
+
+        let result = retry_async_with(
+            backoff(3),
+            || flaky(&calls, 2),
+            |_, _| Some(Duration::from_secs(5)),
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,72 @@
+/// Remove characters that can corrupt logs or downstream parsers
+///
+/// Drops ANSI escape sequences (including their parameters), control
+/// characters such as newlines, and bidirectional overrides that can make
+/// a symbol render as something else. Returns `None` if `text` is already
+/// clean.
+pub fn strip_unsafe_chars(text: &str) -> Option<String> {
+    if !text.chars().any(is_unsafe) {
+        return None;
+    }
+
+    let mut clean = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences run from ESC [ to a final byte in '@'..='~'
+            if chars.peek() == Some(&'[') {
+                chars.next();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        if !is_unsafe(c) {
+            clean.push(c);
+        }
+    }
+    Some(clean)
+}
+
+/// Shorten `text` to at most `max_chars` characters, ending in an ellipsis
+///
+/// Returns `None` if `text` already fits or `max_chars` is 0 (unlimited).
+pub fn truncate_chars(text: &str, max_chars: usize) -> Option<String> {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return None;
+    }
+
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    Some(truncated)
+}
+
+fn is_unsafe(c: char) -> bool {
+    c.is_control() || matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_newlines_and_ansi_escapes() {
+        assert_eq!(
+            strip_unsafe_chars("Wrapped\n\u{1b}[31mEther\u{1b}[0m\r\t").as_deref(),
+            Some("WrappedEther")
+        );
+        assert_eq!(strip_unsafe_chars("US\u{202e}DC").as_deref(), Some("USDC"));
+        assert_eq!(strip_unsafe_chars("Uniswap \"V3\" 🦄"), None);
+    }
+
+    #[test]
+    fn test_truncate_chars_marks_cut_with_ellipsis() {
+        assert_eq!(truncate_chars("ABCDEFGH", 5).as_deref(), Some("ABCD…"));
+        assert_eq!(truncate_chars("🦄🦄🦄", 2).as_deref(), Some("🦄…"));
+        assert_eq!(truncate_chars("ABCDE", 5), None);
+        assert_eq!(truncate_chars("ABCDE", 0), None);
+    }
+}
@@ -0,0 +1,114 @@
+use dashmap::DashMap;
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Coalesces concurrent lookups for the same key into a single fetch
+///
+/// Callers asking for a key that is already being fetched await the in-flight
+/// future instead of starting a new one. The number of distinct fetches
+/// running at once is bounded by a semaphore.
+pub struct SingleFlight<K, V> {
+    in_flight: Arc<DashMap<K, Shared<BoxFuture<'static, V>>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+{
+    /// Create a new coalescer allowing `max_concurrent` fetches at once
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            in_flight: Arc::new(DashMap::new()),
+            permits: Arc::new(Semaphore::new(max_concurrent.max(1))),
+        }
+    }
+
+    /// Run `fetch` for `key`, or join the fetch already in flight for it
+    pub async fn run<F, Fut>(&self, key: K, fetch: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V> + Send + 'static,
+    {
+        let shared = self
+            .in_flight
+            .entry(key.clone())
+            .or_insert_with(|| {
+                let permits = self.permits.clone();
+                let fetch = fetch();
+                async move {
+                    let _permit = permits
+                        .acquire_owned()
+                        .await
+                        .expect("singleflight semaphore is never closed");
+                    fetch.await
+                }
+                .boxed()
+                .shared()
+            })
+            .clone();
+
+        let value = shared.clone().await;
+
+        // Only drop the entry if it still belongs to this fetch
+        self.in_flight
+            .remove_if(&key, |_, in_flight| in_flight.ptr_eq(&shared));
+
+        value
+    }
+
+    /// Number of keys currently being fetched
+    #[allow(dead_code)]
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+}
+
+impl<K, V> Clone for SingleFlight<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            in_flight: self.in_flight.clone(),
+            permits: self.permits.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_lookups_share_one_fetch() {
+        let lookups: SingleFlight<String, u8> = SingleFlight::new(4);
+        let fetches = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..100)
+            .map(|_| {
+                let lookups = lookups.clone();
+                let fetches = fetches.clone();
+                tokio::spawn(async move {
+                    lookups
+                        .run("pool".to_string(), || async move {
+                            fetches.fetch_add(1, Ordering::SeqCst);
+                            tokio::time::sleep(Duration::from_millis(50)).await;
+                            18
+                        })
+                        .await
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), 18);
+        }
+
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(lookups.in_flight(), 0);
+    }
+}
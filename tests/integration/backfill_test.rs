@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uniswap_relay::{
+    config::AppConfig,
+    model::Direction,
+    service::backfill::{BackfillRequest, BackfillSummary},
+    subgraph::transport::MockTransport,
+    EventSink, Result, SwapEvent, SwapEventCollector, UniswapVersion,
+};
+
+#[derive(Default)]
+struct RecordingSink {
+    events: Mutex<Vec<SwapEvent>>,
+}
+
+#[async_trait]
+impl EventSink for RecordingSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        self.events.lock().unwrap().extend_from_slice(events);
+        Ok(())
+    }
+}
+
+fn token(id: &str, symbol: &str, decimals: u8) -> serde_json::Value {
+    serde_json::json!({ "id": id, "symbol": symbol, "name": symbol, "decimals": decimals })
+}
+
+fn v2_swap(id: &str, timestamp: u64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "timestamp": timestamp.to_string(),
+        "pair": {
+            "id": "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc",
+            "token0": token("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "USDC", 6),
+            "token1": token("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", "WETH", 18)
+        },
+        "sender": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+        "amount0_in": "1000",
+        "amount1_out": "0.5",
+        "transaction": { "block_number": "18500000" }
+    })
+}
+
+fn v3_swap(id: &str, timestamp: u64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "timestamp": timestamp.to_string(),
+        "pool": {
+            "id": "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640",
+            "token0": token("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "USDC", 6),
+            "token1": token("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2", "WETH", 18)
+        },
+        "sender": "0xe592427a0aece92de3edee1f18e0157c05861564",
+        "recipient": "0x742d35cc6634c0532925a3b8d4c9db96c4b4d8b6",
+        "amount0": "2000",
+        "amount1": "-1",
+        "block": { "number": "18500001" }
+    })
+}
+
+fn swaps(swaps: Vec<serde_json::Value>) -> serde_json::Value {
+    serde_json::json!({ "data": { "swaps": swaps } })
+}
+
+fn collector(
+    config: AppConfig,
+    transport: Arc<MockTransport>,
+) -> (SwapEventCollector, Arc<RecordingSink>) {
+    let sink = Arc::new(RecordingSink::default());
+    let collector = SwapEventCollector::builder(config)
+        .with_transport(transport)
+        .with_sink(sink.clone())
+        .build()
+        .unwrap();
+    (collector, sink)
+}
+
+#[tokio::test]
+async fn test_backfill_publishes_range_and_counts_errors() {
+    let config = AppConfig::default();
+    let mut malformed = v3_swap("0xswap-bad", 1_700_000_020);
+    malformed.as_object_mut().unwrap().remove("amount0");
+    let transport = Arc::new(MockTransport::new().with_json(
+        &config.subgraph.uniswap_v3_url,
+        swaps(vec![
+            v3_swap("0xswap-1", 1_700_000_010),
+            malformed,
+            v3_swap("0xswap-2", 1_700_000_030),
+        ]),
+    ));
+    let (collector, sink) = collector(config.clone(), transport.clone());
+    let request = BackfillRequest::from_args(
+        [
+            "backfill",
+            "--from",
+            "1700000000",
+            "--to",
+            "1700003600",
+            "--version",
+            "v3",
+        ]
+        .into_iter()
+        .map(String::from),
+    )
+    .unwrap()
+    .unwrap();
+
+    let summary = collector.backfill(&request).await.unwrap();
+
+    assert_eq!(
+        summary,
+        BackfillSummary {
+            fetched: 3,
+            published: 2,
+            errors: 1,
+        }
+    );
+    let events = sink.events.lock().unwrap();
+    assert!(events
+        .iter()
+        .all(|event| event.version == UniswapVersion::V3));
+    assert!(events.iter().all(|event| event.block_number == 18_500_001));
+
+    // Only the V3 subgraph is queried, bounded by the requested range
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].0, config.subgraph.uniswap_v3_url);
+    assert_eq!(
+        requests[0].1["variables"]["where"],
+        serde_json::json!({ "timestamp_gt": "1700000000", "timestamp_lte": "1700003600" })
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_backfill_respects_rate_limit() {
+    let mut config = AppConfig::default();
+    config.rate_limiting.max_subgraph_requests_per_second = 1;
+    config.rate_limiting.burst_size = 1;
+    let transport = Arc::new(
+        MockTransport::new()
+            .with_json(
+                &config.subgraph.uniswap_v2_url,
+                swaps(vec![v2_swap("0xswap-v2", 1_700_000_010)]),
+            )
+            .with_json(
+                &config.subgraph.uniswap_v3_url,
+                swaps(vec![v3_swap("0xswap-v3", 1_700_000_010)]),
+            ),
+    );
+    let (collector, sink) = collector(config, transport.clone());
+    let request = BackfillRequest {
+        from: 1_700_000_000,
+        to: 1_700_003_600,
+        versions: vec![UniswapVersion::V2, UniswapVersion::V3],
+        pool: Some("0xpool".to_string()),
+    };
+
+    let started = tokio::time::Instant::now();
+    let summary = collector.backfill(&request).await.unwrap();
+
+    assert_eq!(summary.published, 2);
+    assert_eq!(sink.events.lock().unwrap().len(), 2);
+    assert_eq!(transport.requests().len(), 2);
+    // The second subgraph query waited for a token
+    assert!(started.elapsed() >= Duration::from_secs(1));
+}
+
+/// Sink that notes how many subgraph requests preceded each publish
+struct PageOrderSink {
+    transport: Arc<MockTransport>,
+    requests_at_publish: Mutex<Vec<usize>>,
+    events: Mutex<Vec<SwapEvent>>,
+}
+
+#[async_trait]
+impl EventSink for PageOrderSink {
+    async fn publish_batch(&self, events: &[SwapEvent]) -> Result<()> {
+        self.requests_at_publish
+            .lock()
+            .unwrap()
+            .push(self.transport.requests().len());
+        self.events.lock().unwrap().extend_from_slice(events);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_backfill_publishes_each_page_before_fetching_next() {
+    let mut config = AppConfig::default();
+    config.application.reference_token =
+        Some("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string());
+    // A full page of the client's 1000 rows, then a short one
+    let first_page = (0..1000)
+        .map(|i| v3_swap(&format!("0xswap-{:04}", i), 1_700_000_000 + i + 1))
+        .collect();
+    let transport = Arc::new(
+        MockTransport::new()
+            .with_json(&config.subgraph.uniswap_v3_url, swaps(first_page))
+            .with_json(
+                &config.subgraph.uniswap_v3_url,
+                swaps(vec![v3_swap("0xswap-last", 1_700_002_000)]),
+            ),
+    );
+    let sink = Arc::new(PageOrderSink {
+        transport: transport.clone(),
+        requests_at_publish: Mutex::new(Vec::new()),
+        events: Mutex::new(Vec::new()),
+    });
+    let collector = SwapEventCollector::builder(config)
+        .with_transport(transport.clone())
+        .with_sink(sink.clone())
+        .build()
+        .unwrap();
+    let request = BackfillRequest {
+        from: 1_700_000_000,
+        to: 1_700_003_600,
+        versions: vec![UniswapVersion::V3],
+        pool: None,
+    };
+
+    let summary = collector.backfill(&request).await.unwrap();
+
+    assert_eq!(summary.fetched, 1001);
+    assert_eq!(summary.published, 1001);
+    // The first page is published before the second is requested
+    let requests_at_publish = sink.requests_at_publish.lock().unwrap();
+    assert_eq!(requests_at_publish.first(), Some(&1));
+    assert_eq!(requests_at_publish.last(), Some(&2));
+
+    // Backfilled events are enriched like live ones
+    let events = sink.events.lock().unwrap();
+    assert!(events
+        .iter()
+        .all(|event| event.direction == Some(Direction::Buy)));
+}
@@ -55,7 +55,7 @@ fn create_test_swap_event() -> SwapEvent {
         .transaction_hash("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string())
         .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
         .token_in(TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,
@@ -174,7 +174,7 @@ async fn test_swap_event_builder_integration() -> Result<()> {
         .transaction_hash("0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890".to_string())
         .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
         .token_in(TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,
@@ -1,6 +1,6 @@
 use std::path::Path;
 use uniswap_relay::{
-    config::AppConfig,
+    config::{AppConfig, Environment},
     error::{DAppError, Result},
 };
 
@@ -22,66 +22,66 @@ impl TestConfigLoader {
     /// Load test configuration with environment overrides
     pub fn from_file_with_env<P: AsRef<Path>>(path: P, env: &str) -> Result<AppConfig> {
         let mut config = Self::from_file(path)?;
-        
+
         // Override environment
-        config.application.environment = env.to_string();
-        
+        config.application.environment = env.parse().map_err(DAppError::Config)?;
+
         // Override Redis URL for test environment
-        if env == "test" {
+        if config.application.environment == Environment::Test {
             config.redis.url = "redis://localhost:6379".to_string();
             config.redis.channel = "test_swaps".to_string();
         }
-        
+
         Ok(config)
     }
 
     /// Create a minimal test configuration
     pub fn minimal() -> AppConfig {
         let mut config = AppConfig::default();
-        
+
         // Set minimal test values
         config.application.name = "uniswap_relay_test".to_string();
         config.application.version = "0.1.0".to_string();
         config.application.log_level = "debug".to_string();
-        config.application.environment = "test".to_string();
-        
+        config.application.environment = Environment::Test;
+
         config.redis.url = "redis://localhost:6379".to_string();
         config.redis.channel = "test_swaps".to_string();
         config.redis.timeout_ms = 5000;
-        
+
         config.subgraph.polling_interval_seconds = 1;
         config.subgraph.request_timeout_ms = 5000;
-        
+
         config.rate_limiting.max_subgraph_requests_per_second = 10;
         config.rate_limiting.burst_size = 20;
-        
+
         config.retry.max_attempts = 3;
         config.retry.initial_delay_ms = 100;
-        
+
         config.monitoring.enable_metrics = true;
         config.monitoring.enable_health_checks = true;
         config.monitoring.metrics_interval_seconds = 1;
-        
+
         config
     }
 
     /// Create a production-like test configuration
     pub fn production_like() -> AppConfig {
         let mut config = Self::minimal();
-        
+
         // Override with production-like values
-        config.application.environment = "production".to_string();
+        config.application.environment = Environment::Production;
         config.application.log_level = "info".to_string();
-        
+
         config.rate_limiting.max_subgraph_requests_per_second = 100;
         config.rate_limiting.burst_size = 200;
-        
+
         config.retry.max_attempts = 5;
         config.retry.initial_delay_ms = 1000;
         config.retry.max_delay_ms = 30000;
-        
+
         config.monitoring.metrics_interval_seconds = 30;
-        
+
         config
     }
 
@@ -120,7 +120,7 @@ impl TestConfigLoader {
     /// Validate test configuration
     pub fn validate_test_config(config: &AppConfig) -> Result<()> {
         // Ensure test-specific requirements are met
-        if config.application.environment != "test" {
+        if config.application.environment != Environment::Test {
             return Err(DAppError::Config(
                 "Test configuration must have environment set to 'test'".to_string(),
             ));
@@ -152,7 +152,7 @@ mod tests {
     #[test]
     fn test_minimal_config() {
         let config = TestConfigLoader::minimal();
-        assert_eq!(config.application.environment, "test");
+        assert_eq!(config.application.environment, Environment::Test);
         assert_eq!(config.redis.channel, "test_swaps");
         assert!(config.monitoring.enable_metrics);
     }
@@ -160,14 +160,19 @@ mod tests {
     #[test]
     fn test_production_like_config() {
         let config = TestConfigLoader::production_like();
-        assert_eq!(config.application.environment, "production");
+        assert_eq!(config.application.environment, Environment::Production);
         assert_eq!(config.rate_limiting.max_subgraph_requests_per_second, 100);
     }
 
     #[test]
     fn test_scenario_configs() {
         let rate_limiting_config = TestConfigLoader::for_scenario("rate_limiting");
-        assert_eq!(rate_limiting_config.rate_limiting.max_subgraph_requests_per_second, 1);
+        assert_eq!(
+            rate_limiting_config
+                .rate_limiting
+                .max_subgraph_requests_per_second,
+            1
+        );
 
         let retry_config = TestConfigLoader::for_scenario("retry");
         assert_eq!(retry_config.retry.max_attempts, 1);
@@ -176,13 +181,84 @@ mod tests {
         assert_eq!(timeout_config.redis.timeout_ms, 100);
     }
 
+    #[test]
+    fn test_load_from_file() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/integration/test_config.toml"
+        );
+        let config = TestConfigLoader::from_file(path).unwrap();
+
+        assert_eq!(config.application.name, "uniswap_relay_test");
+        assert_eq!(config.application.version, "0.1.0");
+        assert_eq!(config.subgraph.request_timeout_ms, 10000);
+        assert_eq!(
+            config.subgraph.request_timeout(),
+            std::time::Duration::from_secs(10)
+        );
+        assert!(TestConfigLoader::validate_test_config(&config).is_ok());
+
+        let overridden = TestConfigLoader::from_file_with_env(path, "staging").unwrap();
+        assert_eq!(overridden.application.environment, Environment::Staging);
+        assert!(TestConfigLoader::from_file_with_env(path, "qa").is_err());
+    }
+
+    #[test]
+    fn test_unknown_environment_rejected_at_load() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/integration/test_config.toml"
+        );
+        let content = std::fs::read_to_string(path)
+            .unwrap()
+            .replace("environment = \"test\"", "environment = \"prodution\"");
+
+        let err = toml::from_str::<AppConfig>(&content).unwrap_err();
+        assert!(err.to_string().contains("Unknown environment 'prodution'"));
+    }
+
     #[test]
     fn test_config_validation() {
         let valid_config = TestConfigLoader::minimal();
         assert!(TestConfigLoader::validate_test_config(&valid_config).is_ok());
 
         let mut invalid_config = TestConfigLoader::minimal();
-        invalid_config.application.environment = "production".to_string();
+        invalid_config.application.environment = Environment::Production;
         assert!(TestConfigLoader::validate_test_config(&invalid_config).is_err());
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_application_identity_validated() {
+        let mut config = TestConfigLoader::minimal();
+        config.application.name = " ".to_string();
+        assert!(matches!(
+            config.validate_detailed(),
+            Err(DAppError::Validation(message)) if message.contains("name")
+        ));
+
+        let mut config = TestConfigLoader::minimal();
+        config.application.version = String::new();
+        assert!(matches!(
+            config.validate_detailed(),
+            Err(DAppError::Validation(message)) if message.contains("version")
+        ));
+    }
+
+    #[test]
+    fn test_request_timeout_ms_satisfies_timeout_check() {
+        let mut config = TestConfigLoader::minimal();
+        config.subgraph.timeout_seconds = 0;
+        config.subgraph.request_timeout_ms = 2500;
+        assert_eq!(
+            config.subgraph.request_timeout(),
+            std::time::Duration::from_millis(2500)
+        );
+        assert!(config.check_timeout_config().is_ok());
+
+        config.subgraph.request_timeout_ms = 0;
+        assert!(matches!(
+            config.check_timeout_config(),
+            Err(DAppError::Timeout(_))
+        ));
+    }
+}
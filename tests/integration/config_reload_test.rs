@@ -0,0 +1,34 @@
+use uniswap_relay::config::AppConfig;
+
+#[test]
+fn test_live_fields_applied_and_others_reported() {
+    let running = AppConfig::default();
+    let mut reloaded = running.clone();
+    reloaded.subgraph.polling_interval_seconds = 3;
+    reloaded.subgraph.query_page_size = 500;
+    reloaded.subgraph.pool_allowlist = vec!["0xpool".to_string()];
+    reloaded.rate_limiting.burst_size = 7;
+    reloaded.application.log_level = "debug".to_string();
+    reloaded.redis.url = "redis://elsewhere:6379".to_string();
+    reloaded.redis.channel = "other_channel".to_string();
+
+    let (updated, ignored) = running.with_live_updates(&reloaded);
+
+    assert_eq!(updated.subgraph.polling_interval_seconds, 3);
+    assert_eq!(updated.subgraph.query_page_size, 500);
+    assert_eq!(updated.subgraph.pool_allowlist, vec!["0xpool".to_string()]);
+    assert_eq!(updated.rate_limiting.burst_size, 7);
+    assert_eq!(updated.application.log_level, "debug");
+    assert_eq!(updated.redis.url, running.redis.url);
+    assert_eq!(updated.redis.channel, running.redis.channel);
+    assert_eq!(ignored, vec!["redis.channel", "redis.url"]);
+}
+
+#[test]
+fn test_unchanged_reload_ignores_nothing() {
+    let running = AppConfig::default();
+
+    let (_, ignored) = running.with_live_updates(&running.clone());
+
+    assert!(ignored.is_empty());
+}
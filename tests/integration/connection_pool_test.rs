@@ -0,0 +1,46 @@
+//! Publisher connection pooling against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{config::AppConfig, model::SwapEventBuilder, redis::RedisPublisher};
+
+#[tokio::test]
+async fn test_concurrent_publishes_spread_across_pool() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "connection-pool-test".to_string();
+    config.redis.connection_pool_size = 4;
+
+    let publisher = RedisPublisher::new(config.clone()).await.unwrap();
+    assert_eq!(publisher.pool_size(), 4);
+
+    let event = SwapEventBuilder::test_builder().unwrap();
+    let tasks: Vec<_> = (0..10)
+        .map(|_| {
+            let publisher = publisher.clone();
+            let event = event.clone();
+            tokio::spawn(async move { publisher.publish_batch(&[event]).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    // Every pooled connection carried at least one of the publishes
+    let client = redis::Client::open(config.redis.url.clone()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let clients: String = redis::cmd("CLIENT")
+        .arg("LIST")
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    let publishing = clients
+        .lines()
+        .filter(|line| line.contains("cmd=publish"))
+        .count();
+    assert_eq!(publishing, 4, "CLIENT LIST:\n{}", clients);
+}
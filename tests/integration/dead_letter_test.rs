@@ -0,0 +1,66 @@
+//! Dead-letter fallback against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::{AppConfig, RedisOutputMode},
+    model::SwapEventBuilder,
+    redis::RedisPublisher,
+};
+
+#[tokio::test]
+async fn test_failed_batch_dead_lettered_then_drained() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+    let path =
+        std::env::temp_dir().join(format!("relay-dead-letter-{}.ndjson", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "dead-letter-test".to_string();
+    config.redis.output_mode = RedisOutputMode::Stream { maxlen: None };
+    config.redis.retry_attempts = 1;
+    config.redis.retry_delay_ms = 10;
+    config.redis.dead_letter_path = Some(path.to_str().unwrap().to_string());
+
+    // XADD fails with WRONGTYPE while the stream key holds a string
+    let client = redis::Client::open(config.redis.url.clone()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    redis::cmd("SET")
+        .arg("dead-letter-test")
+        .arg("not a stream")
+        .query_async::<_, ()>(&mut conn)
+        .await
+        .unwrap();
+
+    let publisher = RedisPublisher::new(config.clone()).await.unwrap();
+    let events = vec![
+        SwapEventBuilder::test_builder().unwrap(),
+        SwapEventBuilder::test_builder().unwrap(),
+    ];
+    publisher.publish_batch(&events).await.unwrap();
+
+    let dead_lettered = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(dead_lettered.lines().count(), 2);
+    assert!(dead_lettered.contains(&events[0].id));
+
+    // Once Redis accepts the events again they are replayed and the file cleared
+    redis::cmd("DEL")
+        .arg("dead-letter-test")
+        .query_async::<_, ()>(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(publisher.drain_dead_letter().await.unwrap(), 2);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+    let length: u64 = redis::cmd("XLEN")
+        .arg("dead-letter-test")
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(length, 2);
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,29 @@
+//! Key deletion against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use redis::AsyncCommands;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{config::AppConfig, redis::RedisPublisher};
+
+#[tokio::test]
+async fn test_delete_key_removes_key() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+
+    let client = redis::Client::open(config.redis.url.clone()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let _: () = conn.set("stale-stream", "leftover").await.unwrap();
+
+    let publisher = RedisPublisher::new(config).await.unwrap();
+    publisher.delete_key("stale-stream").await.unwrap();
+
+    let exists: bool = conn.exists("stale-stream").await.unwrap();
+    assert!(!exists);
+
+    // Deleting it again is a no-op
+    publisher.delete_key("stale-stream").await.unwrap();
+}
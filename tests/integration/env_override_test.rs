@@ -0,0 +1,48 @@
+use uniswap_relay::config::AppConfig;
+
+/// Load with `vars` set under a prefix unique to the calling test
+fn load_with_vars(prefix: &str, vars: &[(&str, &str)]) -> AppConfig {
+    for (name, value) in vars {
+        std::env::set_var(format!("{}_{}", prefix, name), value);
+    }
+    let config = AppConfig::load_with_env_prefix(prefix);
+    for (name, _) in vars {
+        std::env::remove_var(format!("{}_{}", prefix, name));
+    }
+    config.unwrap()
+}
+
+#[test]
+fn test_nested_fields_overridden_from_env() {
+    let config = load_with_vars(
+        "RELAY_ENV_NESTED",
+        &[
+            ("SUBGRAPH__QUERY_PAGE_SIZE", "250"),
+            ("SUBGRAPH__POLLING_INTERVAL_SECONDS", "7"),
+            ("REDIS__URL", "redis://cache.internal:6380"),
+            ("REDIS__PER_VERSION_CHANNELS", "true"),
+            ("RETRY__MAX_ATTEMPTS", "9"),
+            ("RETRY__BACKOFF_MULTIPLIER", "1.5"),
+        ],
+    );
+
+    assert_eq!(config.subgraph.query_page_size, 250);
+    assert_eq!(config.subgraph.polling_interval_seconds, 7);
+    assert_eq!(config.redis.url, "redis://cache.internal:6380");
+    assert!(config.redis.per_version_channels);
+    assert_eq!(config.retry.max_attempts, 9);
+    assert_eq!(config.retry.backoff_multiplier, 1.5);
+}
+
+#[test]
+fn test_unset_fields_keep_file_values() {
+    let baseline = load_with_vars("RELAY_ENV_BASELINE", &[]);
+    let config = load_with_vars("RELAY_ENV_PARTIAL", &[("REDIS__CHANNEL", "swaps_override")]);
+
+    assert_eq!(config.redis.channel, "swaps_override");
+    assert_eq!(config.redis.url, baseline.redis.url);
+    assert_eq!(
+        config.subgraph.query_page_size,
+        baseline.subgraph.query_page_size
+    );
+}
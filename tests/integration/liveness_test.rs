@@ -0,0 +1,47 @@
+//! Liveness key tests against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use redis::AsyncCommands;
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{config::AppConfig, redis::RedisPublisher};
+
+#[tokio::test]
+async fn test_liveness_key_refreshes_and_expires() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.liveness_key_interval_seconds = 1;
+    config.application.instance_id = Some("liveness-test".to_string());
+    let key = config.liveness_key();
+    assert_eq!(key, "relay:alive:liveness-test");
+
+    let client = redis::Client::open(config.redis.url.clone()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+
+    let publisher = RedisPublisher::new(config).await.unwrap();
+    let refresher = publisher.start_liveness_refresher().unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let first: String = conn.get(&key).await.unwrap();
+    let ttl: i64 = conn.ttl(&key).await.unwrap();
+    assert!(
+        ttl > 1,
+        "TTL should exceed the refresh interval, got {}",
+        ttl
+    );
+
+    // The refresher rewrites the key with a new timestamp
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let second: String = conn.get(&key).await.unwrap();
+    assert_ne!(first, second);
+
+    // Once the refresher stops, the key expires on its own
+    refresher.abort();
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let exists: bool = conn.exists(&key).await.unwrap();
+    assert!(!exists);
+}
@@ -0,0 +1,51 @@
+//! Subscriber-count gating against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::AppConfig,
+    model::SwapEventBuilder,
+    redis::{subscriber::RedisSubscriber, RedisPublisher},
+};
+
+#[tokio::test]
+async fn test_publishing_gated_on_min_subscribers() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "min-subscribers-test".to_string();
+    config.redis.min_subscribers = 1;
+
+    let publisher = RedisPublisher::new(config.clone()).await.unwrap();
+    let event = SwapEventBuilder::test_builder().unwrap();
+
+    // Nobody listening: the batch is skipped, not an error
+    assert_eq!(publisher.get_subscriber_count().await.unwrap(), 0);
+    publisher
+        .publish_batch(std::slice::from_ref(&event))
+        .await
+        .unwrap();
+    assert_eq!(publisher.skipped_events(), 1);
+
+    let mut subscriber = RedisSubscriber::connect(&config.redis.url, &config.redis.channel)
+        .await
+        .unwrap();
+    assert_eq!(publisher.get_subscriber_count().await.unwrap(), 1);
+
+    publisher
+        .publish_batch(std::slice::from_ref(&event))
+        .await
+        .unwrap();
+    assert_eq!(publisher.skipped_events(), 1);
+
+    let received = tokio::time::timeout(Duration::from_secs(5), subscriber.next_event())
+        .await
+        .expect("subscriber should receive the event once it is listening")
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.id, event.id);
+}
@@ -2,6 +2,22 @@
 //!
 //! These tests verify the integration between components.
 
+pub mod backfill_test;
+pub mod config_loader;
+pub mod config_reload_test;
+pub mod connection_pool_test;
+pub mod dead_letter_test;
+pub mod delete_key_test;
+pub mod env_override_test;
+pub mod liveness_test;
+pub mod min_subscribers_test;
+pub mod per_version_channels_test;
+pub mod pipeline_test;
+pub mod redis_dedup_test;
+pub mod simple_test;
+pub mod stream_mode_test;
+pub mod sub_config_test;
+pub mod subscriber_count_test;
 pub mod working_test;
 
 /// Initialize test environment
@@ -0,0 +1,47 @@
+//! Per-version channels against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::AppConfig,
+    model::{SwapEventBuilder, UniswapVersion},
+    redis::{subscriber::RedisSubscriber, RedisPublisher},
+};
+
+#[tokio::test]
+async fn test_v3_channel_receives_only_v3_events() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "per-version-test".to_string();
+    config.redis.per_version_channels = true;
+
+    let mut subscriber = RedisSubscriber::connect(&config.redis.url, "per-version-test.v3")
+        .await
+        .unwrap();
+    let publisher = RedisPublisher::new(config).await.unwrap();
+
+    let mut v2 = SwapEventBuilder::test_builder().unwrap();
+    v2.version = UniswapVersion::V2;
+    let v3 = SwapEventBuilder::test_builder().unwrap();
+    publisher.publish_batch(&[v2, v3.clone()]).await.unwrap();
+
+    let received = tokio::time::timeout(Duration::from_secs(5), subscriber.next_event())
+        .await
+        .expect("the V3 event should arrive on the .v3 channel")
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.id, v3.id);
+    assert_eq!(received.version, UniswapVersion::V3);
+
+    // The V2 event went to .v2, so nothing else arrives here
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), subscriber.next_event())
+            .await
+            .is_err()
+    );
+}
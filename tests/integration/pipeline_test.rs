@@ -0,0 +1,121 @@
+//! End-to-end collect → publish → subscribe test with a mocked subgraph and real Redis
+#![cfg(feature = "testcontainers")]
+
+use chrono::{TimeZone, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::AppConfig,
+    redis::{subscriber::RedisSubscriber, RedisPublisher},
+    subgraph::transport::MockTransport,
+    utils::clock::MockClock,
+    SwapEventCollector, UniswapVersion,
+};
+
+fn v2_swap(id: &str, amount_in: &str, amount_out: &str) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "timestamp": "1700000000",
+        "pair": {
+            "id": "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc",
+            "token0": {
+                "id": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+                "symbol": "USDC",
+                "name": "USD Coin",
+                "decimals": 6
+            },
+            "token1": {
+                "id": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                "symbol": "WETH",
+                "name": "Wrapped Ether",
+                "decimals": 18
+            }
+        },
+        "sender": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d",
+        "amount0_in": amount_in,
+        "amount1_out": amount_out,
+        "to": "0x7a250d5630b4cf539739df2c5dacb4c659f2488d"
+    })
+}
+
+#[tokio::test]
+async fn test_collected_swaps_reach_redis_subscribers() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "pipeline-test".to_string();
+
+    let transport = Arc::new(
+        MockTransport::new()
+            .with_json(
+                &config.subgraph.uniswap_v2_url,
+                serde_json::json!({ "data": { "swaps": [
+                    v2_swap("0xswap-1", "1000", "0.5"),
+                    v2_swap("0xswap-2", "2500", "1.25"),
+                ] } }),
+            )
+            .with_json(
+                &config.subgraph.uniswap_v3_url,
+                serde_json::json!({ "data": { "swaps": [] } }),
+            ),
+    );
+    let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+    let mut subscriber = RedisSubscriber::connect(&config.redis.url, &config.redis.channel)
+        .await
+        .unwrap();
+    let publisher = Arc::new(RedisPublisher::new(config.clone()).await.unwrap());
+    let collector = SwapEventCollector::builder(config)
+        .with_transport(transport)
+        .with_sink(publisher)
+        .with_clock(Arc::new(MockClock::new(now)))
+        .build()
+        .unwrap();
+
+    collector.poll_once().await.unwrap();
+
+    let mut received = Vec::new();
+    for _ in 0..2 {
+        let event = tokio::time::timeout(Duration::from_secs(5), subscriber.next_event())
+            .await
+            .expect("subscriber should receive the published event")
+            .unwrap()
+            .unwrap();
+        received.push(event);
+    }
+
+    let normalized: Vec<_> = received
+        .iter()
+        .map(|event| {
+            (
+                event.id.as_str(),
+                event.amount_in.as_str(),
+                event.amount_out.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        normalized,
+        vec![("0xswap-1", "1000", "0.5"), ("0xswap-2", "2500", "1.25")]
+    );
+
+    for event in &received {
+        assert_eq!(event.version, UniswapVersion::V2);
+        assert_eq!(event.timestamp, now);
+        assert_eq!(
+            event.pool_address,
+            "0xb4e16d0168e52d35cacd2c6185b44281ec28c9dc"
+        );
+        assert_eq!(event.token_in.symbol, "USDC");
+        assert_eq!(event.token_in.decimals, 6);
+        assert_eq!(event.token_out.symbol, "WETH");
+        assert_eq!(
+            event.user_address,
+            "0x7a250d5630b4cf539739df2c5dacb4c659f2488d"
+        );
+    }
+}
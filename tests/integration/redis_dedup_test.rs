@@ -0,0 +1,61 @@
+//! Cross-instance `SET NX` dedup against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::AppConfig,
+    model::SwapEventBuilder,
+    redis::{subscriber::RedisSubscriber, RedisPublisher},
+};
+
+#[tokio::test]
+async fn test_same_event_published_once_across_publishers() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "redis-dedup-test".to_string();
+    config.redis.dedup_ttl_seconds = 60;
+
+    let mut subscriber = RedisSubscriber::connect(&config.redis.url, &config.redis.channel)
+        .await
+        .unwrap();
+    let event = SwapEventBuilder::test_builder().unwrap();
+
+    // Two instances, e.g. before and after a restart, see the same swap
+    let first = RedisPublisher::new(config.clone()).await.unwrap();
+    let second = RedisPublisher::new(config.clone()).await.unwrap();
+    first
+        .publish_batch(std::slice::from_ref(&event))
+        .await
+        .unwrap();
+    second
+        .publish_batch(std::slice::from_ref(&event))
+        .await
+        .unwrap();
+
+    let received = tokio::time::timeout(Duration::from_secs(5), subscriber.next_event())
+        .await
+        .expect("the first publish should be delivered")
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.id, event.id);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), subscriber.next_event())
+            .await
+            .is_err(),
+        "the duplicate should not be published"
+    );
+
+    let client = redis::Client::open(config.redis.url.as_str()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let ttl: i64 = redis::cmd("TTL")
+        .arg(format!("{}:seen:{}", config.redis.channel, event.id))
+        .query_async(&mut conn)
+        .await
+        .unwrap();
+    assert!(ttl > 0 && ttl <= 60, "ttl: {}", ttl);
+}
@@ -1,7 +1,7 @@
 use uniswap_relay::{
     config::AppConfig,
     error::Result,
-    model::{SwapEvent, SwapEventBuilder, TokenInfo, UniswapVersion},
+    model::{SwapEventBuilder, TokenInfo, UniswapVersion},
 };
 
 /// Simple test for SwapEventBuilder
@@ -9,10 +9,12 @@ use uniswap_relay::{
 fn test_swap_event_builder() -> Result<()> {
     let event = SwapEventBuilder::default()
         .version(UniswapVersion::V2)
-        .transaction_hash("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string())
+        .transaction_hash(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
+        )
         .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
         .token_in(TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,
@@ -36,7 +38,10 @@ fn test_swap_event_builder() -> Result<()> {
         .map_err(|e| uniswap_relay::error::DAppError::Internal(format!("Builder failed: {}", e)))?;
 
     assert_eq!(event.version, UniswapVersion::V2);
-    assert_eq!(event.pool_address, "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8");
+    assert_eq!(
+        event.pool_address,
+        "0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8"
+    );
     assert_eq!(event.token_in.symbol, "USDC");
     assert_eq!(event.token_out.symbol, "WETH");
 
@@ -47,23 +52,54 @@ fn test_swap_event_builder() -> Result<()> {
 #[test]
 fn test_config_validation() -> Result<()> {
     let config = AppConfig::default();
-    
+
     // Test basic validation
     let result = config.validate();
     assert!(result.is_ok());
-    
+
     // Test comprehensive validation
     let result = config.validate_comprehensive();
     assert!(result.is_ok());
-    
+
     Ok(())
 }
 
+/// Block lag checks need an RPC endpoint to read chain head from
+#[test]
+fn test_max_block_lag_requires_chain_head_rpc() {
+    let mut config = AppConfig::default();
+    config.subgraph.max_block_lag = Some(50);
+    // The decimals endpoint is not used for chain head
+    config.subgraph.decimals_rpc_url = Some("http://rpc.local".to_string());
+
+    assert_eq!(
+        config.validate().unwrap_err(),
+        "subgraph.max_block_lag requires subgraph.chain_head_rpc_url"
+    );
+
+    config.subgraph.chain_head_rpc_url = Some("http://rpc.local".to_string());
+    assert!(config.validate().is_ok());
+}
+
+/// Held-back swaps are only fetched again when polls resume from the cursor
+#[test]
+fn test_min_confirmations_requires_composite_cursor() {
+    let mut config = AppConfig::default();
+    config.subgraph.min_confirmations = 5;
+    assert!(config.validate().is_ok());
+
+    config.subgraph.composite_cursor = false;
+    assert_eq!(
+        config.validate().unwrap_err(),
+        "subgraph.min_confirmations requires subgraph.composite_cursor"
+    );
+}
+
 /// Simple test for environment detection
 #[test]
 fn test_environment_detection() {
     let config = AppConfig::default();
-    
+
     // Test environment methods
     assert!(!config.is_production());
     assert!(config.is_development());
@@ -73,39 +109,39 @@ fn test_environment_detection() {
 #[test]
 fn test_metrics_structure() {
     use uniswap_relay::telemetry::metrics::Metrics;
-    
+
     let metrics = Metrics::default();
-    
+
     // Test that metrics have expected fields
     assert_eq!(metrics.events_processed_total, 0);
     assert_eq!(metrics.errors_total, 0);
-    assert_eq!(metrics.latency_p50_ms, 0);
+    assert_eq!(metrics.latency_p50_ms, 0.0);
 }
 
 /// Simple test for error types
 #[test]
 fn test_error_types() {
     use uniswap_relay::error::{DAppError, EthereumError, SolanaError};
-    
+
     // Test Ethereum error creation
     let eth_error = EthereumError::EventParsing("test error".to_string());
     let dapp_error = DAppError::Ethereum(eth_error);
-    
+
     match dapp_error {
         DAppError::Ethereum(EthereumError::EventParsing(msg)) => {
             assert_eq!(msg, "test error");
         }
         _ => panic!("Expected Ethereum EventParsing error"),
     }
-    
+
     // Test Solana error creation
     let sol_error = SolanaError::Instruction("test instruction error".to_string());
     let dapp_error = DAppError::Solana(sol_error);
-    
+
     match dapp_error {
         DAppError::Solana(SolanaError::Instruction(msg)) => {
             assert_eq!(msg, "test instruction error");
         }
         _ => panic!("Expected Solana Instruction error"),
     }
-} 
\ No newline at end of file
+}
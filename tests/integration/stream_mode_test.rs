@@ -0,0 +1,47 @@
+//! Stream output mode tests against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use redis::Value;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::{AppConfig, RedisOutputMode},
+    model::SwapEventBuilder,
+    redis::RedisPublisher,
+};
+
+#[tokio::test]
+async fn test_stream_mode_appends_events_with_ids() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "swaps".to_string();
+    config.redis.output_mode = RedisOutputMode::Stream { maxlen: Some(1000) };
+
+    let event = SwapEventBuilder::test_builder().unwrap();
+    let publisher = RedisPublisher::new(config.clone()).await.unwrap();
+    publisher
+        .publish_batch(&[event.clone(), event.clone()])
+        .await
+        .unwrap();
+
+    let client = redis::Client::open(config.redis.url.clone()).unwrap();
+    let mut conn = client.get_multiplexed_async_connection().await.unwrap();
+    let entries: Vec<(String, Vec<(String, String)>)> = redis::cmd("XRANGE")
+        .arg("swaps")
+        .arg("-")
+        .arg("+")
+        .query_async::<_, Value>(&mut conn)
+        .await
+        .and_then(|value| redis::from_redis_value(&value))
+        .unwrap();
+
+    assert_eq!(entries.len(), 2);
+    let fields = &entries[0].1;
+    assert!(fields.contains(&("id".to_string(), event.id.clone())));
+    let (_, payload) = fields.iter().find(|(name, _)| name == "event").unwrap();
+    let decoded: serde_json::Value = serde_json::from_str(payload).unwrap();
+    assert_eq!(decoded["id"], event.id.as_str());
+}
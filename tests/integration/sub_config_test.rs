@@ -0,0 +1,40 @@
+//! Constructors that take a single config section, against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::AppConfig, model::SwapEventBuilder, redis::RedisPublisher, subgraph::SubgraphClient,
+};
+
+#[tokio::test]
+async fn test_publisher_from_redis_config_publishes() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.connection_pool_size = 2;
+
+    let publisher = RedisPublisher::from_redis_config(&config.redis)
+        .await
+        .unwrap();
+    assert_eq!(publisher.pool_size(), 2);
+    publisher.test_connection().await.unwrap();
+    publisher
+        .publish_batch(&[SwapEventBuilder::test_builder().unwrap()])
+        .await
+        .unwrap();
+
+    assert!(SubgraphClient::from_subgraph_config(&config.subgraph).is_ok());
+}
+
+#[tokio::test]
+async fn test_publisher_from_redis_config_rejects_bad_url() {
+    let mut config = AppConfig::default();
+    config.redis.url = "not-a-redis-url".to_string();
+
+    assert!(RedisPublisher::from_redis_config(&config.redis)
+        .await
+        .is_err());
+}
@@ -0,0 +1,43 @@
+//! Subscriber counting against a real Redis container
+#![cfg(feature = "testcontainers")]
+
+use std::time::Duration;
+use testcontainers::runners::AsyncRunner;
+use testcontainers_modules::redis::Redis;
+use uniswap_relay::{
+    config::AppConfig,
+    redis::{subscriber::RedisSubscriber, RedisPublisher},
+    telemetry::MetricsCollector,
+};
+
+#[tokio::test]
+async fn test_subscriber_count_tracks_second_connection() {
+    let container = Redis::default().start().await.unwrap();
+    let port = container.get_host_port_ipv4(6379).await.unwrap();
+
+    let mut config = AppConfig::default();
+    config.redis.url = format!("redis://127.0.0.1:{}", port);
+    config.redis.channel = "subscriber-count-test".to_string();
+    config.monitoring.enable_metrics = true;
+    config.monitoring.metrics_interval_seconds = 1;
+
+    let publisher = RedisPublisher::new(config.clone()).await.unwrap();
+    assert_eq!(publisher.get_subscriber_count().await.unwrap(), 0);
+
+    let _subscriber = RedisSubscriber::connect(&config.redis.url, &config.redis.channel)
+        .await
+        .unwrap();
+    assert_eq!(publisher.get_subscriber_count().await.unwrap(), 1);
+
+    // The gauge picks the count up on its next tick
+    let metrics_collector = MetricsCollector::new(config);
+    let gauge = publisher
+        .start_subscriber_gauge(metrics_collector.clone())
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(metrics_collector.get_metrics().redis_subscribers, 1);
+    assert!(metrics_collector
+        .render_prometheus()
+        .contains("uniswap_relay_redis_subscribers 1\n"));
+    gauge.abort();
+}
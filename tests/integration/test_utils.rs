@@ -101,7 +101,7 @@ impl TestDataGenerator {
             .transaction_hash("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string())
             .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
             .token_in(TokenInfo {
-                address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                 symbol: "USDC".to_string(),
                 name: "USD Coin".to_string(),
                 decimals: 6,
@@ -132,7 +132,7 @@ impl TestDataGenerator {
             .transaction_hash("0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890".to_string())
             .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
             .token_in(TokenInfo {
-                address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                 symbol: "USDC".to_string(),
                 name: "USD Coin".to_string(),
                 decimals: 6,
@@ -174,7 +174,7 @@ impl TestDataGenerator {
             .transaction_hash("".to_string()) // Invalid: empty transaction hash
             .pool_address("invalid_address".to_string()) // Invalid: not 0x prefixed
             .token_in(TokenInfo {
-                address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+                address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
                 symbol: "USDC".to_string(),
                 name: "USD Coin".to_string(),
                 decimals: 6,
@@ -204,13 +204,13 @@ pub struct TestComponentFactory;
 
 impl TestComponentFactory {
     /// Create a Redis publisher for testing
-    pub fn redis_publisher(config: &AppConfig) -> Result<RedisPublisher> {
-        RedisPublisher::new(&config.redis)
+    pub async fn redis_publisher(config: &AppConfig) -> Result<RedisPublisher> {
+        RedisPublisher::from_redis_config(&config.redis).await
     }
 
     /// Create a subgraph client for testing
     pub fn subgraph_client(config: &AppConfig) -> Result<SubgraphClient> {
-        SubgraphClient::new(&config.subgraph)
+        SubgraphClient::from_subgraph_config(&config.subgraph)
     }
 
     /// Create a metrics collector for testing
@@ -219,17 +219,12 @@ impl TestComponentFactory {
     }
 
     /// Create a complete SwapEventCollector for testing
-    pub fn swap_collector(config: AppConfig) -> Result<SwapEventCollector> {
-        let redis_publisher = Self::redis_publisher(&config)?;
+    pub async fn swap_collector(config: AppConfig) -> Result<SwapEventCollector> {
+        let redis_publisher = Self::redis_publisher(&config).await?;
         let subgraph_client = Self::subgraph_client(&config)?;
         let metrics_collector = Self::metrics_collector(&config);
 
-        Ok(SwapEventCollector::new(
-            config,
-            subgraph_client,
-            redis_publisher,
-            metrics_collector,
-        ))
+        SwapEventCollector::new(config, subgraph_client, redis_publisher, metrics_collector)
     }
 }
 
@@ -300,12 +295,7 @@ impl TestCleanup {
     /// Clean up test data
     pub async fn cleanup_test_data(redis_publisher: &RedisPublisher) -> Result<()> {
         // Clear test channel
-        let _: Result<()> = redis_publisher
-            .redis_client
-            .del("test_swaps")
-            .await
-            .map_err(|e| uniswap_relay::error::DAppError::Redis(e.into()));
-        Ok(())
+        redis_publisher.delete_key("test_swaps").await
     }
 }
 
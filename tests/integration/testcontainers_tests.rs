@@ -77,7 +77,7 @@ async fn test_redis_integration() -> Result<()> {
     info!("Testing Redis integration with URL: {}", test_config.redis_url);
     
     // Test Redis publisher creation
-    let redis_publisher = RedisPublisher::new(&test_config.config.redis)?;
+    let redis_publisher = RedisPublisher::from_redis_config(&test_config.config.redis).await?;
     
     // Test connection
     redis_publisher.test_connection().await?;
@@ -111,8 +111,8 @@ async fn test_swap_collector_integration() -> Result<()> {
     info!("Testing SwapEventCollector integration");
     
     // Create components
-    let redis_publisher = RedisPublisher::new(&test_config.config.redis)?;
-    let subgraph_client = SubgraphClient::new(&test_config.config.subgraph)?;
+    let redis_publisher = RedisPublisher::from_redis_config(&test_config.config.redis).await?;
+    let subgraph_client = SubgraphClient::from_subgraph_config(&test_config.config.subgraph)?;
     let metrics_collector = MetricsCollector::new(&test_config.config.monitoring);
     
     let mut collector = SwapEventCollector::new(
@@ -120,7 +120,7 @@ async fn test_swap_collector_integration() -> Result<()> {
         subgraph_client,
         redis_publisher,
         metrics_collector,
-    );
+    )?;
     
     // Test collector initialization
     info!("Testing collector initialization");
@@ -195,7 +195,7 @@ async fn test_error_handling_integration() -> Result<()> {
     let mut invalid_config = test_config.config.clone();
     invalid_config.redis.url = "redis://invalid:6379".to_string();
     
-    let redis_result = RedisPublisher::new(&invalid_config.redis);
+    let redis_result = RedisPublisher::from_redis_config(&invalid_config.redis).await;
     assert!(redis_result.is_err());
     info!("Invalid Redis URL error handling test passed");
     
@@ -203,7 +203,7 @@ async fn test_error_handling_integration() -> Result<()> {
     let mut invalid_subgraph_config = test_config.config.clone();
     invalid_subgraph_config.subgraph.uniswap_v2_url = "http://invalid-url".to_string();
     
-    let subgraph_result = SubgraphClient::new(&invalid_subgraph_config.subgraph);
+    let subgraph_result = SubgraphClient::from_subgraph_config(&invalid_subgraph_config.subgraph);
     assert!(subgraph_result.is_err());
     info!("Invalid subgraph URL error handling test passed");
     
@@ -247,8 +247,8 @@ async fn test_end_to_end_event_flow() -> Result<()> {
     info!("Testing end-to-end event flow");
     
     // Create all components
-    let redis_publisher = RedisPublisher::new(&test_config.config.redis)?;
-    let subgraph_client = SubgraphClient::new(&test_config.config.subgraph)?;
+    let redis_publisher = RedisPublisher::from_redis_config(&test_config.config.redis).await?;
+    let subgraph_client = SubgraphClient::from_subgraph_config(&test_config.config.subgraph)?;
     let metrics_collector = MetricsCollector::new(&test_config.config.monitoring);
     
     let mut collector = SwapEventCollector::new(
@@ -256,7 +256,7 @@ async fn test_end_to_end_event_flow() -> Result<()> {
         subgraph_client,
         redis_publisher,
         metrics_collector,
-    );
+    )?;
     
     // Test the complete flow
     info!("Testing complete event flow");
@@ -286,10 +286,13 @@ async fn test_end_to_end_event_flow() -> Result<()> {
     info!("Event serialization/deserialization test passed");
     
     // 4. Test metrics recording
-    let initial_metrics = collector.metrics_collector.get_metrics();
-    collector.metrics_collector.record_events_processed(1);
-    let updated_metrics = collector.metrics_collector.get_metrics();
-    assert_eq!(updated_metrics.events_processed, initial_metrics.events_processed + 1);
+    let initial_metrics = collector.metrics().get_metrics();
+    collector.metrics().record_events_processed(1);
+    let updated_metrics = collector.metrics().get_metrics();
+    assert_eq!(
+        updated_metrics.events_processed_total,
+        initial_metrics.events_processed_total + 1
+    );
     info!("Metrics recording test passed");
     
     // Cleanup
@@ -304,7 +307,7 @@ fn create_test_swap_event() -> SwapEvent {
         .transaction_hash("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string())
         .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
         .token_in(TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,
@@ -336,7 +339,7 @@ async fn test_concurrent_operations() -> Result<()> {
     
     info!("Testing concurrent operations");
     
-    let redis_publisher = RedisPublisher::new(&test_config.config.redis)?;
+    let redis_publisher = RedisPublisher::from_redis_config(&test_config.config.redis).await?;
     
     // Test concurrent publishing
     let handles: Vec<_> = (0..10)
@@ -370,7 +373,7 @@ async fn test_redis_connection_resilience() -> Result<()> {
     
     info!("Testing Redis connection resilience");
     
-    let redis_publisher = RedisPublisher::new(&test_config.config.redis)?;
+    let redis_publisher = RedisPublisher::from_redis_config(&test_config.config.redis).await?;
     
     // Test multiple connection attempts
     for i in 0..5 {
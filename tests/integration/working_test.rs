@@ -1,5 +1,5 @@
 use uniswap_relay::{
-    config::AppConfig,
+    config::{AppConfig, Environment, RedisOutputMode},
     error::Result,
     model::{SwapEvent, SwapEventBuilder, TokenInfo, UniswapVersion},
 };
@@ -14,7 +14,7 @@ fn test_swap_event_builder() -> Result<()> {
         )
         .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
         .token_in(TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,
@@ -74,6 +74,69 @@ fn test_environment_detection() {
     assert!(config.is_development());
 }
 
+/// Test parsing each environment name, and rejecting unknown ones
+#[test]
+fn test_environment_parsing() {
+    let cases = [
+        ("development", Environment::Development),
+        ("test", Environment::Test),
+        ("Staging", Environment::Staging),
+        ("PRODUCTION", Environment::Production),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(name.parse::<Environment>(), Ok(expected));
+        assert_eq!(expected.to_string(), name.to_lowercase());
+    }
+
+    let err = "prod".parse::<Environment>().unwrap_err();
+    assert!(err.contains("Unknown environment 'prod'"));
+
+    let mut config = AppConfig::default();
+    config.application.environment = Environment::Staging;
+    assert!(!config.is_production());
+    assert!(!config.is_development());
+}
+
+/// Test parsing the Redis output mode table
+#[test]
+fn test_redis_output_mode_parsing() {
+    let parse = |text: &str| toml::from_str::<RedisOutputMode>(text).unwrap();
+
+    assert_eq!(parse("type = \"pub_sub\""), RedisOutputMode::PubSub);
+    assert_eq!(
+        parse("type = \"stream\""),
+        RedisOutputMode::Stream { maxlen: None }
+    );
+    assert_eq!(
+        parse("type = \"stream\"\nmaxlen = 100000"),
+        RedisOutputMode::Stream {
+            maxlen: Some(100_000)
+        }
+    );
+    assert_eq!(
+        AppConfig::default().redis.output_mode,
+        RedisOutputMode::PubSub
+    );
+}
+
+/// Test the insecure subgraph override per environment
+#[test]
+fn test_insecure_subgraph_policy() {
+    let mut config = AppConfig::default();
+    config.subgraph.uniswap_v2_url = "http://localhost:8000/subgraphs/name/uniswap-v2".to_string();
+
+    config.application.environment = Environment::Staging;
+    assert!(config.check_network_config().is_err());
+    config.subgraph.allow_insecure_subgraph = true;
+    assert!(config.check_network_config().is_ok());
+
+    config.application.environment = Environment::Production;
+    config.subgraph.allow_insecure_subgraph = false;
+    config.subgraph.uniswap_v2_url =
+        "http://gateway.internal/subgraphs/name/uniswap-v2".to_string();
+    assert!(config.check_network_config().is_err());
+}
+
 /// Test error types
 #[test]
 fn test_error_types() {
@@ -112,7 +175,7 @@ fn test_swap_event_serialization() -> Result<()> {
         )
         .pool_address("0x8ad599c3A0ff1De082011EFDDc58f1908eb6e6D8".to_string())
         .token_in(TokenInfo {
-            address: "0xA0b86a33E6441b8c4C3B1b1ef4F2faD6244b51a".to_string(),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
             symbol: "USDC".to_string(),
             name: "USD Coin".to_string(),
             decimals: 6,